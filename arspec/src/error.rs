@@ -50,6 +50,23 @@ impl<'a> Error for spec::VerificationError {
             &spec::VerificationError::RecordNameDuplicate { .. } => "VE09",
             &spec::VerificationError::ServiceNameDuplicate { .. } => "VE10",
             &spec::VerificationError::SystemNameDuplicate { .. } => "VE11",
+            &spec::VerificationError::NoSuchDefinitionToUse { .. } => "VE12",
+            &spec::VerificationError::NoSuchRecordToExtend { .. } => "VE13",
+            &spec::VerificationError::RecordBaseCycle { .. } => "VE14",
+            &spec::VerificationError::RecordEntryDuplicate { .. } => "VE15",
+            &spec::VerificationError::TypeParameterCountMismatch { .. } => "VE16",
+            &spec::VerificationError::RecordEntryDefaultInvalid { .. } => "VE17",
+            &spec::VerificationError::EnumVariantValueDuplicate { .. } => "VE18",
+            &spec::VerificationError::EnumVariantValueMismatch { .. } => "VE19",
+            &spec::VerificationError::AliasConstraintInvalid { .. } => "VE20",
+            &spec::VerificationError::AliasCycle { .. } => "VE21",
+            &spec::VerificationError::AliasNameDuplicate { .. } => "VE22",
+            &spec::VerificationError::MethodErrorDuplicate { .. } => "VE23",
+            &spec::VerificationError::ImplementErrorInvalid { .. } => "VE24",
+            &spec::VerificationError::ImplementErrorNotMapped { .. } => "VE25",
+            &spec::VerificationError::DefinitionAmbiguous { .. } => "VE26",
+            &spec::VerificationError::NoSuchType { .. } => "VE27",
+            &spec::VerificationError::RecordEntryXmlInvalid { .. } => "VE28",
         }
     }
 }
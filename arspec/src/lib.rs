@@ -1,4 +1,5 @@
 pub mod meta;
+pub mod payload;
 pub mod project;
 pub mod spec;
 
@@ -0,0 +1,373 @@
+//! CBOR payload encoding and decoding, as described by RFC 8949.
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+use super::{Data, Error};
+
+/// Decodes CBOR `input` into [`Data`][dat].
+///
+/// Byte strings are not supported, as they have no `Data` counterpart. Tags
+/// are ignored, except for those of bignums. Map keys that are not strings
+/// are converted into strings.
+///
+/// [dat]: ../enum.Data.html
+pub fn decode(input: &[u8]) -> Result<Data, Error> {
+    let mut decoder = Decoder { input, offset: 0 };
+    let data = decoder.item()?;
+    if decoder.offset < input.len() {
+        return Err(decoder.error("unexpected trailing bytes"));
+    }
+    Ok(data)
+}
+
+/// Encodes `data` as CBOR.
+///
+/// Integers outside the 64-bit range are encoded as bignums, while floats
+/// are encoded with single precision, unless that would lose information.
+pub fn encode(data: &Data) -> Vec<u8> {
+    let mut output = Vec::new();
+    encode_inner(data, &mut output);
+    return output;
+
+    fn encode_inner(data: &Data, output: &mut Vec<u8>) {
+        match *data {
+            Data::Null => output.push(0xf6),
+            Data::Boolean(boolean) => output.push(if boolean { 0xf5 } else { 0xf4 }),
+            Data::Integer(integer) => {
+                let (major, magnitude) = if integer < 0 {
+                    (1, (-1 - integer) as u128)
+                } else {
+                    (0, integer as u128)
+                };
+                match u64::try_from(magnitude) {
+                    Ok(magnitude) => push_head(major, magnitude, output),
+                    Err(_) => {
+                        let bytes = magnitude.to_be_bytes();
+                        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+                        push_head(6, 2 + major as u64, output);
+                        push_head(2, (bytes.len() - skip) as u64, output);
+                        output.extend_from_slice(&bytes[skip..]);
+                    }
+                }
+            }
+            Data::Float(float) => {
+                let single = float as f32;
+                if f64::from(single) == float || float.is_nan() {
+                    output.push(0xfa);
+                    output.extend_from_slice(&single.to_be_bytes());
+                } else {
+                    output.push(0xfb);
+                    output.extend_from_slice(&float.to_be_bytes());
+                }
+            }
+            Data::String(ref string) => {
+                push_head(3, string.len() as u64, output);
+                output.extend_from_slice(string.as_bytes());
+            }
+            Data::List(ref items) => {
+                push_head(4, items.len() as u64, output);
+                for item in items {
+                    encode_inner(item, output);
+                }
+            }
+            Data::Map(ref entries) => {
+                push_head(5, entries.len() as u64, output);
+                for (name, value) in entries {
+                    push_head(3, name.len() as u64, output);
+                    output.extend_from_slice(name.as_bytes());
+                    encode_inner(value, output);
+                }
+            }
+        }
+    }
+
+    fn push_head(major: u8, argument: u64, output: &mut Vec<u8>) {
+        let major = major << 5;
+        if argument < 24 {
+            output.push(major | argument as u8);
+        } else if argument <= u8::MAX.into() {
+            output.extend_from_slice(&[major | 24, argument as u8]);
+        } else if argument <= u16::MAX.into() {
+            output.push(major | 25);
+            output.extend_from_slice(&(argument as u16).to_be_bytes());
+        } else if argument <= u32::MAX.into() {
+            output.push(major | 26);
+            output.extend_from_slice(&(argument as u32).to_be_bytes());
+        } else {
+            output.push(major | 27);
+            output.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+/// Renders `data` in CBOR diagnostic notation, as described by RFC 8949
+/// section 8.
+pub fn diagnostic(data: &Data) -> String {
+    let mut output = String::new();
+    diagnostic_inner(data, &mut output);
+    return output;
+
+    fn diagnostic_inner(data: &Data, output: &mut String) {
+        match *data {
+            Data::Null => output.push_str("null"),
+            Data::Boolean(boolean) => output.push_str(if boolean { "true" } else { "false" }),
+            Data::Integer(integer) => { let _ = write!(output, "{}", integer); }
+            Data::Float(float) => {
+                if float.is_nan() {
+                    output.push_str("NaN");
+                } else if float.is_infinite() {
+                    output.push_str(if float > 0.0 { "Infinity" } else { "-Infinity" });
+                } else if float.fract() == 0.0 && float.abs() < 1e15 {
+                    let _ = write!(output, "{:.1}", float);
+                } else {
+                    let _ = write!(output, "{}", float);
+                }
+            }
+            Data::String(ref string) => push_string(string, output),
+            Data::List(ref items) => {
+                output.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(", ");
+                    }
+                    diagnostic_inner(item, output);
+                }
+                output.push(']');
+            }
+            Data::Map(ref entries) => {
+                output.push('{');
+                for (index, (name, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(", ");
+                    }
+                    push_string(name, output);
+                    output.push_str(": ");
+                    diagnostic_inner(value, output);
+                }
+                output.push('}');
+            }
+        }
+    }
+
+    fn push_string(string: &str, output: &mut String) {
+        output.push('"');
+        for ch in string.chars() {
+            match ch {
+                '"' => output.push_str("\\\""),
+                '\\' => output.push_str("\\\\"),
+                ch if (ch as u32) < 0x20 => { let _ = write!(output, "\\u{:04x}", ch as u32); }
+                ch => output.push(ch),
+            }
+        }
+        output.push('"');
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &'static str) -> Error {
+        Error::CborInvalid { offset: self.offset, message }
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.input.get(self.offset..self.offset.saturating_add(length))
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, length: usize) -> Result<u64, Error> {
+        Ok(self.bytes(length)?.iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+    }
+
+    /// Reads initial byte and argument of next item.
+    ///
+    /// The argument is `None` if the item has indefinite length.
+    fn head(&mut self) -> Result<(u8, u8, Option<u64>), Error> {
+        let initial = self.bytes(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let argument = match info {
+            0..=23 => Some(u64::from(info)),
+            24 => Some(self.uint(1)?),
+            25 => Some(self.uint(2)?),
+            26 => Some(self.uint(4)?),
+            27 => Some(self.uint(8)?),
+            31 if major >= 2 && major != 6 => None,
+            _ => return Err(self.error("invalid additional information")),
+        };
+        Ok((major, info, argument))
+    }
+
+    fn is_break(&mut self) -> Result<bool, Error> {
+        match self.input.get(self.offset) {
+            Some(0xff) => {
+                self.offset += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn item(&mut self) -> Result<Data, Error> {
+        let start = self.offset;
+        let (major, info, argument) = self.head()?;
+        Ok(match (major, argument) {
+            (0, Some(argument)) => Data::Integer(argument.into()),
+            (1, Some(argument)) => Data::Integer(-1 - i128::from(argument)),
+            (2, _) => {
+                self.offset = start;
+                return Err(self.error("byte strings are not supported"));
+            }
+            (3, _) => Data::String(self.text(argument)?),
+            (4, _) => {
+                let mut items = Vec::new();
+                match argument {
+                    Some(length) => for _ in 0..length {
+                        items.push(self.item()?);
+                    },
+                    None => while !self.is_break()? {
+                        items.push(self.item()?);
+                    },
+                }
+                Data::List(items)
+            }
+            (5, _) => {
+                let mut entries = Vec::new();
+                match argument {
+                    Some(length) => for _ in 0..length {
+                        entries.push(self.entry()?);
+                    },
+                    None => while !self.is_break()? {
+                        entries.push(self.entry()?);
+                    },
+                }
+                Data::Map(entries)
+            }
+            (6, Some(tag @ 2)) | (6, Some(tag @ 3)) => {
+                let (major, _, length) = self.head()?;
+                let length = match (major, length) {
+                    (2, Some(length)) if length <= 16 => length as usize,
+                    _ => return Err(self.error("unsupported bignum")),
+                };
+                let magnitude = self.bytes(length)?.iter()
+                    .fold(0u128, |value, byte| (value << 8) | u128::from(*byte));
+                let magnitude = i128::try_from(magnitude)
+                    .map_err(|_| self.error("unsupported bignum"))?;
+                Data::Integer(if tag == 2 { magnitude } else { -1 - magnitude })
+            }
+            (6, Some(_)) => self.item()?,
+            (7, Some(argument)) => match info {
+                20 => Data::Boolean(false),
+                21 => Data::Boolean(true),
+                22 | 23 => Data::Null,
+                25 => Data::Float(half_to_f64(argument as u16)),
+                26 => Data::Float(f32::from_bits(argument as u32).into()),
+                27 => Data::Float(f64::from_bits(argument)),
+                _ => {
+                    self.offset = start;
+                    return Err(self.error("unsupported simple value"));
+                }
+            },
+            _ => {
+                self.offset = start;
+                return Err(self.error("invalid item"));
+            }
+        })
+    }
+
+    fn text(&mut self, length: Option<u64>) -> Result<String, Error> {
+        let bytes = match length {
+            Some(length) => {
+                let length = usize::try_from(length)
+                    .map_err(|_| self.error("string too long"))?;
+                self.bytes(length)?.to_vec()
+            }
+            None => {
+                let mut bytes = Vec::new();
+                while !self.is_break()? {
+                    match self.head()? {
+                        (3, _, Some(length)) => bytes.extend_from_slice(self.bytes(length as usize)?),
+                        _ => return Err(self.error("invalid indefinite-length string chunk")),
+                    }
+                }
+                bytes
+            }
+        };
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    fn entry(&mut self) -> Result<(String, Data), Error> {
+        let key = match self.item()? {
+            Data::String(key) => key,
+            Data::Integer(key) => key.to_string(),
+            Data::Boolean(key) => key.to_string(),
+            _ => return Err(self.error("unsupported map key")),
+        };
+        Ok((key, self.item()?))
+    }
+}
+
+/// Converts IEEE 754 half-precision float `bits` into a double.
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let fraction = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 => if fraction == 0.0 { f64::INFINITY } else { f64::NAN },
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// Renders `bytes` as lowercase hexadecimal digits.
+pub fn hex(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(output, "{:02x}", byte);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_and_diagnose() {
+        let data = Data::Map(vec![
+            ("Port".into(), Data::Integer(8080)),
+            ("Offset".into(), Data::Integer(-500)),
+            ("Interfaces".into(), Data::List(vec![Data::String("JSON".into())])),
+            ("Ratio".into(), Data::Float(1.5)),
+            ("Secure".into(), Data::Boolean(true)),
+            ("Next".into(), Data::Null),
+        ]);
+        assert_eq!(hex(&encode(&data)), concat!(
+            "a6",
+            "64506f7274191f90",
+            "664f66667365743901f3",
+            "6a496e74657266616365738164", "4a534f4e",
+            "65526174696ffa3fc00000",
+            "66536563757265f5",
+            "644e657874f6",
+        ));
+        assert_eq!(
+            diagnostic(&data),
+            r#"{"Port": 8080, "Offset": -500, "Interfaces": ["JSON"], "Ratio": 1.5, "Secure": true, "Next": null}"#,
+        );
+        assert_eq!(hex(&encode(&Data::Integer(u64::MAX as i128 + 1))), "c249010000000000000000");
+
+        assert_eq!(decode(&encode(&data)).unwrap(), data);
+        assert_eq!(decode(&[0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(), Data::Integer(1 << 64));
+        assert_eq!(decode(&[0x9f, 0x01, 0xf9, 0x3c, 0x00, 0xff]).unwrap(), Data::List(vec![
+            Data::Integer(1),
+            Data::Float(1.0),
+        ]));
+        assert!(decode(&[0x82, 0x01]).is_err());
+    }
+}
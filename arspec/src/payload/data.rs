@@ -0,0 +1,100 @@
+use crate::spec::Value;
+
+/// A dynamically typed service payload.
+///
+/// Payloads are independent of any particular encoding, such as JSON, and
+/// can be checked against the types of a [`Specification`][spc] using
+/// [`validate()`][val].
+///
+/// [spc]: ../spec/struct.Specification.html
+/// [val]: fn.validate.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data {
+    /// The absence of a meaningful value.
+    Null,
+
+    /// A boolean.
+    Boolean(bool),
+
+    /// An integer, large enough to hold any signed or unsigned 64-bit
+    /// integer.
+    Integer(i128),
+
+    /// A floating-point number.
+    Float(f64),
+
+    /// A UTF-8 string.
+    String(String),
+
+    /// A list of values.
+    List(Vec<Data>),
+
+    /// A map of values, which is really an ordered list of name/value pairs.
+    Map(Vec<(String, Data)>),
+}
+
+impl Data {
+    /// Looks up named entry, if this is a `Data::Map`.
+    pub fn get(&self, key: &str) -> Option<&Data> {
+        match *self {
+            Data::Map(ref entries) => entries.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, data)| data),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name of the kind of this value, such as `"string"`.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Data::Null => "null",
+            Data::Boolean(_) => "boolean",
+            Data::Integer(_) => "integer",
+            Data::Float(_) => "float",
+            Data::String(_) => "string",
+            Data::List(_) => "list",
+            Data::Map(_) => "map",
+        }
+    }
+}
+
+impl<'a, 'b> From<&'b Value<'a>> for Data {
+    /// Converts specification `value`, such as the value of an attribute,
+    /// into a payload.
+    fn from(value: &'b Value<'a>) -> Self {
+        match *value {
+            Value::Null => Data::Null,
+            Value::Boolean(ref span) => Data::Boolean(span.as_str() == "true"),
+            Value::Integer(ref span) => parse_integer(span.as_str())
+                .map_or(Data::Null, Data::Integer),
+            Value::Float(ref span) => Data::Float(match span.as_str() {
+                "inf" | "+inf" => f64::INFINITY,
+                "-inf" => f64::NEG_INFINITY,
+                float => float.parse().unwrap_or(f64::NAN),
+            }),
+            Value::String(_) => Data::String(value.as_string().unwrap_or_default()),
+            Value::List(ref items) => Data::List(items.iter().map(Data::from).collect()),
+            Value::Map(ref entries) => Data::Map(entries.iter()
+                .map(|(name, value)| (name.as_str().to_string(), value.into()))
+                .collect()),
+        }
+    }
+}
+
+/// Parses integer literal, which may have a sign as well as a `0b`, `0c` or
+/// `0x` radix prefix.
+fn parse_integer(literal: &str) -> Option<i128> {
+    let (is_negative, literal) = match literal.as_bytes().first() {
+        Some(b'-') => (true, &literal[1..]),
+        Some(b'+') => (false, &literal[1..]),
+        _ => (false, literal),
+    };
+    let (radix, digits) = match literal.get(..2) {
+        Some("0b") => (2, &literal[2..]),
+        Some("0c") => (8, &literal[2..]),
+        Some("0x") => (16, &literal[2..]),
+        _ => (10, literal),
+    };
+    let integer = i128::from_str_radix(digits, radix).ok()?;
+    Some(if is_negative { -integer } else { integer })
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Describes a payload decoding error.
+#[derive(Debug)]
+pub enum Error {
+    /// CBOR input could not be decoded.
+    CborInvalid {
+        offset: usize,
+        message: &'static str,
+    },
+
+    /// JSON input could not be decoded.
+    JsonInvalid {
+        offset: usize,
+        message: &'static str,
+    },
+
+    /// XML input could not be decoded.
+    XmlInvalid {
+        offset: usize,
+        message: &'static str,
+    },
+}
+
+impl crate::Error for Error {
+    fn code(&self) -> &'static str {
+        match *self {
+            Error::CborInvalid { .. } => "PL02",
+            Error::JsonInvalid { .. } => "PL01",
+            Error::XmlInvalid { .. } => "PL03",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::CborInvalid { offset, message } => {
+                write!(f, "Invalid CBOR at byte offset {}: {}", offset, message)
+            }
+            Error::JsonInvalid { offset, message } => {
+                write!(f, "Invalid JSON at byte offset {}: {}", offset, message)
+            }
+            Error::XmlInvalid { offset, message } => {
+                write!(f, "Invalid XML at byte offset {}: {}", offset, message)
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::spec::{Constraint, Record, Specification, TypeRef};
+use std::ptr;
+use super::{attribute, Data, Kind};
+
+/// Generates example payload of the type referred to by `type_ref`.
+///
+/// Examples are deterministic. Records become maps with one entry per
+/// field, enums become the values or names of their first variants, while
+/// lists, sets and maps get exactly one item each. `U16` values are
+/// port-like numbers, while strings are named after the fields containing
+/// them. Records, aliases and record fields with `@Example(...)` attributes
+/// use the attribute values instead, as do record fields with default
+/// values. Examples of aliases are adjusted to satisfy their range and length
+/// constraints, but not their patterns. Any record that would contain itself
+/// is replaced by `Data::Null`, as are payloads of unknown types.
+pub fn example<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> Data {
+    example_inner(spec, type_ref, None, &mut Vec::new())
+}
+
+/// Generates example payload of given `record`.
+///
+/// See [`example()`][exa] for details. Any generic parameters of `record`
+/// are treated as types of unknown structure.
+///
+/// [exa]: fn.example.html
+pub fn record_example<'a>(spec: &Specification<'a>, record: &Record<'a>) -> Data {
+    example_record(spec, record, &TypeRef::new(record.name.clone()), &mut Vec::new())
+}
+
+fn example_inner<'a>(
+    spec: &Specification<'a>,
+    type_ref: &TypeRef<'a>,
+    field: Option<&str>,
+    records: &mut Vec<*const Record<'a>>,
+) -> Data {
+    let (target, aliases) = spec.unalias(type_ref);
+    if !aliases.is_empty() {
+        if let Some(example) = aliases.iter().find_map(|alias| attribute(&alias.attributes, "Example")) {
+            return example.into();
+        }
+        let example = example_inner(spec, target, field, records);
+        return aliases.iter()
+            .rev()
+            .flat_map(|alias| alias.constraints.iter())
+            .fold(example, |example, constraint| satisfy(constraint, example));
+    }
+    match Kind::of(spec, type_ref) {
+        Kind::Boolean => Data::Boolean(true),
+        Kind::Integer { min, max } => {
+            let integer = if min < 0 {
+                -42
+            } else if max == u16::MAX.into() {
+                8080
+            } else {
+                42
+            };
+            Data::Integer(integer.max(min).min(max))
+        }
+        Kind::Float => Data::Float(1.5),
+        Kind::String => Data::String(field.unwrap_or("string").into()),
+        Kind::List(item) | Kind::Set(item) => {
+            Data::List(vec![example_inner(spec, item, field, records)])
+        }
+        Kind::Map { key, value } => {
+            let key = match example_inner(spec, key, Some("key"), records) {
+                Data::String(key) => key,
+                Data::Integer(key) => key.to_string(),
+                _ => "key".into(),
+            };
+            Data::Map(vec![(key, example_inner(spec, value, field, records))])
+        }
+        Kind::Option(inner) => example_inner(spec, inner, field, records),
+        Kind::Enum(enum_) => enum_.variants.first()
+            .map_or(Data::Null, |variant| variant.wire_value()),
+        Kind::Record(record, instance) => example_record(spec, record, instance, records),
+        Kind::Unknown => Data::Null,
+    }
+}
+
+fn example_record<'a>(
+    spec: &Specification<'a>,
+    record: &Record<'a>,
+    instance: &TypeRef<'a>,
+    records: &mut Vec<*const Record<'a>>,
+) -> Data {
+    if let Some(value) = attribute(&record.attributes, "Example") {
+        return value.into();
+    }
+    if records.iter().any(|other| ptr::eq(*other, record)) {
+        return Data::Null;
+    }
+    records.push(record);
+    let entries = record.entries_of(instance).iter()
+        .map(|entry| {
+            let name = entry.name.as_str();
+            let data = match attribute(&entry.attributes, "Example").or(entry.default.as_ref()) {
+                Some(value) => value.into(),
+                None => example_inner(spec, &entry.type_ref, Some(name), records),
+            };
+            (name.to_string(), data)
+        })
+        .collect();
+    records.pop();
+    Data::Map(entries)
+}
+
+/// Adjusts `example` to satisfy `constraint`, unless it already does or the
+/// constraint is a pattern.
+fn satisfy(constraint: &Constraint, example: Data) -> Data {
+    if constraint.admits(&example) {
+        return example;
+    }
+    match (constraint, example) {
+        (Constraint::Range { start, end, is_inclusive, .. }, example) => {
+            let bound = match (start, end) {
+                (Some(start), _) => Data::from(start),
+                (None, Some(end)) => match (Data::from(end), *is_inclusive) {
+                    (Data::Integer(end), false) => Data::Integer(end - 1),
+                    (Data::Float(end), false) => Data::Float(end - 1.0),
+                    (end, _) => end,
+                },
+                (None, None) => return example,
+            };
+            match (example, bound) {
+                (Data::Float(_), Data::Integer(bound)) => Data::Float(bound as f64),
+                (_, bound) => bound,
+            }
+        }
+        (Constraint::LengthMin { length, .. }, Data::String(mut string)) => {
+            if let Data::Integer(length) = Data::from(length) {
+                while (string.chars().count() as i128) < length {
+                    string.push('x');
+                }
+            }
+            Data::String(string)
+        }
+        (Constraint::LengthMax { length, .. }, Data::String(string)) => match Data::from(length) {
+            Data::Integer(length) => Data::String(string.chars().take(length as usize).collect()),
+            _ => Data::String(string),
+        },
+        (_, example) => example,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use crate::spec::parser;
+    use super::*;
+
+    #[test]
+    fn record_examples() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Entry {\n",
+                    "    Address: String,\n",
+                    "    Port: U16,\n",
+                    "    @Example([\"JSON\", \"XML\"])\n",
+                    "    Interfaces: Set<String>,\n",
+                    "    Offset: I8,\n",
+                    "    Next: Option<Entry>,\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(record_example(&spec, &spec.records[0]), Data::Map(vec![
+            ("Address".into(), Data::String("Address".into())),
+            ("Port".into(), Data::Integer(8080)),
+            ("Interfaces".into(), Data::List(vec![
+                Data::String("JSON".into()),
+                Data::String("XML".into()),
+            ])),
+            ("Offset".into(), Data::Integer(-42)),
+            ("Next".into(), Data::Null),
+        ]));
+    }
+}
@@ -0,0 +1,315 @@
+//! JSON payload encoding and decoding.
+
+use std::fmt::Write;
+use super::{Data, Error};
+
+/// Decodes JSON `input` into [`Data`][dat].
+///
+/// [dat]: ../enum.Data.html
+pub fn decode(input: &str) -> Result<Data, Error> {
+    let mut decoder = Decoder { input, offset: 0 };
+    decoder.skip_whitespace();
+    let data = decoder.value()?;
+    decoder.skip_whitespace();
+    if decoder.offset < input.len() {
+        return Err(decoder.error("unexpected trailing characters"));
+    }
+    Ok(data)
+}
+
+/// Encodes `data` as indented JSON.
+pub fn encode(data: &Data) -> String {
+    let mut output = String::new();
+    encode_inner(data, 0, &mut output);
+    return output;
+
+    fn encode_inner(data: &Data, indent: usize, output: &mut String) {
+        match *data {
+            Data::Null => output.push_str("null"),
+            Data::Boolean(boolean) => output.push_str(if boolean { "true" } else { "false" }),
+            Data::Integer(integer) => { let _ = write!(output, "{}", integer); }
+            Data::Float(float) => {
+                if !float.is_finite() {
+                    output.push_str("null");
+                } else if float.fract() == 0.0 && float.abs() < 1e15 {
+                    let _ = write!(output, "{:.1}", float);
+                } else {
+                    let _ = write!(output, "{}", float);
+                }
+            }
+            Data::String(ref string) => encode_string(string, output),
+            Data::List(ref items) => {
+                if items.is_empty() {
+                    output.push_str("[]");
+                    return;
+                }
+                output.push_str("[\n");
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(",\n");
+                    }
+                    push_indent(indent + 1, output);
+                    encode_inner(item, indent + 1, output);
+                }
+                output.push('\n');
+                push_indent(indent, output);
+                output.push(']');
+            }
+            Data::Map(ref entries) => {
+                if entries.is_empty() {
+                    output.push_str("{}");
+                    return;
+                }
+                output.push_str("{\n");
+                for (index, (name, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(",\n");
+                    }
+                    push_indent(indent + 1, output);
+                    encode_string(name, output);
+                    output.push_str(": ");
+                    encode_inner(value, indent + 1, output);
+                }
+                output.push('\n');
+                push_indent(indent, output);
+                output.push('}');
+            }
+        }
+    }
+
+    fn push_indent(indent: usize, output: &mut String) {
+        for _ in 0..indent {
+            output.push_str("  ");
+        }
+    }
+
+    fn encode_string(string: &str, output: &mut String) {
+        output.push('"');
+        for ch in string.chars() {
+            match ch {
+                '"' => output.push_str("\\\""),
+                '\\' => output.push_str("\\\\"),
+                '\n' => output.push_str("\\n"),
+                '\r' => output.push_str("\\r"),
+                '\t' => output.push_str("\\t"),
+                ch if (ch as u32) < 0x20 => { let _ = write!(output, "\\u{:04x}", ch as u32); }
+                ch => output.push(ch),
+            }
+        }
+        output.push('"');
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &'static str) -> Error {
+        Error::JsonInvalid { offset: self.offset, message }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.offset += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn expect(&mut self, expected: char, message: &'static str) -> Result<(), Error> {
+        match self.peek() {
+            Some(ch) if ch == expected => {
+                self.offset += ch.len_utf8();
+                Ok(())
+            }
+            _ => Err(self.error(message)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<Data, Error> {
+        match self.peek() {
+            Some('n') => self.keyword("null", Data::Null),
+            Some('t') => self.keyword("true", Data::Boolean(true)),
+            Some('f') => self.keyword("false", Data::Boolean(false)),
+            Some('"') => self.string().map(Data::String),
+            Some('[') => self.list(),
+            Some('{') => self.map(),
+            Some('-') | Some('0'..='9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &'static str, data: Data) -> Result<Data, Error> {
+        if self.input[self.offset..].starts_with(keyword) {
+            self.offset += keyword.len();
+            Ok(data)
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn number(&mut self) -> Result<Data, Error> {
+        let start = self.offset;
+        let mut is_float = false;
+        if self.peek() == Some('-') {
+            self.offset += 1;
+        }
+        while let Some(ch) = self.peek() {
+            match ch {
+                '0'..='9' => {}
+                '.' | 'e' | 'E' | '+' | '-' => is_float = true,
+                _ => break,
+            }
+            self.offset += 1;
+        }
+        let number = &self.input[start..self.offset];
+        let data = if is_float {
+            number.parse().ok().map(Data::Float)
+        } else {
+            number.parse().ok().map(Data::Integer)
+        };
+        data.ok_or(Error::JsonInvalid { offset: start, message: "invalid number" })
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"', "expected string")?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => string.push(match self.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => self.unicode_escape()?,
+                    _ => return Err(self.error("invalid escape sequence")),
+                }),
+                Some(ch) if (ch as u32) < 0x20 => {
+                    return Err(self.error("unescaped control character in string"));
+                }
+                Some(ch) => string.push(ch),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return std::char::from_u32(high)
+                .ok_or_else(|| self.error("invalid unicode escape"));
+        }
+        if !self.input[self.offset..].starts_with("\\u") {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+        self.offset += 2;
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+        std::char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let digits = self.input.get(self.offset..self.offset + 4)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        let code = u32::from_str_radix(digits, 16)
+            .map_err(|_| self.error("invalid unicode escape"))?;
+        self.offset += 4;
+        Ok(code)
+    }
+
+    fn list(&mut self) -> Result<Data, Error> {
+        self.expect('[', "expected list")?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.offset += 1;
+            return Ok(Data::List(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Data::List(items)),
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn map(&mut self) -> Result<Data, Error> {
+        self.expect('{', "expected map")?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.offset += 1;
+            return Ok(Data::Map(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.skip_whitespace();
+            self.expect(':', "expected `:`")?;
+            self.skip_whitespace();
+            entries.push((name, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Data::Map(entries)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = concat!(
+            "{\n",
+            "  \"Name\": \"Nu\\u00f1ez \\\"\\ud83d\\ude00\\\"\",\n",
+            "  \"Port\": 8443,\n",
+            "  \"Offset\": -1.5,\n",
+            "  \"Secure\": true,\n",
+            "  \"Tags\": [],\n",
+            "  \"Metadata\": {\n",
+            "    \"a\": null\n",
+            "  }\n",
+            "}",
+        );
+        let data = decode(input).unwrap();
+        assert_eq!(data.get("Name"), Some(&Data::String("Nuñez \"😀\"".into())));
+        assert_eq!(data.get("Port"), Some(&Data::Integer(8443)));
+        assert_eq!(data.get("Offset"), Some(&Data::Float(-1.5)));
+        assert_eq!(decode(&encode(&data)).unwrap(), data);
+
+        assert!(decode("[1, 2,]").is_err());
+        assert!(decode("{\"a\": 1} x").is_err());
+        match decode("[\"a\", tru]") {
+            Err(Error::JsonInvalid { offset, .. }) => assert_eq!(offset, 6),
+            other => panic!("Expected JsonInvalid, got: {:?}", other),
+        }
+    }
+}
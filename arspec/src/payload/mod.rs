@@ -0,0 +1,141 @@
+//! Service payload management.
+//!
+//! This module contains tools useful for generating, encoding, decoding and
+//! validating the payloads exchanged by the services of a specification.
+
+pub mod cbor;
+pub mod json;
+pub mod xml;
+
+mod data;
+mod error;
+mod example;
+mod validate;
+
+pub use self::data::Data;
+pub use self::error::Error;
+pub use self::example::{example, record_example};
+pub use self::validate::{validate, Violation};
+
+use crate::spec::{Attribute, Enum, Record, Specification, TypeRef, Value};
+use std::fmt;
+
+/// A payload encoding.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Cbor,
+    Json,
+    Xml,
+}
+
+impl Encoding {
+    /// Attempts to parse `Encoding` from its name, such as `JSON`, ignoring
+    /// letter case.
+    pub fn parse(name: &str) -> Option<Encoding> {
+        match name.to_ascii_uppercase().as_str() {
+            "CBOR" => Some(Encoding::Cbor),
+            "JSON" => Some(Encoding::Json),
+            "XML" => Some(Encoding::Xml),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Encoding::Cbor => "CBOR",
+            Encoding::Json => "JSON",
+            Encoding::Xml => "XML",
+        })
+    }
+}
+
+/// Decodes `input` of the type referred to by `type_ref`, encoded using
+/// `encoding`.
+///
+/// The type is only used to guide the decoding of XML, which lacks a data
+/// model of its own. Use [`validate()`][val] to check that the decoded data
+/// actually conforms to its type.
+///
+/// [val]: fn.validate.html
+pub fn decode<'a>(
+    spec: &Specification<'a>,
+    type_ref: &TypeRef<'a>,
+    encoding: Encoding,
+    input: &[u8],
+) -> Result<Data, Error> {
+    match encoding {
+        Encoding::Cbor => cbor::decode(input),
+        Encoding::Json => json::decode(utf8(input, |offset| Error::JsonInvalid { offset, message: "invalid UTF-8" })?),
+        Encoding::Xml => xml::decode(spec, type_ref, utf8(input, |offset| Error::XmlInvalid { offset, message: "invalid UTF-8" })?),
+    }
+}
+
+/// Interprets `input` as UTF-8, or calls `f` with the offset of the first
+/// invalid byte.
+fn utf8<F>(input: &[u8], f: F) -> Result<&str, Error>
+    where F: FnOnce(usize) -> Error,
+{
+    std::str::from_utf8(input).map_err(|error| f(error.valid_up_to()))
+}
+
+/// Looks up value of first attribute with given `name`, if any.
+fn attribute<'a, 'b>(attributes: &'b [Attribute<'a>], name: &str) -> Option<&'b Value<'a>> {
+    attributes.iter()
+        .find(|attribute| attribute.name.as_str() == name)
+        .map(|attribute| &attribute.value)
+}
+
+/// A type, as far as its payloads are concerned.
+#[derive(Debug)]
+enum Kind<'a: 'b, 'b> {
+    Boolean,
+    Integer { min: i128, max: i128 },
+    Float,
+    String,
+    List(&'b TypeRef<'a>),
+    Set(&'b TypeRef<'a>),
+    Map { key: &'b TypeRef<'a>, value: &'b TypeRef<'a> },
+    Option(&'b TypeRef<'a>),
+    Enum(&'b Enum<'a>),
+    /// A record, as well as the type reference referring to it, which
+    /// determines what types any generic parameters of the record have.
+    Record(&'b Record<'a>, &'b TypeRef<'a>),
+
+    /// A type of unknown structure, which admits any payload.
+    Unknown,
+}
+
+impl<'a: 'b, 'b> Kind<'a, 'b> {
+    /// Determines the kind of type referred to by `type_ref`, which is that
+    /// of the aliased type if `type_ref` refers to an alias.
+    fn of(spec: &'b Specification<'a>, type_ref: &'b TypeRef<'a>) -> Self {
+        let (type_ref, _) = spec.unalias(type_ref);
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            return Kind::Record(record, type_ref);
+        }
+        if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+            return Kind::Enum(enum_);
+        }
+        let params = &type_ref.params;
+        match (type_ref.name.as_str(), params.len()) {
+            ("Boolean", 0) => Kind::Boolean,
+            ("Float32", 0) | ("Float64", 0) => Kind::Float,
+            ("I8", 0) => Kind::Integer { min: i8::MIN.into(), max: i8::MAX.into() },
+            ("I16", 0) => Kind::Integer { min: i16::MIN.into(), max: i16::MAX.into() },
+            ("I32", 0) => Kind::Integer { min: i32::MIN.into(), max: i32::MAX.into() },
+            ("I64", 0) => Kind::Integer { min: i64::MIN.into(), max: i64::MAX.into() },
+            ("U8", 0) => Kind::Integer { min: 0, max: u8::MAX.into() },
+            ("U16", 0) => Kind::Integer { min: 0, max: u16::MAX.into() },
+            ("U32", 0) => Kind::Integer { min: 0, max: u32::MAX.into() },
+            ("U64", 0) => Kind::Integer { min: 0, max: u64::MAX.into() },
+            ("String", 0) => Kind::String,
+            ("List", 1) => Kind::List(&params[0]),
+            ("Set", 1) => Kind::Set(&params[0]),
+            ("Map", 2) => Kind::Map { key: &params[0], value: &params[1] },
+            ("Option", 1) => Kind::Option(&params[0]),
+            _ => Kind::Unknown,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::spec::{Specification, TypeRef};
+use std::fmt;
+use super::{Data, Kind};
+
+/// Checks whether `data` is a valid payload of the type referred to by
+/// `type_ref`, returning any violations found.
+///
+/// Record fields of `Option` types may be omitted, while all other fields
+/// are required. Fields not defined by records are not allowed. Integers
+/// must be within the ranges of their types, and values of aliases must
+/// satisfy the constraints of those aliases.
+pub fn validate<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, data: &Data) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    validate_inner(spec, type_ref, data, &mut String::new(), &mut violations);
+    return violations;
+
+    fn validate_inner<'a>(
+        spec: &Specification<'a>,
+        type_ref: &TypeRef<'a>,
+        data: &Data,
+        pointer: &mut String,
+        violations: &mut Vec<Violation>,
+    ) {
+        let (target, aliases) = spec.unalias(type_ref);
+        if !aliases.is_empty() {
+            let count = violations.len();
+            validate_inner(spec, target, data, pointer, violations);
+            if violations.len() > count {
+                return;
+            }
+            for alias in aliases {
+                for constraint in alias.constraints.iter().filter(|constraint| !constraint.admits(data)) {
+                    violations.push(Violation {
+                        pointer: pointer.clone(),
+                        message: format!("{} violates constraint `{}` of alias `{}`",
+                            describe(data), constraint, alias.name.as_str()),
+                    });
+                }
+            }
+            return;
+        }
+        let expected = match (Kind::of(spec, type_ref), data) {
+            (Kind::Boolean, Data::Boolean(_)) |
+            (Kind::Float, Data::Integer(_)) |
+            (Kind::Float, Data::Float(_)) |
+            (Kind::String, Data::String(_)) |
+            (Kind::Option(_), Data::Null) |
+            (Kind::Unknown, _) => return,
+
+            (Kind::Integer { min, max }, Data::Integer(integer)) => {
+                if *integer < min || *integer > max {
+                    violations.push(Violation {
+                        pointer: pointer.clone(),
+                        message: format!("{} is out of range for `{}` ({}..={})",
+                            integer, type_ref.name.as_str(), min, max),
+                    });
+                }
+                return;
+            }
+            (Kind::List(item), Data::List(items)) |
+            (Kind::Set(item), Data::List(items)) => {
+                for (index, data) in items.iter().enumerate() {
+                    with_segment(pointer, &index.to_string(), |pointer| {
+                        validate_inner(spec, item, data, pointer, violations);
+                    });
+                }
+                return;
+            }
+            (Kind::Map { value, .. }, Data::Map(entries)) => {
+                for (name, data) in entries {
+                    with_segment(pointer, name, |pointer| {
+                        validate_inner(spec, value, data, pointer, violations);
+                    });
+                }
+                return;
+            }
+            (Kind::Option(inner), data) => {
+                validate_inner(spec, inner, data, pointer, violations);
+                return;
+            }
+            (Kind::Enum(enum_), Data::String(name)) if !enum_.is_integer() => {
+                if enum_.variant_of(data).is_none() {
+                    violations.push(Violation {
+                        pointer: pointer.clone(),
+                        message: format!("`{}` is not a variant of enum `{}`", name, enum_.name.as_str()),
+                    });
+                }
+                return;
+            }
+            (Kind::Enum(enum_), Data::Integer(value)) if enum_.is_integer() => {
+                if enum_.variant_of(data).is_none() {
+                    violations.push(Violation {
+                        pointer: pointer.clone(),
+                        message: format!("{} is not a variant value of enum `{}`", value, enum_.name.as_str()),
+                    });
+                }
+                return;
+            }
+            (Kind::Record(record, instance), Data::Map(entries)) => {
+                let fields = record.entries_of(instance);
+                for entry in fields.iter() {
+                    let name = entry.name.as_str();
+                    match entries.iter().find(|(key, _)| key == name) {
+                        Some((_, Data::Null)) if entry.may_be_null() => {}
+                        Some((_, data)) => with_segment(pointer, name, |pointer| {
+                            validate_inner(spec, &entry.type_ref, data, pointer, violations);
+                        }),
+                        None => {
+                            if entry.may_be_absent() {
+                                continue;
+                            }
+                            violations.push(Violation {
+                                pointer: pointer.clone(),
+                                message: format!("missing field `{}`", name),
+                            });
+                        }
+                    }
+                }
+                for (key, _) in entries {
+                    if !fields.iter().any(|entry| entry.name.as_str() == key) {
+                        violations.push(Violation {
+                            pointer: pointer.clone(),
+                            message: format!("unknown field `{}` in record `{}`", key, record.name.as_str()),
+                        });
+                    }
+                }
+                return;
+            }
+
+            (Kind::Boolean, _) => "boolean",
+            (Kind::Integer { .. }, _) => "integer",
+            (Kind::Float, _) => "float",
+            (Kind::String, _) => "string",
+            (Kind::List(_), _) => "list",
+            (Kind::Set(_), _) => "set",
+            (Kind::Map { .. }, _) => "map",
+            (Kind::Enum(enum_), _) if enum_.is_integer() => "enum variant value",
+            (Kind::Enum(_), _) => "enum variant name",
+            (Kind::Record(..), _) => "record",
+        };
+        violations.push(Violation {
+            pointer: pointer.clone(),
+            message: format!("expected {}, found {}", expected, data.kind()),
+        });
+    }
+
+    /// Describes scalar `data` for use in violation messages.
+    fn describe(data: &Data) -> String {
+        match data {
+            Data::Integer(integer) => integer.to_string(),
+            Data::Float(number) => number.to_string(),
+            Data::String(string) => format!("`{}`", string),
+            data => data.kind().into(),
+        }
+    }
+
+    /// Appends JSON pointer reference token `segment` to `pointer` while
+    /// calling `f`.
+    fn with_segment<F>(pointer: &mut String, segment: &str, f: F)
+        where F: FnOnce(&mut String),
+    {
+        let length = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+        f(pointer);
+        pointer.truncate(length);
+    }
+}
+
+/// A violation of the type of some payload.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Violation {
+    /// JSON pointer, as described by RFC 6901, identifying the violating
+    /// part of the payload.
+    pub pointer: String,
+
+    /// Description of violation.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "At payload root: {}", self.message)
+        } else {
+            write!(f, "At `{}`: {}", self.pointer, self.message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use crate::payload::{example, json};
+    use crate::spec::parser;
+    use super::*;
+
+    #[test]
+    fn validate_json() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Entry { Name: String, Port: U16, Tags: List<Tag>, Note: Option<String> }\n",
+                    "record Range { Min: I8, Max: U64 }\n",
+                    "record Query { Entry: Entry }\n",
+                    "enum Tag { Secure, Insecure }\n",
+                    "service S { method Get(Query); }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let input = spec.services[0].methods[0].input.as_ref().unwrap();
+
+        assert!(validate(&spec, input, &example(&spec, input)).is_empty());
+
+        let data = json::decode(r#"{
+            "Entry": { "Name": 1, "Tags": ["Secure", "Unknown"], "Extra": null }
+        }"#).unwrap();
+        let violations = validate(&spec, input, &data)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(violations, vec![
+            "At `/Entry/Name`: expected string, found integer",
+            "At `/Entry`: missing field `Port`",
+            "At `/Entry/Tags/1`: `Unknown` is not a variant of enum `Tag`",
+            "At `/Entry`: unknown field `Extra` in record `Entry`",
+        ]);
+
+        let range = TypeRef::new(spec.records[1].name.clone());
+        let data = json::decode(r#"{ "Min": -129, "Max": 18446744073709551615 }"#).unwrap();
+        let violations = validate(&spec, &range, &data)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(violations, vec!["At `/Min`: -129 is out of range for `I8` (-128..=127)"]);
+
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: "record Entry { Name?: String, Port: U16 = 8080 }\n".into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let entry = TypeRef::new(spec.records[0].name.clone());
+        assert!(validate(&spec, &entry, &json::decode("{}").unwrap()).is_empty());
+        assert!(validate(&spec, &entry, &json::decode(r#"{ "Name": null }"#).unwrap()).is_empty());
+        let violations = validate(&spec, &entry, &json::decode(r#"{ "Port": null }"#).unwrap())
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(violations, vec!["At `/Port`: expected integer, found null"]);
+
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: "enum Reason { TTL_EXPIRED = 2, LOST = 3 }\n".into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let reason = TypeRef::new(spec.enums[0].name.clone());
+        assert!(validate(&spec, &reason, &json::decode("2").unwrap()).is_empty());
+        let violations = [r#"4"#, r#""LOST""#].iter()
+            .flat_map(|json| validate(&spec, &reason, &json::decode(json).unwrap()))
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(violations, vec![
+            "At payload root: 4 is not a variant value of enum `Reason`",
+            "At payload root: expected enum variant value, found string",
+        ]);
+
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "type Port = U16 where 1..=65535;\n",
+                    "type Name = String where len <= 8, pattern \"^[A-Za-z]+$\";\n",
+                    "record Entry { Name: Name, Ports: List<Port> }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let entry = TypeRef::new(spec.records[0].name.clone());
+        assert!(validate(&spec, &entry, &example(&spec, &entry)).is_empty());
+        let data = json::decode(r#"{ "Name": "Alpha 1234", "Ports": [0, 80, 65536] }"#).unwrap();
+        let violations = validate(&spec, &entry, &data)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(violations, vec![
+            "At `/Name`: `Alpha 1234` violates constraint `len <= 8` of alias `Name`",
+            "At `/Name`: `Alpha 1234` violates constraint `pattern \"^[A-Za-z]+$\"` of alias `Name`",
+            "At `/Ports/0`: 0 violates constraint `1..=65535` of alias `Port`",
+            "At `/Ports/2`: 65536 is out of range for `U16` (0..=65535)",
+        ]);
+    }
+}
@@ -0,0 +1,657 @@
+//! XML payload encoding and decoding.
+//!
+//! As XML documents lack a canonical data model, payloads are mapped to and
+//! from XML using the types of their specifications, as follows:
+//!
+//! - Values become elements named after their record fields, or after their
+//!   types if not in records. Scalar values become element text.
+//! - Records become elements with one child element per field, in order of
+//!   definition. Fields of `Option` types are left out when null.
+//! - Record fields of `List` and `Set` types become one repeated element per
+//!   item. Lists and sets elsewhere become elements with one `item` child
+//!   element per item.
+//! - Maps become elements with one `entry` child element per entry, each of
+//!   which has a `key` attribute.
+//! - Enums become elements containing the values or names of their
+//!   variants.
+//!
+//! Record fields with the `@XmlAttribute(true)` attribute become XML
+//! attributes instead of child elements, which requires them to be of scalar
+//! or enum types. The `@XmlName("...")` attribute overrides the element or
+//! attribute name of a field, or the element name of a record at the root of
+//! a payload. No two fields of a record may have the same attribute or
+//! element name.
+//!
+//! When decoding, the name of the root element is ignored, as are the names
+//! of list, set and map items.
+
+use crate::spec::{Record, RecordEntry, Specification, TypeRef, Value, VerificationError};
+use super::{attribute, Data, Error, Kind};
+
+/// Name of XML element or attribute representing record `entry`.
+pub fn field_name(entry: &RecordEntry) -> String {
+    attribute(&entry.attributes, "XmlName")
+        .and_then(|value| value.as_string())
+        .unwrap_or_else(|| entry.name.as_str().into())
+}
+
+/// Name of XML element representing `record` at the root of a payload.
+pub fn record_name(record: &Record) -> String {
+    attribute(&record.attributes, "XmlName")
+        .and_then(|value| value.as_string())
+        .unwrap_or_else(|| record.name.as_str().into())
+}
+
+/// Whether record `entry` is represented as an XML attribute, which
+/// requires it to have the `@XmlAttribute(true)` attribute and to be of a
+/// scalar or enum type, optionally wrapped in an `Option`.
+pub fn is_attribute<'a>(spec: &Specification<'a>, entry: &RecordEntry<'a>) -> bool {
+    is_attribute_requested(entry) && is_attribute_type(spec, entry)
+}
+
+/// Whether record `entry` has the `@XmlAttribute(true)` attribute.
+fn is_attribute_requested(entry: &RecordEntry) -> bool {
+    matches!(
+        attribute(&entry.attributes, "XmlAttribute"),
+        Some(Value::Boolean(span)) if span.as_str() == "true"
+    )
+}
+
+/// Whether record `entry` is of a scalar or enum type, optionally wrapped in
+/// an `Option`.
+fn is_attribute_type<'a>(spec: &Specification<'a>, entry: &RecordEntry<'a>) -> bool {
+    let mut kind = Kind::of(spec, entry.value_type());
+    if let Kind::Option(inner) = kind {
+        kind = Kind::of(spec, inner);
+    }
+    matches!(kind, Kind::Boolean | Kind::Integer { .. } | Kind::Float | Kind::String | Kind::Enum(_))
+}
+
+/// Asserts that every entry of `record` with the `@XmlAttribute(true)`
+/// attribute can be represented as an XML attribute, and that no two of its
+/// entries have the same XML attribute or element name.
+pub(crate) fn verify_record<'a>(spec: &Specification<'a>, record: &Record<'a>) -> Result<(), VerificationError> {
+    let invalid = |entry: &RecordEntry, reason: String| VerificationError::RecordEntryXmlInvalid {
+        entry: entry.name.to_excerpt(),
+        reason,
+    };
+    let mut attributes: Vec<(String, &RecordEntry)> = Vec::new();
+    let mut elements: Vec<(String, &RecordEntry)> = Vec::new();
+    for entry in &record.entries {
+        let (names, kind) = if is_attribute_requested(entry) {
+            if !is_attribute_type(spec, entry) {
+                return Err(invalid(entry, "only entries of scalar or enum types may be XML attributes".into()));
+            }
+            (&mut attributes, "attribute")
+        } else {
+            (&mut elements, "element")
+        };
+        let name = field_name(entry);
+        if let Some((_, original)) = names.iter().find(|(other, _)| *other == name) {
+            return Err(invalid(entry, format!(
+                "XML {} name `{}` is already used by entry `{}`",
+                kind, name, original.name.as_str(),
+            )));
+        }
+        names.push((name, entry));
+    }
+    Ok(())
+}
+
+/// Type of the items of record `entry`, if it is represented by repeated
+/// elements.
+fn repeated_item_of<'a: 'b, 'b>(spec: &'b Specification<'a>, entry: &'b RecordEntry<'a>) -> Option<&'b TypeRef<'a>> {
+    let mut kind = Kind::of(spec, entry.value_type());
+    if let Kind::Option(inner) = kind {
+        kind = Kind::of(spec, inner);
+    }
+    match kind {
+        Kind::List(item) | Kind::Set(item) => Some(item),
+        _ => None,
+    }
+}
+
+/// Decodes XML `input` of the type referred to by `type_ref` into
+/// [`Data`][dat].
+///
+/// Elements that do not correspond to any known type are decoded as maps,
+/// if having child elements, or as strings otherwise.
+///
+/// [dat]: ../enum.Data.html
+pub fn decode<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, input: &str) -> Result<Data, Error> {
+    let mut decoder = Decoder { input, offset: 0 };
+    decoder.skip_misc()?;
+    let element = decoder.element()?;
+    decoder.skip_misc()?;
+    if decoder.offset < input.len() {
+        return Err(decoder.error("unexpected content after root element"));
+    }
+    Ok(from_element(spec, Some(type_ref), &element))
+}
+
+/// Encodes `data` of the type referred to by `type_ref` as indented XML.
+pub fn encode<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, data: &Data) -> String {
+    let name = spec.resolve_record(&type_ref.name)
+        .map_or_else(|| type_ref.name.as_str().into(), record_name);
+    let element = to_element(spec, Some(type_ref), &name, data);
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    element.write(0, &mut output);
+    output
+}
+
+/// An XML element.
+#[derive(Debug, Eq, PartialEq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Element {
+            name: name.into(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        }
+    }
+
+    fn write(&self, indent: usize, output: &mut String) {
+        push_indent(indent, output);
+        output.push('<');
+        output.push_str(&self.name);
+        for (name, value) in &self.attributes {
+            output.push(' ');
+            output.push_str(name);
+            output.push_str("=\"");
+            push_escaped(value, output);
+            output.push('"');
+        }
+        if self.children.is_empty() && self.text.is_empty() {
+            output.push_str("/>\n");
+            return;
+        }
+        output.push('>');
+        if self.children.is_empty() {
+            push_escaped(&self.text, output);
+        } else {
+            output.push('\n');
+            for child in &self.children {
+                child.write(indent + 1, output);
+            }
+            push_indent(indent, output);
+        }
+        output.push_str("</");
+        output.push_str(&self.name);
+        output.push_str(">\n");
+    }
+}
+
+/// Converts `data` into element `name`, guided by the type referred to by
+/// `type_ref`, if known.
+fn to_element<'a>(spec: &Specification<'a>, type_ref: Option<&TypeRef<'a>>, name: &str, data: &Data) -> Element {
+    let kind = type_ref.map_or(Kind::Unknown, |type_ref| Kind::of(spec, type_ref));
+    let mut element = Element::new(name);
+    match (kind, data) {
+        (Kind::Option(inner), _) => return to_element(spec, Some(inner), name, data),
+        (Kind::Record(record, instance), Data::Map(entries)) => {
+            for entry in record.entries_of(instance).iter() {
+                let field = entry.name.as_str();
+                let value = match entries.iter().find(|(key, _)| key == field) {
+                    Some((_, value)) => value,
+                    None => continue,
+                };
+                if let Data::Null = value {
+                    if entry.may_be_null() {
+                        continue;
+                    }
+                }
+                let name = field_name(entry);
+                if is_attribute(spec, entry) {
+                    let text = to_element(spec, Some(&entry.type_ref), &name, value).text;
+                    element.attributes.push((name, text));
+                    continue;
+                }
+                match (repeated_item_of(spec, entry), value) {
+                    (Some(item), Data::List(items)) => for data in items {
+                        element.children.push(to_element(spec, Some(item), &name, data));
+                    },
+                    _ => element.children.push(to_element(spec, Some(&entry.type_ref), &name, value)),
+                }
+            }
+        }
+        (Kind::List(item), Data::List(items)) |
+        (Kind::Set(item), Data::List(items)) => {
+            for data in items {
+                element.children.push(to_element(spec, Some(item), "item", data));
+            }
+        }
+        (Kind::Map { value, .. }, Data::Map(entries)) => {
+            for (key, data) in entries {
+                let mut child = to_element(spec, Some(value), "entry", data);
+                child.attributes.insert(0, ("key".into(), key.clone()));
+                element.children.push(child);
+            }
+        }
+        (_, Data::List(items)) => {
+            for data in items {
+                element.children.push(to_element(spec, None, "item", data));
+            }
+        }
+        (_, Data::Map(entries)) => {
+            for (key, data) in entries {
+                element.children.push(to_element(spec, None, key, data));
+            }
+        }
+        (_, Data::Null) => {}
+        (_, Data::Boolean(boolean)) => element.text = boolean.to_string(),
+        (_, Data::Integer(integer)) => element.text = integer.to_string(),
+        (_, Data::Float(float)) => element.text = match *float {
+            float if float == f64::INFINITY => "INF".into(),
+            float if float == f64::NEG_INFINITY => "-INF".into(),
+            float => float.to_string(),
+        },
+        (_, Data::String(string)) => element.text = string.clone(),
+    }
+    element
+}
+
+/// Converts `element` into data, guided by the type referred to by
+/// `type_ref`, if known.
+fn from_element<'a>(spec: &Specification<'a>, type_ref: Option<&TypeRef<'a>>, element: &Element) -> Data {
+    let kind = type_ref.map_or(Kind::Unknown, |type_ref| Kind::of(spec, type_ref));
+    let text = element.text.trim();
+    match kind {
+        Kind::Boolean => match text {
+            "true" | "1" => Data::Boolean(true),
+            "false" | "0" => Data::Boolean(false),
+            _ => Data::String(text.into()),
+        },
+        Kind::Integer { .. } => text.trim_start_matches('+').parse()
+            .map_or_else(|_| Data::String(text.into()), Data::Integer),
+        Kind::Enum(enum_) if enum_.is_integer() => text.trim_start_matches('+').parse()
+            .map_or_else(|_| Data::String(text.into()), Data::Integer),
+        Kind::Float => match text {
+            "INF" | "+INF" => Data::Float(f64::INFINITY),
+            "-INF" => Data::Float(f64::NEG_INFINITY),
+            text => text.parse().map_or_else(|_| Data::String(text.into()), Data::Float),
+        },
+        Kind::String | Kind::Enum(_) => Data::String(element.text.clone()),
+        Kind::List(item) | Kind::Set(item) => Data::List(element.children.iter()
+            .map(|child| from_element(spec, Some(item), child))
+            .collect()),
+        Kind::Map { value, .. } => Data::Map(element.children.iter()
+            .map(|child| {
+                let key = child.attributes.iter()
+                    .find(|(name, _)| name == "key")
+                    .map_or_else(|| child.name.clone(), |(_, key)| key.clone());
+                (key, from_element(spec, Some(value), child))
+            })
+            .collect()),
+        Kind::Option(inner) => from_element(spec, Some(inner), element),
+        Kind::Record(record, instance) => from_record_element(spec, &record.entries_of(instance), element),
+        Kind::Unknown => if element.children.is_empty() {
+            Data::String(element.text.clone())
+        } else {
+            Data::Map(element.children.iter()
+                .map(|child| (child.name.clone(), from_element(spec, None, child)))
+                .collect())
+        },
+    }
+}
+
+/// Converts `element` into data, guided by the record fields `fields`.
+fn from_record_element<'a>(spec: &Specification<'a>, fields: &[RecordEntry<'a>], element: &Element) -> Data {
+    let mut entries: Vec<(String, Data)> = Vec::new();
+    for (name, value) in &element.attributes {
+        let entry = fields.iter()
+            .find(|entry| is_attribute(spec, entry) && field_name(entry) == *name);
+        if let Some(entry) = entry {
+            let mut attribute = Element::new(name);
+            attribute.text = value.clone();
+            entries.push((entry.name.as_str().into(), from_element(spec, Some(&entry.type_ref), &attribute)));
+        }
+    }
+    for child in &element.children {
+        let entry = fields.iter()
+            .find(|entry| !is_attribute(spec, entry) && field_name(entry) == child.name);
+        let entry = match entry {
+            Some(entry) => entry,
+            None => {
+                entries.push((child.name.clone(), from_element(spec, None, child)));
+                continue;
+            }
+        };
+        let name = entry.name.as_str();
+        let item = match repeated_item_of(spec, entry) {
+            Some(item) => item,
+            None => {
+                entries.push((name.into(), from_element(spec, Some(&entry.type_ref), child)));
+                continue;
+            }
+        };
+        let data = from_element(spec, Some(item), child);
+        match entries.iter_mut().find(|(key, _)| key == name) {
+            Some((_, Data::List(items))) => items.push(data),
+            _ => entries.push((name.into(), Data::List(vec![data]))),
+        }
+    }
+    // Absent repeated elements represent empty lists, unless they may be
+    // absent.
+    for entry in fields {
+        if entry.may_be_absent() {
+            continue;
+        }
+        let name = entry.name.as_str();
+        if repeated_item_of(spec, entry).is_some() && !entries.iter().any(|(key, _)| key == name) {
+            entries.push((name.into(), Data::List(Vec::new())));
+        }
+    }
+    Data::Map(entries)
+}
+
+struct Decoder<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &'static str) -> Error {
+        Error::XmlInvalid { offset: self.offset, message }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips `terminator` and everything before it.
+    fn skip_past(&mut self, terminator: &str, message: &'static str) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        match rest.find(terminator) {
+            Some(index) => {
+                self.offset += index + terminator.len();
+                Ok(&rest[..index])
+            }
+            None => Err(self.error(message)),
+        }
+    }
+
+    /// Skips whitespace, comments, processing instructions and document
+    /// type declarations.
+    fn skip_misc(&mut self) -> Result<(), Error> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>", "unterminated processing instruction")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_past(">", "unterminated document type declaration")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, Error> {
+        let rest = self.rest();
+        let length = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '/' || ch == '>' || ch == '=')
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected name"));
+        }
+        self.offset += length;
+        Ok(rest[..length].into())
+    }
+
+    fn element(&mut self) -> Result<Element, Error> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected element"));
+        }
+        self.offset += 1;
+        let mut element = Element::new(&self.name()?);
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.offset += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.offset += 1;
+                break;
+            }
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected `=`"));
+            }
+            self.offset += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => quote,
+                _ => return Err(self.error("expected quoted attribute value")),
+            };
+            self.offset += 1;
+            let start = self.offset;
+            let value = self.skip_past(if quote == '"' { "\"" } else { "'" }, "unterminated attribute value")?;
+            element.attributes.push((name, unescape(value)
+                .ok_or(Error::XmlInvalid { offset: start, message: "invalid entity reference" })?));
+        }
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.offset += 2;
+                let start = self.offset;
+                if self.name()? != element.name {
+                    return Err(Error::XmlInvalid { offset: start, message: "mismatched end tag" });
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("expected `>`"));
+                }
+                self.offset += 1;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.offset += 9;
+                let text = self.skip_past("]]>", "unterminated CDATA section")?;
+                element.text.push_str(text);
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "unterminated processing instruction")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else if rest.is_empty() {
+                return Err(self.error("unexpected end of input"));
+            } else {
+                let start = self.offset;
+                let length = rest.find('<').unwrap_or(rest.len());
+                self.offset += length;
+                element.text.push_str(&unescape(&rest[..length])
+                    .ok_or(Error::XmlInvalid { offset: start, message: "invalid entity reference" })?);
+            }
+        }
+    }
+}
+
+/// Replaces entity and character references in `text` with the characters
+/// they represent.
+fn unescape(text: &str) -> Option<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find('&') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let end = rest.find(';')?;
+        output.push(match &rest[..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            reference => {
+                let code = if let Some(digits) = reference.strip_prefix("#x") {
+                    u32::from_str_radix(digits, 16).ok()?
+                } else if let Some(digits) = reference.strip_prefix('#') {
+                    digits.parse().ok()?
+                } else {
+                    return None;
+                };
+                std::char::from_u32(code)?
+            }
+        });
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Some(output)
+}
+
+fn push_indent(indent: usize, output: &mut String) {
+    for _ in 0..indent {
+        output.push_str("  ");
+    }
+}
+
+fn push_escaped(text: &str, output: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use crate::payload::example;
+    use crate::spec::parser;
+    use super::*;
+
+    #[test]
+    fn encode_example() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "@XmlName(\"entry\")\n",
+                    "record Entry {\n",
+                    "    @XmlAttribute(true) @XmlName(\"name\") Name: String,\n",
+                    "    @XmlAttribute(true) Id: Option<U16>,\n",
+                    "    Tags: Set<String>,\n",
+                    "    Metadata: Map<String, U16>,\n",
+                    "    Note: Option<String>,\n",
+                    "}\n",
+                    "service S { method Get(): Entry; }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let output = spec.services[0].methods[0].output.as_ref().unwrap();
+        let mut data = example(&spec, output);
+        if let Data::Map(ref mut entries) = data {
+            entries[0].1 = Data::String("<A & B>".into());
+            entries[2].1 = Data::List(vec![Data::String("a".into()), Data::String("b".into())]);
+            entries[4].1 = Data::Null;
+        }
+        let output_xml = encode(&spec, output, &data);
+        assert_eq!(output_xml, concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<entry name=\"&lt;A &amp; B&gt;\" Id=\"8080\">\n",
+            "  <Tags>a</Tags>\n",
+            "  <Tags>b</Tags>\n",
+            "  <Metadata>\n",
+            "    <entry key=\"key\">8080</entry>\n",
+            "  </Metadata>\n",
+            "</entry>\n",
+        ));
+        if let Data::Map(ref mut entries) = data {
+            entries.remove(4);
+        }
+        assert_eq!(decode(&spec, output, &output_xml).unwrap(), data);
+    }
+
+    #[test]
+    fn decode_encoded() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "enum Level { Low, High }\n",
+                    "record Entry { Name: String, Level: Level, Tags: List<I32>, Metadata: Map<String, Boolean>, Note: Option<String> }\n",
+                    "service S { method Get(): Entry; }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let output = spec.services[0].methods[0].output.as_ref().unwrap();
+        let data = example(&spec, output);
+        assert_eq!(decode(&spec, output, &encode(&spec, output, &data)).unwrap(), data);
+
+        let data = decode(&spec, output, concat!(
+            "<?xml version=\"1.0\"?>\n",
+            "<!-- Comment. -->\n",
+            "<Entry>\n",
+            "  <Name>A &amp; <![CDATA[<B>]]>&#x21;</Name>\n",
+            "  <Tags>1</Tags><Tags>x</Tags>\n",
+            "  <Metadata><entry key='a'>true</entry></Metadata>\n",
+            "  <Extra><Inner>1</Inner></Extra>\n",
+            "</Entry>\n",
+        )).unwrap();
+        assert_eq!(data, Data::Map(vec![
+            ("Name".into(), Data::String("A & <B>!".into())),
+            ("Tags".into(), Data::List(vec![Data::Integer(1), Data::String("x".into())])),
+            ("Metadata".into(), Data::Map(vec![("a".into(), Data::Boolean(true))])),
+            ("Extra".into(), Data::Map(vec![("Inner".into(), Data::String("1".into()))])),
+        ]));
+        assert_eq!(decode(&spec, output, "<Entry><Name>A</Name></Entry>").unwrap(), Data::Map(vec![
+            ("Name".into(), Data::String("A".into())),
+            ("Tags".into(), Data::List(Vec::new())),
+        ]));
+
+        assert!(decode(&spec, output, "<Entry><Name>A</Nam></Entry>").is_err());
+        assert!(decode(&spec, output, "<Entry/><Entry/>").is_err());
+    }
+
+    #[test]
+    fn verify_attributes_and_names() {
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "test.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().map_err(|error| error.to_string())
+        };
+        verify(concat!(
+            "enum Kind { A, B }\n",
+            "record R {\n",
+            "    @XmlAttribute(true) Kind: Option<Kind>,\n",
+            "    @XmlName(\"Kind\") Name: String,\n",
+            "    @XmlAttribute(false) Items: List<String>,\n",
+            "}\n",
+        )).unwrap();
+
+        assert!(verify("record R { @XmlAttribute(true) Items: List<String> }\n").unwrap_err()
+            .starts_with("Invalid XML mapping of record entry, only entries of scalar or enum types may be XML attributes."));
+        assert!(verify("record A { X: U8 }\nrecord R { @XmlAttribute(true) A: A }\n").unwrap_err()
+            .starts_with("Invalid XML mapping of record entry, only entries of scalar or enum types may be XML attributes."));
+        assert!(verify("record R { @XmlName(\"X\") A: U8, X: String }\n").unwrap_err()
+            .starts_with("Invalid XML mapping of record entry, XML element name `X` is already used by entry `A`."));
+        assert!(verify("record R { @XmlAttribute(true) A: U8, @XmlAttribute(true) @XmlName(\"A\") B: U8 }\n").unwrap_err()
+            .starts_with("Invalid XML mapping of record entry, XML attribute name `A` is already used by entry `A`."));
+        assert!(verify("record B { @XmlName(\"X\") A: U8 }\nrecord R extends B { X: U8 }\n").unwrap_err()
+            .starts_with("Invalid XML mapping of record entry, XML element name `X` is already used by entry `A`."));
+    }
+}
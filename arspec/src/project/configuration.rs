@@ -1,10 +1,38 @@
-use arspec_parser::Text;
+use arspec_parser::{Excerpt, Text};
 use crate::error::Result;
+use std::fmt;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
 use super::parser;
 
+/// Names of generators that may be configured in the `ProjectGenerators`
+/// section of a project configuration.
+///
+/// The `doc` generator is run by the `doc` command, while all other
+/// generators are run by the `gen` command.
+pub const GENERATORS: &[&str] = &[
+    "asyncapi", "cddl", "doc", "java", "openapi", "rust-client", "rust-server", "typescript", "xsd",
+];
+
+/// Names of the documentation themes that may be given as the `Theme` setting
+/// of the `doc` generator.
+pub const THEMES: &[&str] = &["light", "dark"];
+
+/// Names of the settings read by the generator with given `name`.
+///
+/// Generators not listed have no settings. Only the `java` generator reads
+/// `Primitives`, which maps primitive names to Java types, as the other code
+/// generators map built-in primitives to native types and declare any other
+/// primitives as types of unknown structure.
+pub fn settings_of(generator: &str) -> &'static [&'static str] {
+    match generator {
+        "doc" => &["Theme"],
+        "java" => &["Package", "Primitives"],
+        _ => &[],
+    }
+}
+
 /// Arrowhead Framework project configuration.
 #[derive(Debug, Default)]
 pub struct Configuration {
@@ -16,6 +44,26 @@ pub struct Configuration {
 
     /// Project version.
     pub version: String,
+
+    /// Other specification projects depended upon.
+    pub dependencies: Vec<Dependency>,
+
+    /// Output folder, relative to project root.
+    pub target: String,
+
+    /// Glob patterns matching project source files to include. If empty, all
+    /// source files are included.
+    pub include: Vec<String>,
+
+    /// Glob patterns matching project source files to exclude, even if
+    /// included.
+    pub exclude: Vec<String>,
+
+    /// Settings of individual generators, keyed by generator names.
+    pub generators: Vec<(String, Setting)>,
+
+    /// Any issues found while reading configuration.
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl Configuration {
@@ -28,6 +76,12 @@ impl Configuration {
             name: name.into(),
             description: None,
             version: "0.1.0".into(),
+            dependencies: Vec::new(),
+            target: "target".into(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            generators: Vec::new(),
+            diagnostics: Vec::new(),
         }
     }
 
@@ -41,6 +95,14 @@ impl Configuration {
         Ok(conf)
     }
 
+    /// Looks up settings of generator with given `name`, if any.
+    #[inline]
+    pub fn generator(&self, name: &str) -> Option<&Setting> {
+        self.generators.iter()
+            .find(|(generator, _)| generator == name)
+            .map(|(_, setting)| setting)
+    }
+
     /// Writes `Configuration` to file at given `path`.
     pub fn write_to<P>(&self, path: P) -> Result
         where P: AsRef<Path>,
@@ -52,20 +114,149 @@ impl Configuration {
 
         file.write_all(format!(
             concat!(
-                "ProjectName: \"{}\"\n",
-                "ProjectVersion: \"{}\"",
+                "ProjectName: {}\n",
+                "ProjectVersion: {}\n",
             ),
-            self.name.replace('\"', "\\\""),
-            self.version.replace('\"', "\\\""),
+            Setting::String(self.name.clone()),
+            Setting::String(self.version.clone()),
         ).as_bytes())?;
 
         if let Some(ref description) = self.description {
             file.write_all(format!(
-                "ProjectDescription: \"{}\"\n",
-                description.replace('\"', "\\\""),
+                "ProjectDescription: {}\n",
+                Setting::String(description.clone()),
             ).as_bytes())?;
         }
 
+        if !self.dependencies.is_empty() {
+            let dependencies = Setting::Map(self.dependencies.iter()
+                .map(|dependency| (
+                    dependency.path.clone(),
+                    Setting::String(dependency.version.clone()),
+                ))
+                .collect());
+            file.write_all(format!("ProjectDependencies: {}\n", dependencies).as_bytes())?;
+        }
+
+        if self.target != "target" {
+            file.write_all(format!(
+                "ProjectTarget: {}\n",
+                Setting::String(self.target.clone()),
+            ).as_bytes())?;
+        }
+
+        for (key, patterns) in &[("ProjectInclude", &self.include), ("ProjectExclude", &self.exclude)] {
+            if !patterns.is_empty() {
+                let patterns = Setting::List(patterns.iter()
+                    .map(|pattern| Setting::String(pattern.clone()))
+                    .collect());
+                file.write_all(format!("{}: {}\n", key, patterns).as_bytes())?;
+            }
+        }
+
+        if !self.generators.is_empty() {
+            let generators = Setting::Map(self.generators.clone());
+            file.write_all(format!("ProjectGenerators: {}\n", generators).as_bytes())?;
+        }
+
         Ok(())
     }
 }
+
+/// A dependency on another specification project.
+#[derive(Debug)]
+pub struct Dependency {
+    /// Path to root folder of depended upon project, relative to the root
+    /// folder of the depending project.
+    pub path: String,
+
+    /// Required version of depended upon project.
+    pub version: String,
+}
+
+/// A generator setting value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Setting {
+    /// A string.
+    String(String),
+
+    /// A list of settings.
+    List(Vec<Setting>),
+
+    /// An ordered list of named settings.
+    Map(Vec<(String, Setting)>),
+}
+
+impl Setting {
+    /// Returns string, if this is a `Setting::String`.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Setting::String(ref string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Looks up named setting, if this is a `Setting::Map`.
+    pub fn get(&self, key: &str) -> Option<&Setting> {
+        match *self {
+            Setting::Map(ref entries) => entries.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, setting)| setting),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write_setting(f, self, 0);
+
+        fn write_setting(f: &mut fmt::Formatter, setting: &Setting, indent: usize) -> fmt::Result {
+            match *setting {
+                Setting::String(ref string) => write_string(f, string),
+                Setting::List(ref items) => {
+                    f.write_str("[\n")?;
+                    for item in items {
+                        write!(f, "{:1$}", "", (indent + 1) * 4)?;
+                        write_setting(f, item, indent + 1)?;
+                        f.write_str(",\n")?;
+                    }
+                    write!(f, "{:1$}]", "", indent * 4)
+                }
+                Setting::Map(ref entries) => {
+                    f.write_str("{\n")?;
+                    for (key, value) in entries {
+                        write!(f, "{:1$}", "", (indent + 1) * 4)?;
+                        write_string(f, key)?;
+                        f.write_str(": ")?;
+                        write_setting(f, value, indent + 1)?;
+                        f.write_str(",\n")?;
+                    }
+                    write!(f, "{:1$}}}", "", indent * 4)
+                }
+            }
+        }
+
+        fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+            write!(f, "\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+/// A configuration issue that does not prevent the configuration from being
+/// used.
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// Description of issue.
+    pub message: String,
+
+    /// Location of issue.
+    pub excerpt: Excerpt,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}", self.message, self.excerpt)
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Describes a project management error.
+#[derive(Debug)]
+pub enum Error {
+    /// The version of a project dependency does not satisfy the version
+    /// required by the depending project.
+    DependencyVersionMismatch {
+        path: PathBuf,
+        required: String,
+        actual: String,
+    },
+
+    /// A project or dependency version could not be parsed.
+    VersionInvalid {
+        path: PathBuf,
+        version: String,
+    },
+}
+
+impl crate::Error for Error {
+    fn code(&self) -> &'static str {
+        match *self {
+            Error::DependencyVersionMismatch { .. } => "PR01",
+            Error::VersionInvalid { .. } => "PR02",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::DependencyVersionMismatch { ref path, ref required, ref actual } => {
+                write!(
+                    f,
+                    "Dependency at `{}` has version `{}`, which is not compatible with required version `{}`",
+                    path.display(), actual, required,
+                )
+            }
+            Error::VersionInvalid { ref path, ref version } => {
+                write!(
+                    f,
+                    "Invalid version `{}` in project at `{}`, expected `X.Y.Z`",
+                    version, path.display(),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Glob pattern matching.
+
+/// Determines whether or not `path` matches glob `pattern`.
+///
+/// Paths are expected to be relative and to use `/` as separator. Patterns
+/// may contain the following special character sequences:
+///
+/// - `?` matches any one character except `/`.
+/// - `*` matches zero or more characters except `/`.
+/// - `**` matches zero or more characters, including `/`. If followed by
+///   `/`, it also matches zero folders.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    return matches_inner(&pattern, &path);
+
+    fn matches_inner(pattern: &[char], path: &[char]) -> bool {
+        match pattern {
+            [] => path.is_empty(),
+            ['*', '*', rest @ ..] => {
+                if let ['/', rest_after_slash @ ..] = rest {
+                    if matches_inner(rest_after_slash, path) {
+                        return true;
+                    }
+                }
+                (0..=path.len()).any(|offset| matches_inner(rest, &path[offset..]))
+            }
+            ['*', rest @ ..] => {
+                let mut offset = 0;
+                loop {
+                    if matches_inner(rest, &path[offset..]) {
+                        return true;
+                    }
+                    match path.get(offset) {
+                        Some('/') | None => return false,
+                        Some(_) => offset += 1,
+                    }
+                }
+            }
+            ['?', rest @ ..] => match path {
+                [ch, path_rest @ ..] if *ch != '/' => matches_inner(rest, path_rest),
+                _ => false,
+            },
+            [ch, rest @ ..] => match path {
+                [path_ch, path_rest @ ..] if path_ch == ch => matches_inner(rest, path_rest),
+                _ => false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn patterns() {
+        assert!(matches("*.ahfs", "common.ahfs"));
+        assert!(!matches("*.ahfs", "core/common.ahfs"));
+        assert!(matches("**/*.ahfs", "common.ahfs"));
+        assert!(matches("**/*.ahfs", "core/sub/common.ahfs"));
+        assert!(matches("target/**", "target/figures/x.ahfs"));
+        assert!(!matches("target/**", "targets/x.ahfs"));
+        assert!(matches("core/?.ahfs", "core/a.ahfs"));
+        assert!(!matches("core/?.ahfs", "core/ab.ahfs"));
+        assert!(matches("**/draft-*", "a/b/draft-1.ahfs"));
+        assert!(!matches("vendor/**", "src/vendor/x.ahfs"));
+    }
+}
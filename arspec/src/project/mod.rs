@@ -3,13 +3,21 @@
 //! This module contains tools useful for managing a folder containing a
 //! specification project.
 
+pub mod glob;
 pub mod parser;
 
 mod configuration;
+mod error;
+mod version;
 
-pub use self::configuration::Configuration;
+pub use self::configuration::{Configuration, Dependency, Diagnostic, GENERATORS, Setting, THEMES, settings_of};
+pub use self::error::Error;
+pub use self::version::Version;
 
+use arspec_parser::{Corpus, Text};
 use crate::error::Result;
+use crate::spec;
+use crate::spec::scope;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -76,17 +84,27 @@ impl Project {
     }
 
     /// Assembles list of all project source files.
+    ///
+    /// Source files inside the [`target()`][tgt] folder are never included.
+    /// Other source files are included only if matching any configured
+    /// include pattern, unless no such are configured, and none of the
+    /// configured exclude patterns.
+    ///
+    /// [tgt]: #method.target
     pub fn files(&self) -> Result<Box<[PathBuf]>> {
         let mut files = Vec::new();
-        files_inner(self.root(), &mut files)?;
+        files_inner(self, &self.target(), self.root(), &mut files)?;
         return Ok(files.into_boxed_slice());
 
-        fn files_inner(dir: &Path, files: &mut Vec<PathBuf>) -> Result {
+        fn files_inner(project: &Project, target: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result {
             for entry in dir.read_dir()? {
                 let entry = entry?;
                 match entry.file_type()? {
                     t @ _ if t.is_dir() => {
-                        files_inner(&entry.path(), files)?;
+                        let path = entry.path();
+                        if path != target {
+                            files_inner(project, target, &path, files)?;
+                        }
                         continue;
                     }
                     t @ _ if t.is_file() => {}
@@ -96,12 +114,145 @@ impl Project {
                 if path.extension().unwrap_or_default() != SOURCE_EXTENSION {
                     continue;
                 }
+                if !project.is_selected(&path) {
+                    continue;
+                }
                 files.push(path);
             }
             Ok(())
         }
     }
 
+    /// Whether or not source file at `path`, located inside the project root
+    /// folder, matches the configured include and exclude patterns.
+    fn is_selected(&self, path: &Path) -> bool {
+        let path = match path.strip_prefix(self.root()) {
+            Ok(path) => path.components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => return true,
+        };
+        let configuration = &self.configuration;
+        let is_included = configuration.include.is_empty() || configuration.include.iter()
+            .any(|pattern| glob::matches(pattern, &path));
+        let is_excluded = configuration.exclude.iter()
+            .any(|pattern| glob::matches(pattern, &path));
+
+        is_included && !is_excluded
+    }
+
+    /// Loads all projects this project depends on, directly or indirectly.
+    ///
+    /// Fails if any dependency cannot be loaded, or if the `ProjectVersion`
+    /// of any dependency is not compatible with the version required of it.
+    pub fn dependencies(&self) -> Result<Vec<Project>> {
+        let mut dependencies = Vec::new();
+        let mut visited = vec![self.root().canonicalize()?];
+        dependencies_inner(self, &mut visited, &mut dependencies)?;
+        return Ok(dependencies);
+
+        fn dependencies_inner(
+            project: &Project,
+            visited: &mut Vec<PathBuf>,
+            out: &mut Vec<Project>,
+        ) -> Result {
+            for dependency in &project.configuration.dependencies {
+                let root = project.root().join(&dependency.path);
+                let configuration = Configuration::read_at(root.join(PROJECT_FILE))?;
+
+                let required = Version::parse(&dependency.version)
+                    .ok_or_else(|| Error::VersionInvalid {
+                        path: project.root().join(PROJECT_FILE),
+                        version: dependency.version.clone(),
+                    })?;
+                let actual = Version::parse(&configuration.version)
+                    .ok_or_else(|| Error::VersionInvalid {
+                        path: root.join(PROJECT_FILE),
+                        version: configuration.version.clone(),
+                    })?;
+                if !actual.is_compatible_with(&required) {
+                    return Err(Error::DependencyVersionMismatch {
+                        path: root,
+                        required: dependency.version.clone(),
+                        actual: configuration.version,
+                    }.into());
+                }
+
+                let canonical_root = root.canonicalize()?;
+                if visited.contains(&canonical_root) {
+                    continue;
+                }
+                visited.push(canonical_root);
+
+                let dependency = Project {
+                    root: root.into(),
+                    configuration,
+                };
+                dependencies_inner(&dependency, visited, out)?;
+                out.push(dependency);
+            }
+            Ok(())
+        }
+    }
+
+    /// Reads all project source files, the source files of all projects
+    /// depended upon, as well as any files imported by any of them, into a
+    /// new `Corpus`.
+    ///
+    /// Use [`dependencies()`][dep] and [`contains()`][con] to determine
+    /// which source texts belong to other projects.
+    ///
+    /// [dep]: #method.dependencies
+    /// [con]: #method.contains
+    #[inline]
+    pub fn corpus(&self) -> Result<Corpus> {
+        self.corpus_with(&self.dependencies()?)
+    }
+
+    /// Reads all project source files, the source files of given
+    /// `dependencies`, as well as any files imported by any of them, into a
+    /// new `Corpus`.
+    ///
+    /// Useful for avoiding loading the [`dependencies()`][dep] of this
+    /// project more than once.
+    ///
+    /// [dep]: #method.dependencies
+    pub fn corpus_with(&self, dependencies: &[Project]) -> Result<Corpus> {
+        let mut paths = self.files()?.into_vec();
+        for dependency in dependencies {
+            paths.extend(dependency.files()?.into_vec());
+        }
+
+        let mut corpus = Corpus::default();
+        for path in paths {
+            let normalized = scope::normalize(&path);
+            let is_loaded = corpus.texts.iter()
+                .any(|text| scope::normalize(Path::new(&*text.name)) == normalized);
+            if !is_loaded {
+                corpus.texts.push(Text::read_at(&path)?);
+            }
+        }
+
+        read_imports(&mut corpus)?;
+        Ok(corpus)
+    }
+
+    /// Whether or not given source `text` is located in the root folder of
+    /// this project, or in any of its subfolders, other than the root folders
+    /// of the projects it depends on.
+    pub fn contains(&self, text: &Text) -> bool {
+        let path = scope::normalize(Path::new(&*text.name));
+        let is_within = |root: &Path| {
+            // An empty root is the current folder, which relative paths
+            // leading out of it via `..` are not within.
+            path.strip_prefix(scope::normalize(root))
+                .is_ok_and(|relative| !relative.starts_with(".."))
+        };
+        is_within(self.root()) && !self.configuration.dependencies.iter()
+            .any(|dependency| is_within(&self.root().join(&dependency.path)))
+    }
+
     /// Project root folder.
     #[inline]
     pub fn root(&self) -> &Path {
@@ -114,11 +265,69 @@ impl Project {
         &self.configuration
     }
 
-    /// Target output folder.
+    /// Target output folder, as configured by `ProjectTarget` in the project
+    /// configuration file, or `target` by default.
     #[inline]
     pub fn target(&self) -> PathBuf {
         let mut buf: PathBuf = self.root().into();
-        buf.push("target");
+        buf.push(&self.configuration.target);
         buf
     }
 }
+
+/// Reads all source files imported by the texts of `corpus`, directly or
+/// indirectly, into it, unless already loaded.
+pub fn read_imports(corpus: &mut Corpus) -> Result {
+    let mut offset = 0;
+    while offset < corpus.texts.len() {
+        let paths = spec::parser::imports(&corpus.texts[offset])
+            .iter()
+            .map(|import| import.resolve())
+            .collect::<Vec<_>>();
+        offset += 1;
+        for path in paths {
+            let is_loaded = corpus.texts.iter()
+                .any(|text| scope::normalize(Path::new(&*text.name)) == path);
+            if !is_loaded {
+                corpus.texts.push(Text::read_at(&path)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    #[test]
+    fn contains_no_dependency_texts() {
+        let folder = env::temp_dir().join(format!("arspec-project-{}", std::process::id()));
+        let mut app = Project::create("App", folder.join("app")).unwrap();
+        app.configuration.dependencies.push(Dependency { path: "../core".into(), version: "0.1.0".into() });
+        Project::create("Core", folder.join("core")).unwrap();
+        fs::write(folder.join("app").join("a.ahfs"), "record A { B: B }\n").unwrap();
+        fs::write(folder.join("core").join("b.ahfs"), "record B {}\n").unwrap();
+
+        let corpus = app.corpus().unwrap();
+        fs::remove_dir_all(&folder).unwrap();
+
+        let contained = corpus.texts.iter()
+            .map(|text| (Path::new(&*text.name).file_name().unwrap().to_owned(), app.contains(text)))
+            .collect::<Vec<_>>();
+        assert_eq!(contained, vec![("a.ahfs".into(), true), ("b.ahfs".into(), false)]);
+
+        // Projects are opened at `.` by the command line interface.
+        let mut configuration = Configuration::new("App");
+        configuration.dependencies.push(Dependency { path: "../core".into(), version: "4.2.0".into() });
+        configuration.dependencies.push(Dependency { path: "vendor/other".into(), version: "1.0.0".into() });
+        let app = Project { root: Path::new(".").into(), configuration };
+        let text = |name: &str| Text { name: name.into(), body: "".into() };
+        assert!(app.contains(&text("./a.ahfs")));
+        assert!(app.contains(&text("./sub/../a.ahfs")));
+        assert!(!app.contains(&text("./../core/b.ahfs")));
+        assert!(!app.contains(&text("../core/b.ahfs")));
+        assert!(!app.contains(&text("./vendor/other/c.ahfs")));
+    }
+}
@@ -1,6 +1,6 @@
 use arspec_parser::{Corpus, Error, Matcher, Parser, Scanner, Span, Token};
 use std::fmt;
-use super::Configuration;
+use super::{Configuration, Dependency, Diagnostic, GENERATORS, Setting, THEMES, settings_of};
 
 /// Attempt to create [`Configuration`][cnf] from given source [`text`][txt].
 ///
@@ -34,9 +34,14 @@ impl<'a> Parser<'a> for ConfigurationParser {
                 }
 
                 let class = match ch {
+                    '{' => Class::BraceLeft,
+                    '}' => Class::BraceRight,
                     ':' => Class::Colon,
+                    ',' => Class::Comma,
+                    '[' => Class::SquareLeft,
+                    ']' => Class::SquareRight,
                     '"' => scan_string(scanner)?,
-                    'P' => scan_symbol(scanner)?,
+                    ch if ch.is_ascii_alphabetic() || ch == '_' => scan_symbol(scanner)?,
                     _ => Class::UnknownSymbol,
                 };
 
@@ -65,16 +70,21 @@ impl<'a> Parser<'a> for ConfigurationParser {
         fn scan_symbol(scanner: &mut Scanner) -> Option<Class> {
             loop {
                 let ch = scanner.next()?;
-                if !ch.is_ascii_alphabetic() {
+                if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-') {
                     scanner.unwind();
                     break;
                 }
             }
             Some(match scanner.review() {
+                "ProjectDependencies" => Class::ProjectDependencies,
                 "ProjectDescription" => Class::ProjectDescription,
+                "ProjectExclude" => Class::ProjectExclude,
+                "ProjectGenerators" => Class::ProjectGenerators,
+                "ProjectInclude" => Class::ProjectInclude,
                 "ProjectName" => Class::ProjectName,
+                "ProjectTarget" => Class::ProjectTarget,
                 "ProjectVersion" => Class::ProjectVersion,
-                _ => Class::UnknownSymbol,
+                _ => Class::Symbol,
             })
         }
     }
@@ -84,20 +94,150 @@ impl<'a> Parser<'a> for ConfigurationParser {
         let mut name: Option<Span<'a>> = None;
         let mut description: Option<Span<'a>> = None;
         let mut version: Option<Span<'a>> = None;
+        let mut target: Option<Span<'a>> = None;
+        let mut dependencies = Vec::new();
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        let mut generators = Vec::new();
+        let mut diagnostics = Vec::new();
 
         while !matcher.at_end() {
-            let token = matcher.any(&[
+            let key = matcher.any(&[
+                Class::ProjectDependencies,
                 Class::ProjectDescription,
+                Class::ProjectExclude,
+                Class::ProjectGenerators,
+                Class::ProjectInclude,
                 Class::ProjectName,
+                Class::ProjectTarget,
                 Class::ProjectVersion,
+                Class::Symbol,
             ])?;
-            let target = match token.class {
+            matcher.one(Class::Colon)?;
+
+            let target = match key.class {
                 Class::ProjectDescription => &mut description,
                 Class::ProjectName => &mut name,
+                Class::ProjectTarget => &mut target,
                 Class::ProjectVersion => &mut version,
-                _ => unreachable!(),
+                _ => {
+                    let value = setting(&mut matcher)?;
+                    match key.class {
+                        Class::ProjectDependencies => match value {
+                            Setting::Map(entries) => {
+                                for (path, version) in entries {
+                                    match version {
+                                        Setting::String(version) => {
+                                            dependencies.push(Dependency { path, version });
+                                        }
+                                        _ => diagnostics.push(Diagnostic {
+                                            message: format!(
+                                                "Version of dependency `{}` must be a string, ignoring dependency.",
+                                                path,
+                                            ),
+                                            excerpt: key.span.to_excerpt(),
+                                        }),
+                                    }
+                                }
+                            }
+                            _ => diagnostics.push(expected(&key, "a map of paths and versions")),
+                        },
+                        Class::ProjectExclude | Class::ProjectInclude => {
+                            let patterns = match value {
+                                Setting::List(items) => items.into_iter()
+                                    .filter_map(|item| match item {
+                                        Setting::String(pattern) => Some(pattern),
+                                        _ => None,
+                                    })
+                                    .collect(),
+                                Setting::String(pattern) => vec![pattern],
+                                _ => {
+                                    diagnostics.push(expected(&key, "a list of glob patterns"));
+                                    continue;
+                                }
+                            };
+                            if key.class == Class::ProjectExclude {
+                                exclude = patterns;
+                            } else {
+                                include = patterns;
+                            }
+                        }
+                        Class::ProjectGenerators => match value {
+                            Setting::Map(entries) => {
+                                for (generator, settings) in entries {
+                                    if !GENERATORS.contains(&generator.as_str()) {
+                                        diagnostics.push(Diagnostic {
+                                            message: format!(
+                                                "Unknown generator `{}`, its settings are ignored. Known generators are: {}.",
+                                                generator,
+                                                GENERATORS.join(", "),
+                                            ),
+                                            excerpt: key.span.to_excerpt(),
+                                        });
+                                        continue;
+                                    }
+                                    let known = settings_of(&generator);
+                                    let settings = match settings {
+                                        Setting::Map(entries) => Setting::Map(entries.into_iter()
+                                            .filter(|(name, value)| {
+                                                let is_known = known.contains(&name.as_str());
+                                                if !is_known {
+                                                    diagnostics.push(Diagnostic {
+                                                        message: if name == "Primitives" {
+                                                            format!(
+                                                                "Primitive type mappings are only supported by generator `java`, `Primitives` of generator `{}` ignored.",
+                                                                generator,
+                                                            )
+                                                        } else if known.is_empty() {
+                                                            format!("Generator `{}` has no settings, `{}` is ignored.", generator, name)
+                                                        } else {
+                                                            format!(
+                                                                "Unknown setting `{}` of generator `{}` ignored. Known settings are: {}.",
+                                                                name,
+                                                                generator,
+                                                                known.join(", "),
+                                                            )
+                                                        },
+                                                        excerpt: key.span.to_excerpt(),
+                                                    });
+                                                    return false;
+                                                }
+                                                let is_theme_known = value.as_str()
+                                                    .is_some_and(|theme| THEMES.contains(&theme));
+                                                if generator == "doc" && name == "Theme" && !is_theme_known {
+                                                    diagnostics.push(Diagnostic {
+                                                        message: format!(
+                                                            "Unknown `Theme` of generator `doc` ignored. Known themes are: {}.",
+                                                            THEMES.join(", "),
+                                                        ),
+                                                        excerpt: key.span.to_excerpt(),
+                                                    });
+                                                    return false;
+                                                }
+                                                true
+                                            })
+                                            .collect()),
+                                        _ => {
+                                            diagnostics.push(Diagnostic {
+                                                message: format!("Expected settings of generator `{}` to be a map, ignoring them.", generator),
+                                                excerpt: key.span.to_excerpt(),
+                                            });
+                                            continue;
+                                        }
+                                    };
+                                    generators.push((generator, settings));
+                                }
+                            }
+                            _ => diagnostics.push(expected(&key, "a map of generator settings")),
+                        },
+                        _ => diagnostics.push(Diagnostic {
+                            message: format!("Unknown key `{}` ignored.", key.span.as_str()),
+                            excerpt: key.span.to_excerpt(),
+                        }),
+                    }
+                    continue;
+                }
             };
-            matcher.one(Class::Colon)?;
             let token = matcher.one(Class::String)?;
 
             *target = Some(token.span);
@@ -115,10 +255,69 @@ impl<'a> Parser<'a> for ConfigurationParser {
                 || "0.1.0".to_string(),
                 span_to_string,
             ),
+            dependencies,
+            target: target.map_or_else(
+                || "target".to_string(),
+                span_to_string,
+            ),
+            include,
+            exclude,
+            generators,
+            diagnostics,
         };
 
         return Ok(());
 
+        fn expected(key: &Token<Class>, what: &str) -> Diagnostic {
+            Diagnostic {
+                message: format!("Expected `{}` to be {}, ignoring it.", key.span.as_str(), what),
+                excerpt: key.span.to_excerpt(),
+            }
+        }
+
+        fn setting(matcher: &mut Matcher<Class>) -> Result<Setting, Error<Class>> {
+            let token = matcher.any(&[
+                Class::String,
+                Class::SquareLeft,
+                Class::BraceLeft,
+            ])?;
+            Ok(match token.class {
+                Class::String => Setting::String(span_to_string(token.span)),
+                Class::SquareLeft => {
+                    let mut items = Vec::new();
+                    while matcher.one_optional(Class::SquareRight).is_none() {
+                        items.push(setting(matcher)?);
+                        if matcher.one_optional(Class::Comma).is_none() {
+                            matcher.one(Class::SquareRight)?;
+                            break;
+                        }
+                    }
+                    Setting::List(items)
+                }
+                Class::BraceLeft => {
+                    let mut entries = Vec::new();
+                    while matcher.one_optional(Class::BraceRight).is_none() {
+                        let key = matcher.any(&[
+                            Class::String,
+                            Class::Symbol,
+                        ])?;
+                        let key = match key.class {
+                            Class::String => span_to_string(key.span),
+                            _ => key.span.as_str().to_string(),
+                        };
+                        matcher.one(Class::Colon)?;
+                        entries.push((key, setting(matcher)?));
+                        if matcher.one_optional(Class::Comma).is_none() {
+                            matcher.one(Class::BraceRight)?;
+                            break;
+                        }
+                    }
+                    Setting::Map(entries)
+                }
+                _ => unreachable!(),
+            })
+        }
+
         fn span_to_string(span: Span) -> String {
             let input = span.as_str();
             let input = &input[1..input.len() - 1];
@@ -157,12 +356,23 @@ impl<'a> Parser<'a> for ConfigurationParser {
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Class {
     // Delimiters.
+    BraceLeft,
+    BraceRight,
     Colon,
+    Comma,
+    SquareLeft,
+    SquareRight,
 
     // Symbols.
+    ProjectDependencies,
     ProjectDescription,
+    ProjectExclude,
+    ProjectGenerators,
+    ProjectInclude,
     ProjectName,
+    ProjectTarget,
     ProjectVersion,
+    Symbol,
 
     // Literals.
     String,
@@ -175,11 +385,22 @@ pub enum Class {
 impl fmt::Display for Class {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match *self {
+            Class::BraceLeft => "{",
+            Class::BraceRight => "}",
             Class::Colon => ":",
+            Class::Comma => ",",
+            Class::SquareLeft => "[",
+            Class::SquareRight => "]",
 
+            Class::ProjectDependencies => "ProjectDependencies",
             Class::ProjectDescription => "ProjectDescription",
+            Class::ProjectExclude => "ProjectExclude",
+            Class::ProjectGenerators => "ProjectGenerators",
+            Class::ProjectInclude => "ProjectInclude",
             Class::ProjectName => "ProjectName",
+            Class::ProjectTarget => "ProjectTarget",
             Class::ProjectVersion => "ProjectVersion",
+            Class::Symbol => "<Symbol>",
 
             Class::String => "String",
 
@@ -187,4 +408,86 @@ impl fmt::Display for Class {
             Class::UnknownSymbol => "<UnknownSymbol>",
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use crate::project::Setting;
+
+    #[test]
+    fn dependencies() {
+        let corpus: Corpus = Text {
+            name: "project.txt".into(),
+            body: concat!(
+                "ProjectName: \"App\"\n",
+                "ProjectVersion: \"1.0.0\"\n",
+                "ProjectDependencies: {\n",
+                "    \"../core\": \"4.2.0\",\n",
+                "    \"../other\": \"0.1\"\n",
+                "}\n",
+            ).into(),
+        }.into();
+        let configuration = super::parse(&corpus).unwrap();
+        assert_eq!(configuration.name, "App");
+        assert_eq!(configuration.dependencies.len(), 2);
+        assert_eq!(configuration.dependencies[0].path, "../core");
+        assert_eq!(configuration.dependencies[0].version, "4.2.0");
+        assert_eq!(configuration.dependencies[1].path, "../other");
+        assert_eq!(configuration.dependencies[1].version, "0.1");
+    }
+
+    #[test]
+    fn settings() {
+        let corpus: Corpus = Text {
+            name: "project.txt".into(),
+            body: concat!(
+                "ProjectName: \"App\"\n",
+                "ProjectTarget: \"docs\"\n",
+                "ProjectInclude: [\"specs/**/*.ahfs\"]\n",
+                "ProjectExclude: [\"specs/vendor/**\", \"**/draft-*.ahfs\"]\n",
+                "ProjectGenerators: {\n",
+                "    doc: { Theme: \"dark\" },\n",
+                "    \"unknown\": {},\n",
+                "    java: { Package: \"eu.arrowhead\", Primitives: { U64: \"long\" }, Color: \"red\" },\n",
+                "    typescript: { Primitives: { U64: \"bigint\" } },\n",
+                "    \"rust-client\": {},\n",
+                "}\n",
+                "ProjectColor: \"blue\"\n",
+            ).into(),
+        }.into();
+        let configuration = super::parse(&corpus).unwrap();
+        assert_eq!(configuration.target, "docs");
+        assert_eq!(configuration.include, vec!["specs/**/*.ahfs"]);
+        assert_eq!(configuration.exclude, vec!["specs/vendor/**", "**/draft-*.ahfs"]);
+        assert_eq!(configuration.generators.len(), 4);
+        assert_eq!(
+            configuration.generator("doc").and_then(|doc| doc.get("Theme")),
+            Some(&Setting::String("dark".into())),
+        );
+        let java = configuration.generator("java").unwrap();
+        assert!(java.get("Primitives").is_some());
+        assert_eq!(java.get("Color"), None);
+        assert_eq!(configuration.generator("typescript"), Some(&Setting::Map(Vec::new())));
+        assert_eq!(configuration.diagnostics.len(), 4);
+        assert!(configuration.diagnostics[0].message.contains("`unknown`"));
+        assert!(configuration.diagnostics[1].message.contains("`Color` of generator `java`"));
+        assert!(configuration.diagnostics[2].message.contains("`Primitives` of generator `typescript` ignored"));
+        assert!(configuration.diagnostics[3].message.contains("ProjectColor"));
+    }
+
+    #[test]
+    fn unknown_theme() {
+        let corpus: Corpus = Text {
+            name: "project.txt".into(),
+            body: concat!(
+                "ProjectName: \"App\"\n",
+                "ProjectGenerators: { doc: { Theme: \"blue\" } }\n",
+            ).into(),
+        }.into();
+        let configuration = super::parse(&corpus).unwrap();
+        assert_eq!(configuration.generator("doc").and_then(|doc| doc.get("Theme")), None);
+        assert_eq!(configuration.diagnostics.len(), 1);
+        assert!(configuration.diagnostics[0].message.contains("Known themes are: light, dark."));
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+
+/// A semantic version, as described at <https://semver.org>.
+///
+/// Any pre-release or build metadata suffixes are ignored.
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Version {
+    /// Major version, incremented when incompatible changes are made.
+    pub major: u64,
+
+    /// Minor version, incremented when backwards-compatible additions are
+    /// made.
+    pub minor: u64,
+
+    /// Patch version, incremented when backwards-compatible fixes are made.
+    pub patch: u64,
+}
+
+impl Version {
+    /// Attempts to parse `Version` from string formatted as `X.Y.Z`.
+    ///
+    /// Leading `^` characters, as well as missing minor and patch versions,
+    /// are tolerated.
+    pub fn parse(version: &str) -> Option<Version> {
+        let version = version.trim().trim_start_matches('^');
+        let version = version.split(['-', '+']).next()?;
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+        let patch = parts.next().map_or(Some(0), |patch| patch.parse().ok())?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version { major, minor, patch })
+    }
+
+    /// Whether or not this version can be used where the `required` version
+    /// is expected.
+    ///
+    /// Follows the same rules as Cargo caret requirements, meaning that the
+    /// left-most non-zero version number must be equal, while the remaining
+    /// ones must not be smaller than those required.
+    pub fn is_compatible_with(&self, required: &Version) -> bool {
+        if self < required {
+            return false;
+        }
+        match (required.major, required.minor) {
+            (0, 0) => self == required,
+            (0, minor) => self.major == 0 && self.minor == minor,
+            (major, _) => self.major == major,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let version = |major, minor, patch| Some(Version { major, minor, patch });
+
+        assert_eq!(Version::parse("4.2.0"), version(4, 2, 0));
+        assert_eq!(Version::parse("^1.2"), version(1, 2, 0));
+        assert_eq!(Version::parse("3"), version(3, 0, 0));
+        assert_eq!(Version::parse("1.0.0-beta+1"), version(1, 0, 0));
+        assert_eq!(Version::parse("1.0.0.0"), None);
+        assert_eq!(Version::parse("one"), None);
+    }
+
+    #[test]
+    fn is_compatible_with() {
+        let v = |version| Version::parse(version).unwrap();
+
+        assert!(v("4.2.0").is_compatible_with(&v("4.2.0")));
+        assert!(v("4.3.1").is_compatible_with(&v("4.2.0")));
+        assert!(!v("4.1.9").is_compatible_with(&v("4.2.0")));
+        assert!(!v("5.0.0").is_compatible_with(&v("4.2.0")));
+        assert!(v("0.2.5").is_compatible_with(&v("0.2.1")));
+        assert!(!v("0.3.0").is_compatible_with(&v("0.2.1")));
+        assert!(!v("0.0.2").is_compatible_with(&v("0.0.1")));
+    }
+}
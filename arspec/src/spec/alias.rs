@@ -0,0 +1,244 @@
+use arspec_parser::Span;
+use crate::payload::{validate, Data};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ptr;
+use super::{Attribute, Pattern, Specification, TypeRef, Value, VerificationError};
+
+/// A type alias definition, optionally constraining the values of the
+/// aliased type.
+#[derive(Debug)]
+pub struct Alias<'a> {
+    /// Name of alias.
+    pub name: Span<'a>,
+
+    /// Aliased type, which may itself be an alias.
+    pub definition: TypeRef<'a>,
+
+    /// Constraints that values must satisfy in addition to being values of
+    /// the aliased type.
+    pub constraints: Vec<Constraint<'a>>,
+
+    /// Any attributes.
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> Alias<'a> {
+    /// Create new alias definition without constraints.
+    #[inline]
+    pub fn new(name: Span<'a>, definition: TypeRef<'a>, attributes: Vec<Attribute<'a>>) -> Self {
+        Alias {
+            name,
+            definition,
+            constraints: Vec::new(),
+            attributes,
+        }
+    }
+
+    /// Asserts that this alias is not cyclic, and that its constraints are
+    /// consistent with each other and with the aliased type.
+    pub fn verify(&self, spec: &Specification<'a>) -> Result<(), VerificationError> {
+        let (target, aliases) = spec.unalias(&self.definition);
+        if aliases.iter().any(|alias| ptr::eq(*alias, self)) || spec.resolve_alias(&target.name).is_some() {
+            return Err(VerificationError::AliasCycle {
+                alias: self.name.to_excerpt(),
+            });
+        }
+        super::record::verify_arity(spec, &self.definition, &[])?;
+
+        let invalid = |constraint: &Constraint, reason: String| VerificationError::AliasConstraintInvalid {
+            constraint: constraint.span().to_excerpt(),
+            reason,
+        };
+        let target_name = if target.params.is_empty() { target.name.as_str() } else { "" };
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            if self.constraints[..index].iter().any(|other| other.is_same_kind_as(constraint)) {
+                return Err(invalid(constraint, "the constraint is already given".into()));
+            }
+            let is_applicable = match constraint {
+                Constraint::Range { .. } => matches!(target_name,
+                    "I8" | "I16" | "I32" | "I64" | "U8" | "U16" | "U32" | "U64" | "Float32" | "Float64"),
+                _ => target_name == "String",
+            };
+            if !is_applicable {
+                return Err(invalid(constraint, format!("{} do not apply to `{}`", match constraint {
+                    Constraint::Range { .. } => "ranges",
+                    Constraint::LengthMin { .. } | Constraint::LengthMax { .. } => "lengths",
+                    Constraint::Pattern { .. } => "patterns",
+                }, target)));
+            }
+            match constraint {
+                Constraint::Range { start, end, is_inclusive, .. } => {
+                    let bounds = [(start, false), (end, !is_inclusive)];
+                    for (bound, is_exclusive) in bounds.iter().filter_map(|(bound, is_exclusive)| {
+                        bound.as_ref().map(|bound| (Data::from(bound), *is_exclusive))
+                    }) {
+                        // Exclusive bounds need not be valid values themselves.
+                        let bound = match bound {
+                            Data::Integer(integer) if is_exclusive => Data::Integer(integer - 1),
+                            bound => bound,
+                        };
+                        if let Some(violation) = validate(spec, &self.definition, &bound).into_iter().next() {
+                            return Err(invalid(constraint, format!("invalid range bound, {}", violation.message)));
+                        }
+                    }
+                    if constraint.is_empty_range() {
+                        return Err(invalid(constraint, "the range is empty".into()));
+                    }
+                }
+                Constraint::LengthMin { length, .. } | Constraint::LengthMax { length, .. } => {
+                    match Data::from(length) {
+                        Data::Integer(length) if length >= 0 => {}
+                        _ => return Err(invalid(constraint, "lengths cannot be negative".into())),
+                    }
+                }
+                Constraint::Pattern { pattern, .. } => {
+                    let pattern = pattern.as_string().unwrap_or_default();
+                    if let Err(error) = Pattern::parse(&pattern) {
+                        return Err(invalid(constraint, format!("invalid pattern, {}", error)));
+                    }
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (self.length_min(), self.length_max()) {
+            if min > max {
+                let constraint = self.constraints.iter()
+                    .find(|constraint| matches!(constraint, Constraint::LengthMax { .. }))
+                    .unwrap();
+                return Err(invalid(constraint, format!("the maximum length is less than {}", min)));
+            }
+        }
+        Ok(())
+    }
+
+    /// The minimum number of characters of strings, if constrained.
+    pub fn length_min(&self) -> Option<u64> {
+        self.constraints.iter().find_map(|constraint| match constraint {
+            Constraint::LengthMin { length, .. } => length_of(length),
+            _ => None,
+        })
+    }
+
+    /// The maximum number of characters of strings, if constrained.
+    pub fn length_max(&self) -> Option<u64> {
+        self.constraints.iter().find_map(|constraint| match constraint {
+            Constraint::LengthMax { length, .. } => length_of(length),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> AsRef<str> for Alias<'a> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// A constraint restricting the values of an aliased type.
+#[derive(Clone, Debug)]
+pub enum Constraint<'a> {
+    /// Numbers from `start` to `end`, such as `1..=65535`, where `end` is
+    /// only included if `is_inclusive`. Ranges without `start` or `end` are
+    /// unbounded in that direction.
+    Range { span: Span<'a>, start: Option<Value<'a>>, end: Option<Value<'a>>, is_inclusive: bool },
+
+    /// Strings of at least `length` characters, such as `len >= 1`.
+    LengthMin { span: Span<'a>, length: Value<'a> },
+
+    /// Strings of at most `length` characters, such as `len <= 64`.
+    LengthMax { span: Span<'a>, length: Value<'a> },
+
+    /// Strings containing matches of the regular expression `pattern`, such
+    /// as `pattern "^[A-Za-z]+$"`.
+    Pattern { span: Span<'a>, pattern: Value<'a> },
+}
+
+impl<'a> Constraint<'a> {
+    /// Span of the whole constraint.
+    pub fn span(&self) -> &Span<'a> {
+        match self {
+            Constraint::Range { span, .. } |
+            Constraint::LengthMin { span, .. } |
+            Constraint::LengthMax { span, .. } |
+            Constraint::Pattern { span, .. } => span,
+        }
+    }
+
+    /// Determines whether `data` satisfies this constraint, assuming that it
+    /// is a value of the constrained type.
+    pub fn admits(&self, data: &Data) -> bool {
+        match (self, data) {
+            (Constraint::Range { start, end, is_inclusive, .. }, data) => {
+                if let Data::Float(number) = *data {
+                    if number.is_nan() {
+                        return false;
+                    }
+                }
+                let is_after_start = start.as_ref()
+                    .and_then(|start| compare(data, &Data::from(start)))
+                    .is_none_or(|ordering| ordering != Ordering::Less);
+                let is_before_end = end.as_ref()
+                    .and_then(|end| compare(data, &Data::from(end)))
+                    .is_none_or(|ordering| ordering == Ordering::Less || *is_inclusive && ordering == Ordering::Equal);
+                is_after_start && is_before_end
+            }
+            (Constraint::LengthMin { length, .. }, Data::String(string)) => {
+                length_of(length).is_none_or(|length| string.chars().count() as u64 >= length)
+            }
+            (Constraint::LengthMax { length, .. }, Data::String(string)) => {
+                length_of(length).is_none_or(|length| string.chars().count() as u64 <= length)
+            }
+            (Constraint::Pattern { pattern, .. }, Data::String(string)) => pattern.as_string()
+                .and_then(|pattern| Pattern::parse(&pattern).ok())
+                .is_none_or(|pattern| pattern.is_match(string)),
+            _ => true,
+        }
+    }
+
+    fn is_same_kind_as(&self, other: &Constraint) -> bool {
+        matches!((self, other),
+            (Constraint::Range { .. }, Constraint::Range { .. }) |
+            (Constraint::LengthMin { .. }, Constraint::LengthMin { .. }) |
+            (Constraint::LengthMax { .. }, Constraint::LengthMax { .. }) |
+            (Constraint::Pattern { .. }, Constraint::Pattern { .. }))
+    }
+
+    fn is_empty_range(&self) -> bool {
+        let (start, end, is_inclusive) = match self {
+            Constraint::Range { start: Some(start), end: Some(end), is_inclusive, .. } => (start, end, *is_inclusive),
+            _ => return false,
+        };
+        match compare(&Data::from(start), &Data::from(end)) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => !is_inclusive,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Constraint<'a> {
+    /// Writes constraint as it appears in its specification.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.span().as_str())
+    }
+}
+
+/// Compares numbers `a` and `b`, unless either is not a number.
+fn compare(a: &Data, b: &Data) -> Option<Ordering> {
+    match (a, b) {
+        (Data::Integer(a), Data::Integer(b)) => Some(a.cmp(b)),
+        (Data::Integer(a), Data::Float(b)) => (*a as f64).partial_cmp(b),
+        (Data::Float(a), Data::Integer(b)) => a.partial_cmp(&(*b as f64)),
+        (Data::Float(a), Data::Float(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+fn length_of(value: &Value) -> Option<u64> {
+    match Data::from(value) {
+        Data::Integer(integer) => u64::try_from(integer).ok(),
+        _ => None,
+    }
+}
@@ -2,11 +2,34 @@ use arspec_parser::Span;
 use super::Value;
 
 /// An arbitrary attribute, associated with some other specification element.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Attribute<'a> {
     /// Attribute name.
     pub name: Span<'a>,
 
     /// Arbitrary attribute value.
     pub value: Value<'a>,
-}
\ No newline at end of file
+}
+
+/// Looks up the `@Doc` attribute among `attributes`, and returns its text
+/// with common leading whitespace and surrounding blank lines removed.
+pub fn doc_of(attributes: &[Attribute]) -> Option<String> {
+    let text = attributes.iter()
+        .find(|attribute| attribute.name.as_str() == "Doc")
+        .and_then(|attribute| attribute.value.as_string())?;
+    let indent = text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let doc = text.lines()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let doc = doc.trim_matches('\n');
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.into())
+    }
+}
@@ -0,0 +1,766 @@
+use arspec_parser::{Excerpt, Span};
+use crate::project::Version;
+use std::collections::HashMap;
+use std::fmt;
+use super::{Alias, Constraint, EnumVariant, Implement, RecordEntry, Specification, TypeRef, Value};
+
+/// The semantic differences between two versions of a specification.
+#[derive(Debug, Default)]
+pub struct Diff {
+    /// Any changes made to the old specification to produce the new one.
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Determines which changes were made to `old` to produce `new`.
+    ///
+    /// Definitions are matched by their package-qualified names, while
+    /// documentation and other attributes are ignored.
+    pub fn new(old: &Specification, new: &Specification) -> Diff {
+        let mut differ = Differ {
+            old,
+            new,
+            old_usages: usages_of(old),
+            new_usages: usages_of(new),
+            changes: Vec::new(),
+        };
+        differ.aliases();
+        differ.enums();
+        differ.primitives();
+        differ.records();
+        differ.services();
+        differ.implementations();
+        differ.systems();
+        Diff { changes: differ.changes }
+    }
+
+    /// Whether or not any change may break existing consumers.
+    #[inline]
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.impact == Impact::Breaking)
+    }
+
+    /// Recommends next version of a specification currently at version
+    /// `current`, according to the rules of semantic versioning.
+    ///
+    /// Breaking changes require a new major version, while compatible
+    /// changes only require a new minor version. If there are no semantic
+    /// changes, a new patch version is recommended. As long as the major
+    /// version is `0`, the minor version is treated as major and the patch
+    /// version as minor.
+    pub fn recommend_version(&self, current: &Version) -> Version {
+        let Version { major, minor, patch } = *current;
+        let (major, minor, patch) = if self.is_breaking() {
+            if major == 0 { (0, minor + 1, 0) } else { (major + 1, 0, 0) }
+        } else if !self.changes.is_empty() && major != 0 {
+            (major, minor + 1, 0)
+        } else {
+            (major, minor, patch + 1)
+        };
+        Version { major, minor, patch }
+    }
+}
+
+/// A semantic change made to some specification element.
+#[derive(Debug)]
+pub struct Change {
+    /// What happened to the element.
+    pub kind: ChangeKind,
+
+    /// The kind of element that changed.
+    pub element: Element,
+
+    /// Qualified name of changed element, starting with the name of the
+    /// top-level definition it belongs to.
+    pub path: Vec<String>,
+
+    /// Whether or not the change may break existing consumers.
+    pub impact: Impact,
+
+    /// Location of element in new specification, or in the old one if the
+    /// element was removed.
+    pub excerpt: Excerpt,
+}
+
+impl Change {
+    /// Qualified name of the top-level definition containing the changed
+    /// element.
+    ///
+    /// Service implementations are considered part of the services they
+    /// implement.
+    pub fn owner(&self) -> &str {
+        let name = &self.path[0];
+        match self.element.definition() {
+            Element::Service => name.split('[').next().unwrap_or(name),
+            _ => name,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = self.path.join(".");
+        match self.kind {
+            ChangeKind::Added => write!(f, "Added {} `{}`.", self.element, path),
+            ChangeKind::Removed => write!(f, "Removed {} `{}`.", self.element, path),
+            ChangeKind::Changed { ref from, ref to } => {
+                write!(f, "Changed {} `{}` from ", self.element, path)?;
+                match *from {
+                    Some(ref from) => write!(f, "`{}`", from)?,
+                    None => f.write_str("nothing")?,
+                }
+                f.write_str(" to ")?;
+                match *to {
+                    Some(ref to) => write!(f, "`{}`.", to),
+                    None => f.write_str("nothing."),
+                }
+            }
+        }
+    }
+}
+
+/// Describes what happened to a changed specification element.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ChangeKind {
+    /// Element only exists in new specification.
+    Added,
+
+    /// Element only exists in old specification.
+    Removed,
+
+    /// Element exists in both specifications, but its definition changed.
+    Changed { from: Option<String>, to: Option<String> },
+}
+
+/// Kinds of specification elements that can be changed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Element {
+    Alias,
+    Enum,
+    EnumVariant,
+    Field,
+    Implementation,
+    Method,
+    MethodError,
+    MethodInput,
+    MethodOutput,
+    Primitive,
+    Property,
+    Record,
+    Route,
+    Service,
+    System,
+    SystemConsumes,
+    SystemProduces,
+}
+
+impl Element {
+    /// Kind of top-level definition containing this kind of element.
+    pub fn definition(&self) -> Element {
+        match *self {
+            Element::EnumVariant => Element::Enum,
+            Element::Field => Element::Record,
+            Element::Implementation |
+            Element::Method |
+            Element::MethodError |
+            Element::MethodInput |
+            Element::MethodOutput |
+            Element::Property |
+            Element::Route => Element::Service,
+            Element::SystemConsumes |
+            Element::SystemProduces => Element::System,
+            element => element,
+        }
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Element::Alias => "alias",
+            Element::Enum => "enum",
+            Element::EnumVariant => "enum variant",
+            Element::Field => "field",
+            Element::Implementation => "implementation",
+            Element::Method => "method",
+            Element::MethodError => "method error",
+            Element::MethodInput => "method input",
+            Element::MethodOutput => "method output",
+            Element::Primitive => "primitive",
+            Element::Property => "property",
+            Element::Record => "record",
+            Element::Route => "route",
+            Element::Service => "service",
+            Element::System => "system",
+            Element::SystemConsumes => "consumed service",
+            Element::SystemProduces => "produced service",
+        })
+    }
+}
+
+/// The consequences of a change for existing consumers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Impact {
+    /// Existing consumers keep working.
+    Compatible,
+
+    /// Existing consumers may stop working.
+    Breaking,
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Impact::Compatible => "compatible",
+            Impact::Breaking => "breaking",
+        })
+    }
+}
+
+/// How alias, record and enum types are used by service methods.
+#[derive(Copy, Clone, Debug, Default)]
+struct Usage {
+    /// Type is sent by consumers, as part of some method input.
+    input: bool,
+
+    /// Type is received by consumers, as part of some method output or
+    /// error.
+    output: bool,
+}
+
+/// Determines how the alias, record and enum types of `spec` are used, keyed
+/// by their package-qualified names.
+fn usages_of(spec: &Specification) -> HashMap<String, Usage> {
+    let mut usages = HashMap::new();
+    for service in &spec.services {
+        for method in &service.methods {
+            if let Some(ref input) = method.input {
+                mark(spec, input, &mut usages, |usage| &mut usage.input);
+            }
+            if let Some(ref output) = method.output {
+                mark(spec, output, &mut usages, |usage| &mut usage.output);
+            }
+            for error in &method.errors {
+                mark(spec, error, &mut usages, |usage| &mut usage.output);
+            }
+        }
+    }
+    return usages;
+
+    fn mark<'a>(
+        spec: &Specification<'a>,
+        type_ref: &TypeRef<'a>,
+        usages: &mut HashMap<String, Usage>,
+        flag: fn(&mut Usage) -> &mut bool,
+    ) {
+        for param in &type_ref.params {
+            mark(spec, param, usages, flag);
+        }
+        if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+            let flag_ref = flag(usages.entry(spec.qualify(&alias.name)).or_default());
+            if *flag_ref {
+                return;
+            }
+            *flag_ref = true;
+            mark(spec, &alias.definition, usages, flag);
+        }
+        if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+            *flag(usages.entry(spec.qualify(&enum_.name)).or_default()) = true;
+        }
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            let flag_ref = flag(usages.entry(spec.qualify(&record.name)).or_default());
+            if *flag_ref {
+                return;
+            }
+            *flag_ref = true;
+            for entry in &record.entries {
+                mark(spec, &entry.type_ref, usages, flag);
+            }
+        }
+    }
+}
+
+/// Renders the aliased type of `alias`, followed by its constraints, if any.
+fn alias_string(alias: &Alias) -> String {
+    let mut string = alias.definition.to_string();
+    if !alias.constraints.is_empty() {
+        let constraints = alias.constraints.iter()
+            .map(|constraint| constraint.to_string())
+            .collect::<Vec<_>>();
+        string = format!("{} where {}", string, constraints.join(", "));
+    }
+    string
+}
+
+/// Renders the type of record `entry`, preceded by `optional` if it is
+/// optional and followed by its default value, if any.
+fn entry_string(entry: &RecordEntry) -> String {
+    let mut string = entry.type_ref.to_string();
+    if entry.is_optional {
+        string.insert_str(0, "optional ");
+    }
+    if let Some(ref default) = entry.default {
+        string = format!("{} = {}", string, default);
+    }
+    string
+}
+
+/// Renders the value representing enum `variant` in payloads, which is its
+/// name unless it has an explicit value.
+fn variant_value_string(variant: &EnumVariant) -> String {
+    match variant.value {
+        Some(ref value) => value.to_string(),
+        None => format!("\"{}\"", variant.name.as_str()),
+    }
+}
+
+/// Renders `entries` as a map, without any insignificant whitespace.
+fn entries_string(entries: &[(Span, Value)]) -> String {
+    let entries = entries.iter()
+        .map(|(name, value)| format!("{}: {}", name.as_str(), value))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Name of `implementation`, which includes its protocol and encoding.
+fn implementation_name(spec: &Specification, implementation: &Implement) -> String {
+    format!(
+        "{}[{}/{}]",
+        spec.qualify(&implementation.name),
+        implementation.protocol.as_str(),
+        implementation.encoding.as_str(),
+    )
+}
+
+/// Pairs the items of `old` and `new` with equal keys.
+///
+/// Returns removed items, pairs of retained items and added items, in that
+/// order.
+#[allow(clippy::type_complexity)]
+fn pair<'b, E>(old: &'b [(String, E)], new: &'b [(String, E)]) -> (Vec<&'b E>, Vec<(&'b E, &'b E)>, Vec<&'b E>) {
+    let mut removed = Vec::new();
+    let mut retained = Vec::new();
+    for (key, old_item) in old {
+        match new.iter().find(|(new_key, _)| new_key == key) {
+            Some((_, new_item)) => retained.push((old_item, new_item)),
+            None => removed.push(old_item),
+        }
+    }
+    let added = new.iter()
+        .filter(|(key, _)| !old.iter().any(|(old_key, _)| old_key == key))
+        .map(|(_, item)| item)
+        .collect();
+    (removed, retained, added)
+}
+
+struct Differ<'a, 'b> {
+    old: &'b Specification<'a>,
+    new: &'b Specification<'a>,
+    old_usages: HashMap<String, Usage>,
+    new_usages: HashMap<String, Usage>,
+    changes: Vec<Change>,
+}
+
+impl<'a, 'b> Differ<'a, 'b> {
+    fn push(&mut self, kind: ChangeKind, element: Element, path: Vec<String>, impact: Impact, span: &Span) {
+        self.changes.push(Change {
+            kind,
+            element,
+            path,
+            impact,
+            excerpt: span.to_excerpt(),
+        });
+    }
+
+    /// Combined usage of type with qualified `name` in both specifications.
+    ///
+    /// Types not used by any service method are assumed to be both sent and
+    /// received, as they could be used by other specifications.
+    fn usage(&self, name: &str) -> Usage {
+        let old = self.old_usages.get(name).copied().unwrap_or_default();
+        let new = self.new_usages.get(name).copied().unwrap_or_default();
+        let usage = Usage { input: old.input || new.input, output: old.output || new.output };
+        if usage.input || usage.output {
+            usage
+        } else {
+            Usage { input: true, output: true }
+        }
+    }
+
+    /// Pushes changes of top-level definitions only ever being either added
+    /// or removed.
+    fn top_level<E>(&mut self, element: Element, removed: &[&(String, &E)], added: &[&(String, &E)], name_of: fn(&E) -> &Span<'a>) {
+        for (name, item) in removed.iter().copied() {
+            self.push(ChangeKind::Removed, element, vec![name.clone()], Impact::Breaking, name_of(item));
+        }
+        for (name, item) in added.iter().copied() {
+            self.push(ChangeKind::Added, element, vec![name.clone()], Impact::Compatible, name_of(item));
+        }
+    }
+
+    fn aliases(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.aliases.iter()
+            .map(|alias| (spec.qualify(&alias.name), alias))
+            .map(|(name, alias)| (name.clone(), (name, alias)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::Alias, &removed, &added, |alias| &alias.name);
+
+        for ((name, old), (_, new)) in retained {
+            let (from, to) = (alias_string(old), alias_string(new));
+            if from == to {
+                continue;
+            }
+            // Added constraints may reject values consumers send, while
+            // removed constraints may admit values consumers do not expect.
+            // Changed constraints are assumed to do both.
+            let usage = self.usage(name);
+            let is_missing_from = |constraint: &Constraint, constraints: &[Constraint]| !constraints.iter()
+                .any(|other| other.to_string() == constraint.to_string());
+            let is_tightened = new.constraints.iter().any(|constraint| is_missing_from(constraint, &old.constraints));
+            let is_loosened = old.constraints.iter().any(|constraint| is_missing_from(constraint, &new.constraints));
+            let impact = if old.definition.to_string() != new.definition.to_string()
+                || is_tightened && usage.input
+                || is_loosened && usage.output
+            {
+                Impact::Breaking
+            } else {
+                Impact::Compatible
+            };
+            let kind = ChangeKind::Changed { from: Some(from), to: Some(to) };
+            self.push(kind, Element::Alias, vec![name.clone()], impact, &new.name);
+        }
+    }
+
+    fn enums(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.enums.iter()
+            .map(|enum_| (spec.qualify(&enum_.name), enum_))
+            .map(|(name, enum_)| (name.clone(), (name, enum_)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::Enum, &removed, &added, |enum_| &enum_.name);
+
+        for ((name, old), (_, new)) in retained {
+            let usage = self.usage(name);
+            let (old, new) = (
+                old.variants.iter().map(|variant| (variant.name.as_str().to_string(), variant)).collect::<Vec<_>>(),
+                new.variants.iter().map(|variant| (variant.name.as_str().to_string(), variant)).collect::<Vec<_>>(),
+            );
+            let (removed, retained, added) = pair(&old, &new);
+            for variant in removed {
+                let impact = if usage.input { Impact::Breaking } else { Impact::Compatible };
+                let path = vec![name.clone(), variant.name.as_str().into()];
+                self.push(ChangeKind::Removed, Element::EnumVariant, path, impact, &variant.name);
+            }
+            for variant in added {
+                let impact = if usage.output { Impact::Breaking } else { Impact::Compatible };
+                let path = vec![name.clone(), variant.name.as_str().into()];
+                self.push(ChangeKind::Added, Element::EnumVariant, path, impact, &variant.name);
+            }
+            for (old, new) in retained {
+                if old.wire_value() == new.wire_value() {
+                    continue;
+                }
+                let kind = ChangeKind::Changed { from: Some(variant_value_string(old)), to: Some(variant_value_string(new)) };
+                let path = vec![name.clone(), new.name.as_str().into()];
+                self.push(kind, Element::EnumVariant, path, Impact::Breaking, &new.name);
+            }
+        }
+    }
+
+    fn primitives(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.primitives.iter()
+            .map(|primitive| (spec.qualify(&primitive.definition.name), primitive))
+            .map(|(name, primitive)| (name.clone(), (name, primitive)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, _, added) = pair(&old, &new);
+        self.top_level(Element::Primitive, &removed, &added, |primitive| &primitive.definition.name);
+    }
+
+    fn records(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.records.iter()
+            .map(|record| (spec.qualify(&record.name), record))
+            .map(|(name, record)| (name.clone(), (name, record)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::Record, &removed, &added, |record| &record.name);
+
+        for ((name, old), (_, new)) in retained {
+            let usage = self.usage(name);
+            let (old, new) = (
+                old.entries.iter().map(|entry| (entry.name.as_str().to_string(), entry)).collect::<Vec<_>>(),
+                new.entries.iter().map(|entry| (entry.name.as_str().to_string(), entry)).collect::<Vec<_>>(),
+            );
+            let (removed, retained, added) = pair(&old, &new);
+            for entry in removed {
+                let impact = if usage.output && !entry.may_be_absent() { Impact::Breaking } else { Impact::Compatible };
+                let path = vec![name.clone(), entry.name.as_str().into()];
+                self.push(ChangeKind::Removed, Element::Field, path, impact, &entry.name);
+            }
+            for (old_entry, new_entry) in retained {
+                let (from, to) = (entry_string(old_entry), entry_string(new_entry));
+                if from != to {
+                    let impact = if old_entry.type_ref.to_string() != new_entry.type_ref.to_string() {
+                        Impact::Breaking
+                    } else if !old_entry.may_be_absent() && new_entry.may_be_absent() {
+                        if usage.output { Impact::Breaking } else { Impact::Compatible }
+                    } else if usage.input {
+                        Impact::Breaking
+                    } else {
+                        Impact::Compatible
+                    };
+                    let kind = ChangeKind::Changed { from: Some(from), to: Some(to) };
+                    let path = vec![name.clone(), new_entry.name.as_str().into()];
+                    self.push(kind, Element::Field, path, impact, &new_entry.type_ref.name);
+                }
+            }
+            for entry in added {
+                let impact = if usage.input && !entry.may_be_absent() { Impact::Breaking } else { Impact::Compatible };
+                let path = vec![name.clone(), entry.name.as_str().into()];
+                self.push(ChangeKind::Added, Element::Field, path, impact, &entry.name);
+            }
+        }
+    }
+
+    fn services(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.services.iter()
+            .map(|service| (spec.qualify(&service.name), service))
+            .map(|(name, service)| (name.clone(), (name, service)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::Service, &removed, &added, |service| &service.name);
+
+        for ((name, old), (_, new)) in retained {
+            let (old, new) = (
+                old.methods.iter().map(|method| (method.name.as_str().to_string(), method)).collect::<Vec<_>>(),
+                new.methods.iter().map(|method| (method.name.as_str().to_string(), method)).collect::<Vec<_>>(),
+            );
+            let (removed, retained, added) = pair(&old, &new);
+            for method in removed {
+                let path = vec![name.clone(), method.name.as_str().into()];
+                self.push(ChangeKind::Removed, Element::Method, path, Impact::Breaking, &method.name);
+            }
+            for (old_method, new_method) in retained {
+                let ports = [
+                    (Element::MethodInput, &old_method.input, &new_method.input),
+                    (Element::MethodOutput, &old_method.output, &new_method.output),
+                ];
+                for &(element, from, to) in &ports {
+                    let from = from.as_ref().map(TypeRef::to_string);
+                    let to = to.as_ref().map(TypeRef::to_string);
+                    if from != to {
+                        let kind = ChangeKind::Changed { from, to };
+                        let path = vec![name.clone(), new_method.name.as_str().into()];
+                        self.push(kind, element, path, Impact::Breaking, &new_method.name);
+                    }
+                }
+
+                // Consumers cannot handle errors they do not know about.
+                let (old_errors, new_errors) = (
+                    old_method.errors.iter().map(|error| (error.name.as_str().to_string(), error)).collect::<Vec<_>>(),
+                    new_method.errors.iter().map(|error| (error.name.as_str().to_string(), error)).collect::<Vec<_>>(),
+                );
+                let (removed, retained, added) = pair(&old_errors, &new_errors);
+                let path = |error: &TypeRef| vec![name.clone(), new_method.name.as_str().into(), error.name.as_str().into()];
+                for error in removed {
+                    self.push(ChangeKind::Removed, Element::MethodError, path(error), Impact::Compatible, &error.name);
+                }
+                for (old_error, new_error) in retained {
+                    let (from, to) = (old_error.to_string(), new_error.to_string());
+                    if from != to {
+                        let kind = ChangeKind::Changed { from: Some(from), to: Some(to) };
+                        self.push(kind, Element::MethodError, path(new_error), Impact::Breaking, &new_error.name);
+                    }
+                }
+                for error in added {
+                    self.push(ChangeKind::Added, Element::MethodError, path(error), Impact::Breaking, &error.name);
+                }
+            }
+            for method in added {
+                let path = vec![name.clone(), method.name.as_str().into()];
+                self.push(ChangeKind::Added, Element::Method, path, Impact::Compatible, &method.name);
+            }
+        }
+    }
+
+    fn implementations(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.implementations.iter()
+            .map(|implementation| (implementation_name(spec, implementation), implementation))
+            .map(|(name, implementation)| (name.clone(), (name, implementation)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::Implementation, &removed, &added, |implementation| &implementation.name);
+
+        for ((name, old), (_, new)) in retained {
+            let (old_properties, new_properties) = (
+                old.properties.iter().map(|property| (property.name.as_str().to_string(), property)).collect::<Vec<_>>(),
+                new.properties.iter().map(|property| (property.name.as_str().to_string(), property)).collect::<Vec<_>>(),
+            );
+            let (removed, retained, added) = pair(&old_properties, &new_properties);
+            for property in removed {
+                let kind = ChangeKind::Changed { from: Some(property.value.to_string()), to: None };
+                let path = vec![name.clone(), property.name.as_str().into()];
+                self.push(kind, Element::Property, path, Impact::Breaking, &new.name);
+            }
+            for (old_property, new_property) in retained {
+                let (from, to) = (old_property.value.to_string(), new_property.value.to_string());
+                if from != to {
+                    let kind = ChangeKind::Changed { from: Some(from), to: Some(to) };
+                    let path = vec![name.clone(), new_property.name.as_str().into()];
+                    self.push(kind, Element::Property, path, Impact::Breaking, &new_property.name);
+                }
+            }
+            for property in added {
+                let kind = ChangeKind::Changed { from: None, to: Some(property.value.to_string()) };
+                let path = vec![name.clone(), property.name.as_str().into()];
+                self.push(kind, Element::Property, path, Impact::Breaking, &property.name);
+            }
+
+            let (old_methods, new_methods) = (
+                old.methods.iter().map(|method| (method.name.as_str().to_string(), method)).collect::<Vec<_>>(),
+                new.methods.iter().map(|method| (method.name.as_str().to_string(), method)).collect::<Vec<_>>(),
+            );
+            let (removed, retained, added) = pair(&old_methods, &new_methods);
+            for method in removed {
+                let path = vec![name.clone(), method.name.as_str().into()];
+                self.push(ChangeKind::Removed, Element::Route, path, Impact::Breaking, &method.name);
+            }
+            for (old_method, new_method) in retained {
+                let (from, to) = (entries_string(&old_method.data), entries_string(&new_method.data));
+                if from != to {
+                    let kind = ChangeKind::Changed { from: Some(from), to: Some(to) };
+                    let path = vec![name.clone(), new_method.name.as_str().into()];
+                    self.push(kind, Element::Route, path, Impact::Breaking, &new_method.name);
+                }
+            }
+            for method in added {
+                let path = vec![name.clone(), method.name.as_str().into()];
+                self.push(ChangeKind::Added, Element::Route, path, Impact::Compatible, &method.name);
+            }
+        }
+    }
+
+    fn systems(&mut self) {
+        let index = |spec: &'b Specification<'a>| spec.systems.iter()
+            .map(|system| (spec.qualify(&system.name), system))
+            .map(|(name, system)| (name.clone(), (name, system)))
+            .collect::<Vec<_>>();
+        let (old, new) = (index(self.old), index(self.new));
+        let (removed, retained, added) = pair(&old, &new);
+        self.top_level(Element::System, &removed, &added, |system| &system.name);
+
+        for ((name, old), (_, new)) in retained {
+            // A system requiring more services, or providing fewer, may no
+            // longer fit into existing deployments.
+            let refs = [
+                (Element::SystemConsumes, &old.consumes, &new.consumes, Impact::Compatible, Impact::Breaking),
+                (Element::SystemProduces, &old.produces, &new.produces, Impact::Breaking, Impact::Compatible),
+            ];
+            for &(element, old_refs, new_refs, removal, addition) in &refs {
+                let (old_refs, new_refs) = (
+                    old_refs.iter().map(|service_ref| (service_ref.name.as_str().to_string(), service_ref)).collect::<Vec<_>>(),
+                    new_refs.iter().map(|service_ref| (service_ref.name.as_str().to_string(), service_ref)).collect::<Vec<_>>(),
+                );
+                let (removed, _, added) = pair(&old_refs, &new_refs);
+                for service_ref in removed {
+                    let path = vec![name.clone(), service_ref.name.as_str().into()];
+                    self.push(ChangeKind::Removed, element, path, removal, &new.name);
+                }
+                for service_ref in added {
+                    let path = vec![name.clone(), service_ref.name.as_str().into()];
+                    self.push(ChangeKind::Added, element, path, addition, &service_ref.name);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use crate::project::Version;
+    use crate::spec::parser;
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let corpus = |body: &str| Corpus {
+            texts: vec![Text { name: "test.ahfs".into(), body: body.into() }],
+        };
+        let old = corpus(concat!(
+            "service S {\n",
+            "    method Get(Query): Answer throws Missing;\n",
+            "    method Put(Answer);\n",
+            "}\n",
+            "implement S using HTTP/JSON {\n",
+            "    method Get { Method: \"GET\", Path: \"/\" }\n",
+            "    method Put { Method: \"PUT\", Path: \"/\" }\n",
+            "}\n",
+            "record Query { Name: String }\n",
+            "record Answer { Status: Status, Value: U16, Ratio: Ratio }\n",
+            "enum Status { Ok, Error }\n",
+            "type Ratio = Float32 where 0..=1;\n",
+            "type Unused = String;\n",
+            "record Missing { Name: String }\n",
+            "record Denied { Reason: String }\n",
+        ));
+        let new = corpus(concat!(
+            "service S {\n",
+            "    method Get(Query): Answer throws Denied;\n",
+            "    method Put(Answer);\n",
+            "    method Delete(Query);\n",
+            "}\n",
+            "implement S using HTTP/JSON {\n",
+            "    method Get { Method: \"GET\", Path: \"/\" }\n",
+            "    method Put { Method: \"POST\", Path: \"/\" }\n",
+            "    method Delete { Method: \"DELETE\", Path: \"/\" }\n",
+            "}\n",
+            "record Query { Name: String, Limit: U16, Offset?: U32 }\n",
+            "record Answer { Status: Status, Value: U32, Ratio: Ratio }\n",
+            "enum Status { Ok, Error, Unknown }\n",
+            "type Ratio = Float32 where 0..;\n",
+            "record Missing { Name: String }\n",
+            "record Denied { Reason: String }\n",
+        ));
+        let (old, new) = (parser::parse(&old).unwrap(), parser::parse(&new).unwrap());
+        let diff = Diff::new(&old, &new);
+
+        let changes = diff.changes.iter()
+            .map(|change| (change.path.join("."), change.element, change.impact))
+            .collect::<Vec<_>>();
+        assert_eq!(changes, vec![
+            ("Unused".to_string(), Element::Alias, Impact::Breaking),
+            ("Ratio".to_string(), Element::Alias, Impact::Breaking),
+            ("Status.Unknown".to_string(), Element::EnumVariant, Impact::Breaking),
+            ("Query.Limit".to_string(), Element::Field, Impact::Breaking),
+            ("Query.Offset".to_string(), Element::Field, Impact::Compatible),
+            ("Answer.Value".to_string(), Element::Field, Impact::Breaking),
+            ("S.Get.Missing".to_string(), Element::MethodError, Impact::Compatible),
+            ("S.Get.Denied".to_string(), Element::MethodError, Impact::Breaking),
+            ("S.Delete".to_string(), Element::Method, Impact::Compatible),
+            ("S[HTTP/JSON].Put".to_string(), Element::Route, Impact::Breaking),
+            ("S[HTTP/JSON].Delete".to_string(), Element::Route, Impact::Compatible),
+        ]);
+        assert_eq!(
+            diff.changes[5].to_string(),
+            "Changed field `Answer.Value` from `U16` to `U32`.",
+        );
+        assert_eq!(
+            diff.changes[1].to_string(),
+            "Changed alias `Ratio` from `Float32 where 0..=1` to `Float32 where 0..`.",
+        );
+
+        let version = Version { major: 4, minor: 2, patch: 0 };
+        assert_eq!(diff.recommend_version(&version), Version { major: 5, minor: 0, patch: 0 });
+        assert_eq!(
+            Diff::new(&old, &old).recommend_version(&version),
+            Version { major: 4, minor: 2, patch: 1 },
+        );
+    }
+}
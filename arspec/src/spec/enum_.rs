@@ -1,5 +1,6 @@
 use arspec_parser::Span;
-use super::{Attribute, VerificationError, verify};
+use crate::payload::Data;
+use super::{Attribute, Value, VerificationError, verify};
 
 /// An enumerator type definition.
 #[derive(Debug)]
@@ -25,6 +26,18 @@ impl<'a> Enum<'a> {
         }
     }
 
+    /// Whether the variants of this enum are represented by integers rather
+    /// than by strings, which is the case if they have integer values.
+    pub fn is_integer(&self) -> bool {
+        self.variants.first()
+            .is_some_and(|variant| matches!(variant.value, Some(Value::Integer(_))))
+    }
+
+    /// Finds the variant represented by `data`, if any.
+    pub fn variant_of(&self, data: &Data) -> Option<&EnumVariant<'a>> {
+        self.variants.iter().find(|variant| variant.wire_value() == *data)
+    }
+
     /// Asserts that this enum has no internal inconsistencies.
     pub fn verify(&self) -> Result<(), VerificationError> {
         verify::find_duplicate(&self.variants)
@@ -32,7 +45,25 @@ impl<'a> Enum<'a> {
                 original: dup.original.name.to_excerpt(),
                 duplicate: dup.duplicate.name.to_excerpt(),
             }))
-            .unwrap_or(Ok(()))
+            .unwrap_or(Ok(()))?;
+
+        let is_integer = self.is_integer();
+        for (index, variant) in self.variants.iter().enumerate() {
+            if is_integer != matches!(variant.value, Some(Value::Integer(_))) {
+                return Err(VerificationError::EnumVariantValueMismatch {
+                    variant: variant.value_span().to_excerpt(),
+                    expected: if is_integer { "an integer" } else { "a string" },
+                });
+            }
+            let value = variant.wire_value();
+            if let Some(original) = self.variants[..index].iter().find(|other| other.wire_value() == value) {
+                return Err(VerificationError::EnumVariantValueDuplicate {
+                    original: original.value_span().to_excerpt(),
+                    duplicate: variant.value_span().to_excerpt(),
+                });
+            }
+        }
+        Ok(())
     }
 }
 
@@ -49,6 +80,10 @@ pub struct EnumVariant<'a> {
     /// Name of enum variant.
     pub name: Span<'a>,
 
+    /// Integer or string representing the variant in payloads, if other
+    /// than its name.
+    pub value: Option<Value<'a>>,
+
     /// Any attributes.
     pub attributes: Vec<Attribute<'a>>,
 }
@@ -59,9 +94,27 @@ impl<'a> EnumVariant<'a> {
     pub fn new(name: Span<'a>, attributes: Vec<Attribute<'a>>) -> Self {
         EnumVariant {
             name,
+            value: None,
             attributes,
         }
     }
+
+    /// Payload representing this variant, which is its value, if any, or
+    /// its name as a string otherwise.
+    pub fn wire_value(&self) -> Data {
+        match self.value {
+            Some(ref value) => value.into(),
+            None => Data::String(self.name.as_str().into()),
+        }
+    }
+
+    /// Span of the value of this variant, or of its name if it has none.
+    fn value_span(&self) -> &Span<'a> {
+        match self.value {
+            Some(Value::Integer(ref span)) | Some(Value::String(ref span)) => span,
+            _ => &self.name,
+        }
+    }
 }
 
 impl<'a> AsRef<str> for EnumVariant<'a> {
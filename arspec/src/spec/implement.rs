@@ -1,5 +1,5 @@
 use arspec_parser::Span;
-use super::{Attribute, Property, Specification, Value, VerificationError};
+use super::{Attribute, Property, Service, ServiceMethod, Specification, TypeRef, Value, VerificationError};
 
 /// Specifies how to implement a named [`Service`][srv].
 ///
@@ -44,9 +44,49 @@ impl<'a> Implement<'a> {
         }
     }
 
+    /// Value of the `BasePath` property of this implementation, prefixing
+    /// the paths of all its HTTP routes, or an empty string if not given.
+    pub fn base_path(&self) -> String {
+        self.properties.iter()
+            .find(|property| property.name.as_str() == "BasePath")
+            .and_then(|property| property.value.as_string())
+            .unwrap_or_default()
+    }
+
+    /// Collects the HTTP routes of this implementation of `service`.
+    ///
+    /// Implementation methods lacking `Method` or `Path` data, or not
+    /// matching any method of `service`, are ignored.
+    pub fn routes<'b>(&'b self, service: &'b Service<'a>) -> Vec<Route<'a, 'b>> {
+        let base_path = self.base_path();
+        let mut routes = Vec::with_capacity(self.methods.len());
+        for method in &self.methods {
+            let data = |key: &str| method.data.iter()
+                .find(|(name, _)| name.as_str() == key)
+                .and_then(|(_, value)| value.as_string());
+            let service_method = service.methods.iter()
+                .find(|service_method| service_method.name == method.name);
+            if let (Some(http_method), Some(path), Some(service_method)) = (data("Method"), data("Path"), service_method) {
+                let errors = service_method.errors.iter()
+                    .filter_map(|error| match method.error_code(error.name.as_str()) {
+                        Some(Value::Integer(status)) => Some((error, status.as_str().parse().ok()?)),
+                        _ => None,
+                    })
+                    .collect();
+                routes.push(Route {
+                    method: http_method.to_ascii_uppercase(),
+                    path: format!("{}{}", base_path.trim_end_matches('/'), path),
+                    service_method,
+                    errors,
+                });
+            }
+        }
+        routes
+    }
+
     pub fn verify(&self, spec: &Specification) -> Result<(), VerificationError> {
-        let service = spec.services.iter()
-            .find(|service| self.name == service.name)
+        spec.verify_service_unambiguous(&self.name)?;
+        let service = spec.resolve_service(&self.name)
             .ok_or_else(|| VerificationError::NoSuchServiceToImplement {
                 service: self.name.to_excerpt(),
             })?;
@@ -87,7 +127,64 @@ impl<'a> Implement<'a> {
             _ => Err(VerificationError::UnknownServiceEncoding {
                 encoding: self.encoding.to_excerpt(),
             })
+        }?;
+
+        for method in &self.methods {
+            let service_method = service.methods.iter()
+                .find(|service_method| service_method.name == method.name)
+                .unwrap();
+            self.verify_errors(method, service_method)?;
         }
+        Ok(())
+    }
+
+    /// Asserts that every error of `service_method` is mapped to a distinct
+    /// error code by `method`, and that no other errors are mapped.
+    fn verify_errors(&self, method: &ImplementMethod<'a>, service_method: &ServiceMethod<'a>) -> Result<(), VerificationError> {
+        let (codes, expected, is_valid): (&str, &str, fn(&Value) -> bool) = match self.protocol.as_str() {
+            "COAP" => ("response codes", "a response code from \"4.00\" to \"5.31\"", |value| {
+                value.as_string()
+                    .and_then(|code| {
+                        let (class, detail) = code.split_once('.')?;
+                        Some(matches!(class, "4" | "5") && detail.len() == 2 && detail.parse::<u8>().ok()? <= 31)
+                    })
+                    .unwrap_or(false)
+            }),
+            "HTTP" => ("status codes", "a status code from 400 to 599", |value| match value {
+                Value::Integer(span) => span.as_str().parse::<u16>().is_ok_and(|code| (400..=599).contains(&code)),
+                _ => false,
+            }),
+            _ => ("topics", "a topic", |value| value.as_string().is_some_and(|topic| !topic.is_empty())),
+        };
+        let invalid = |error: &Span, reason: String| VerificationError::ImplementErrorInvalid {
+            error: error.to_excerpt(),
+            reason,
+        };
+        let errors = match method.data.iter().find(|(name, _)| name.as_str() == "Errors") {
+            Some((_, Value::Map(errors))) => &errors[..],
+            Some((name, _)) => return Err(invalid(name, format!("expected map of error names to {}", codes))),
+            None => &[],
+        };
+        for (index, (name, code)) in errors.iter().enumerate() {
+            if service_method.error(name.as_str()).is_none() {
+                return Err(invalid(name, format!("`{}` is not an error of method `{}`", name.as_str(), method.name.as_str())));
+            }
+            if !is_valid(code) {
+                return Err(invalid(name, format!("expected {}, found `{}`", expected, code)));
+            }
+            if let Some((other, _)) = errors[..index].iter().find(|(_, other)| other.to_string() == code.to_string()) {
+                return Err(invalid(name, format!("`{}` is already used by error `{}`", code, other.as_str())));
+            }
+        }
+        for error in &service_method.errors {
+            if !errors.iter().any(|(name, _)| *name == error.name) {
+                return Err(VerificationError::ImplementErrorNotMapped {
+                    error: error.name.to_excerpt(),
+                    implementation: method.name.to_excerpt(),
+                });
+            }
+        }
+        Ok(())
     }
 }
 
@@ -118,4 +215,60 @@ impl<'a> ImplementMethod<'a> {
             attributes,
         }
     }
-}
\ No newline at end of file
+
+    /// Looks up the code of named error, as given by the `Errors` data of
+    /// this method, which maps error names to HTTP status codes, CoAP
+    /// response codes or MQTT topics, depending on the protocol used.
+    pub fn error_code(&self, name: &str) -> Option<&Value<'a>> {
+        match self.data.iter().find(|(key, _)| key.as_str() == "Errors") {
+            Some((_, Value::Map(errors))) => errors.iter()
+                .find(|(key, _)| key.as_str() == name)
+                .map(|(_, code)| code),
+            _ => None,
+        }
+    }
+}
+
+/// A service method made available by an HTTP [`Implement`][imp].
+///
+/// [imp]: struct.Implement.html
+#[derive(Debug)]
+pub struct Route<'a: 'b, 'b> {
+    /// HTTP method, such as `POST`.
+    pub method: String,
+
+    /// Path, including any base path of the implementation, such as
+    /// `/registry/{id}`.
+    pub path: String,
+
+    /// Implemented service method.
+    pub service_method: &'b ServiceMethod<'a>,
+
+    /// Errors of the implemented service method and the HTTP status codes
+    /// they are mapped to.
+    pub errors: Vec<(&'b TypeRef<'a>, u16)>,
+}
+
+impl<'a: 'b, 'b> Route<'a, 'b> {
+    /// Segments of route path following its leading `/`.
+    pub fn segments(&self) -> impl Iterator<Item = RouteSegment<'_>> {
+        self.path.trim_start_matches('/').split('/')
+            .map(|segment| match segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')) {
+                Some(name) => RouteSegment::Parameter(name),
+                None => RouteSegment::Literal(segment),
+            })
+    }
+}
+
+/// An HTTP [`Route`][rte] path segment.
+///
+/// [rte]: struct.Route.html
+#[derive(Debug, Eq, PartialEq)]
+pub enum RouteSegment<'b> {
+    /// A segment that must match exactly.
+    Literal(&'b str),
+
+    /// A segment enclosed in braces, such as `{id}`, that matches any one
+    /// segment.
+    Parameter(&'b str),
+}
@@ -1,34 +1,46 @@
 pub mod parser;
 
+mod alias;
 mod attribute;
+mod diff;
 mod enum_;
 mod implement;
+mod pattern;
 mod primitive;
 mod property;
-mod record;
+pub(crate) mod record;
+pub(crate) mod scope;
 mod service;
 mod system;
 mod type_ref;
 mod value;
 mod verify;
 
-pub use self::attribute::Attribute;
+pub use self::alias::{Alias, Constraint};
+pub use self::attribute::{Attribute, doc_of};
+pub use self::diff::{Change, ChangeKind, Diff, Element, Impact};
 pub use self::enum_::{Enum, EnumVariant};
-pub use self::implement::{Implement, ImplementMethod};
+pub use self::implement::{Implement, ImplementMethod, Route, RouteSegment};
+pub use self::pattern::{Pattern, PatternError};
 pub use self::primitive::Primitive;
 pub use self::property::Property;
 pub use self::record::{Record, RecordEntry};
+pub use self::scope::{Import, normalize, Package, Scope, Use};
 pub use self::service::{Service, ServiceMethod, ServiceRef};
 pub use self::system::System;
 pub use self::type_ref::TypeRef;
 pub use self::value::Value;
 
-use arspec_parser::Excerpt;
+use arspec_parser::{Excerpt, Span, Text};
 use std::fmt;
+use std::ptr;
 
 /// An Arrowhead Framework specification collection.
 #[derive(Debug, Default)]
 pub struct Specification<'a> {
+    /// Type alias definitions.
+    pub aliases: Vec<Alias<'a>>,
+
     /// Enumerator type definitions.
     pub enums: Vec<Enum<'a>>,
 
@@ -41,6 +53,9 @@ pub struct Specification<'a> {
     /// Record type definitions.
     pub records: Vec<Record<'a>>,
 
+    /// Package, import and use declarations of each source text.
+    pub scopes: Vec<Scope<'a>>,
+
     /// Abstract service definitions.
     pub services: Vec<Service<'a>>,
 
@@ -49,51 +64,296 @@ pub struct Specification<'a> {
 }
 
 impl<'a> Specification<'a> {
+    /// Looks up [`Scope`][scp] of given source `text`, if it has one.
+    ///
+    /// [scp]: struct.Scope.html
+    pub fn scope_of(&self, text: &Text) -> Option<&Scope<'a>> {
+        self.scopes.iter().find(|scope| ptr::eq(scope.source, text))
+    }
+
+    /// Name of package of given source `text`, or `""` if it belongs to the
+    /// root package.
+    #[inline]
+    pub fn package_of(&self, text: &Text) -> &'a str {
+        self.scope_of(text).map_or("", |scope| scope.package_name())
+    }
+
+    /// Qualifies `name` with the name of the package of its source text,
+    /// unless it belongs to the root package.
+    pub fn qualify(&self, name: &Span) -> String {
+        match self.package_of(name.source) {
+            "" => name.as_str().into(),
+            package => format!("{}::{}", package, name.as_str()),
+        }
+    }
+
+    /// Resolves type alias `name`, as referred to from the source text of the
+    /// provided name.
+    #[inline]
+    pub fn resolve_alias(&self, name: &Span<'a>) -> Option<&Alias<'a>> {
+        self.resolve(&self.aliases, name, |alias| &alias.name)
+    }
+
+    /// Follows `type_ref` through any aliases it refers to, returning the
+    /// first type reference not referring to an alias, as well as the aliases
+    /// followed to reach it, starting with the one referred to by `type_ref`.
+    ///
+    /// Cyclic aliases are only followed until any alias would be repeated.
+    pub fn unalias<'b>(&'b self, type_ref: &'b TypeRef<'a>) -> (&'b TypeRef<'a>, Vec<&'b Alias<'a>>) {
+        let mut type_ref = type_ref;
+        let mut aliases: Vec<&'b Alias<'a>> = Vec::new();
+        while let Some(alias) = self.resolve_alias(&type_ref.name) {
+            if !type_ref.params.is_empty() || aliases.iter().any(|other| ptr::eq(*other, alias)) {
+                break;
+            }
+            aliases.push(alias);
+            type_ref = &alias.definition;
+        }
+        (type_ref, aliases)
+    }
+
+    /// Resolves enum type `name`, as referred to from the source text of the
+    /// provided name.
+    #[inline]
+    pub fn resolve_enum(&self, name: &Span<'a>) -> Option<&Enum<'a>> {
+        self.resolve(&self.enums, name, |enum_| &enum_.name)
+    }
+
+    /// Resolves primitive type `name`, as referred to from the source text of
+    /// the provided name.
+    #[inline]
+    pub fn resolve_primitive(&self, name: &Span<'a>) -> Option<&Primitive<'a>> {
+        self.resolve(&self.primitives, name, |primitive| &primitive.definition.name)
+    }
+
+    /// Resolves record type `name`, as referred to from the source text of the
+    /// provided name.
+    #[inline]
+    pub fn resolve_record(&self, name: &Span<'a>) -> Option<&Record<'a>> {
+        self.resolve(&self.records, name, |record| &record.name)
+    }
+
+    /// Resolves service `name`, as referred to from the source text of the
+    /// provided name.
+    #[inline]
+    pub fn resolve_service(&self, name: &Span<'a>) -> Option<&Service<'a>> {
+        self.resolve(&self.services, name, |service| &service.name)
+    }
+
+    /// Resolves system `name`, as referred to from the source text of the
+    /// provided name.
+    #[inline]
+    pub fn resolve_system(&self, name: &Span<'a>) -> Option<&System<'a>> {
+        self.resolve(&self.systems, name, |system| &system.name)
+    }
+
+    /// Finds the item in `items` named `name` that is visible from the source
+    /// text of `name`.
+    ///
+    /// Items in the same package as the name take precedence over used items,
+    /// which take precedence over imported items, which in turn take
+    /// precedence over items in the root package. Items in other packages are
+    /// not visible.
+    fn resolve<'b, E>(&'b self, items: &'b [E], name: &Span<'a>, name_of: fn(&E) -> &Span<'a>) -> Option<&'b E> {
+        self.candidates(items, name, name_of).into_iter()
+            .min_by_key(|&(rank, _)| rank)
+            .map(|(_, item)| item)
+    }
+
+    /// Finds the items in `items` named `name` that are visible from the
+    /// source text of `name`, each paired with its precedence, where lower
+    /// ranks take precedence over higher.
+    fn candidates<'b, E>(&'b self, items: &'b [E], name: &Span<'a>, name_of: fn(&E) -> &Span<'a>) -> Vec<(u8, &'b E)> {
+        let scope = self.scope_of(name.source);
+        let package = scope.map_or("", |scope| scope.package_name());
+
+        items.iter()
+            .filter(|item| name_of(item).as_str() == name.as_str())
+            .filter_map(|item| {
+                let source = name_of(item).source;
+                let item_package = self.package_of(source);
+                let rank = if item_package == package {
+                    0
+                } else if scope.is_some_and(|scope| scope.uses.iter().any(|use_| {
+                    use_.package.as_str() == item_package && use_.name == *name
+                })) {
+                    1
+                } else if scope.is_some_and(|scope| scope.imports_text(source)) {
+                    2
+                } else if item_package.is_empty() {
+                    3
+                } else {
+                    return None;
+                };
+                Some((rank, item))
+            })
+            .collect()
+    }
+
+    /// Asserts that `name` does not resolve to items of more than one
+    /// package in `items`, which happens if equally named items of different
+    /// packages are both used or both imported.
+    fn verify_unambiguous<E>(&self, items: &[E], name: &Span<'a>, name_of: fn(&E) -> &Span<'a>) -> Result<(), VerificationError> {
+        let candidates = self.candidates(items, name, name_of);
+        let rank = match candidates.iter().map(|&(rank, _)| rank).min() {
+            Some(rank) => rank,
+            None => return Ok(()),
+        };
+        let candidates = candidates.into_iter()
+            .filter(|&(other, _)| other == rank)
+            .map(|(_, item)| name_of(item))
+            .collect::<Vec<_>>();
+        let package = self.package_of(candidates[0].source);
+        if candidates.iter().all(|candidate| self.package_of(candidate.source) == package) {
+            return Ok(());
+        }
+        Err(VerificationError::DefinitionAmbiguous {
+            name: name.to_excerpt(),
+            definitions: candidates.iter().map(|candidate| candidate.to_excerpt()).collect(),
+        })
+    }
+
+    /// Asserts that type `name` does not refer to types of more than one
+    /// package.
+    pub(crate) fn verify_type_unambiguous(&self, name: &Span<'a>) -> Result<(), VerificationError> {
+        self.verify_unambiguous(&self.aliases, name, |alias| &alias.name)?;
+        self.verify_unambiguous(&self.enums, name, |enum_| &enum_.name)?;
+        self.verify_unambiguous(&self.primitives, name, |primitive| &primitive.definition.name)?;
+        self.verify_unambiguous(&self.records, name, |record| &record.name)
+    }
+
+    /// Asserts that service `name` does not refer to services of more than
+    /// one package.
+    pub(crate) fn verify_service_unambiguous(&self, name: &Span<'a>) -> Result<(), VerificationError> {
+        self.verify_unambiguous(&self.services, name, |service| &service.name)
+    }
+
+    /// Groups `items` by the packages of the source texts they are defined in.
+    fn group_by_package<'b, E>(&self, items: &'b [E], name_of: fn(&E) -> &Span<'a>) -> Vec<Vec<&'b E>> {
+        let mut groups: Vec<(&str, Vec<&'b E>)> = Vec::new();
+        for item in items {
+            let package = self.package_of(name_of(item).source);
+            match groups.iter_mut().find(|(name, _)| *name == package) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((package, vec![item])),
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+
     /// Performs _naive_ specification verification.
     ///
+    /// Type and service names are only required to be unique within their
+    /// packages.
+    ///
     /// TODO: Make this much more sophisticated. Add more passes.
     pub fn verify(&self) -> Result<(), VerificationError> {
+        for alias in &self.aliases {
+            alias.verify(self)?;
+        }
+        for aliases in self.group_by_package(&self.aliases, |alias| &alias.name) {
+            verify::find_duplicate(&aliases)
+                .map(|dup| Err(VerificationError::AliasNameDuplicate {
+                    duplicate: dup.duplicate.name.to_excerpt(),
+                    original: dup.original.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
+
         for enum_ in &self.enums {
             enum_.verify()?;
         }
-        verify::find_duplicate(&self.enums)
-            .map(|dup| Err(VerificationError::EnumNameDuplicate {
-                duplicate: dup.duplicate.name.to_excerpt(),
-                original: dup.original.name.to_excerpt(),
-            }))
-            .unwrap_or(Ok(()))?;
+        for enums in self.group_by_package(&self.enums, |enum_| &enum_.name) {
+            verify::find_duplicate(&enums)
+                .map(|dup| Err(VerificationError::EnumNameDuplicate {
+                    duplicate: dup.duplicate.name.to_excerpt(),
+                    original: dup.original.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
 
         for implementation in &self.implementations {
             implementation.verify(self)?;
         }
 
-        verify::find_duplicate(&self.primitives)
-            .map(|dup| Err(VerificationError::PrimitiveNameDuplicate {
-                duplicate: dup.duplicate.definition.name.to_excerpt(),
-                original: dup.original.definition.name.to_excerpt(),
-            }))
-            .unwrap_or(Ok(()))?;
-
-        verify::find_duplicate(&self.records)
-            .map(|dup| Err(VerificationError::RecordNameDuplicate {
-                duplicate: dup.duplicate.name.to_excerpt(),
-                original: dup.original.name.to_excerpt(),
-            }))
-            .unwrap_or(Ok(()))?;
-
-        verify::find_duplicate(&self.services)
-            .map(|dup| Err(VerificationError::ServiceNameDuplicate {
-                duplicate: dup.duplicate.name.to_excerpt(),
-                original: dup.original.name.to_excerpt(),
-            }))
-            .unwrap_or(Ok(()))?;
-
-        verify::find_duplicate(&self.systems)
-            .map(|dup| Err(VerificationError::SystemNameDuplicate {
-                duplicate: dup.duplicate.name.to_excerpt(),
-                original: dup.original.name.to_excerpt(),
-            }))
-            .unwrap_or(Ok(()))?;
+        for primitives in self.group_by_package(&self.primitives, |primitive| &primitive.definition.name) {
+            verify::find_duplicate(&primitives)
+                .map(|dup| Err(VerificationError::PrimitiveNameDuplicate {
+                    duplicate: dup.duplicate.definition.name.to_excerpt(),
+                    original: dup.original.definition.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
+
+        for record in &self.records {
+            record.verify(self)?;
+        }
+        for records in self.group_by_package(&self.records, |record| &record.name) {
+            verify::find_duplicate(&records)
+                .map(|dup| Err(VerificationError::RecordNameDuplicate {
+                    duplicate: dup.duplicate.name.to_excerpt(),
+                    original: dup.original.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
+
+        for scope in &self.scopes {
+            for use_ in &scope.uses {
+                let is_defined = {
+                    let is_match = |name: &Span<'a>| {
+                        *name == use_.name && self.package_of(name.source) == use_.package.as_str()
+                    };
+                    self.aliases.iter().any(|alias| is_match(&alias.name))
+                        || self.enums.iter().any(|enum_| is_match(&enum_.name))
+                        || self.primitives.iter().any(|primitive| is_match(&primitive.definition.name))
+                        || self.records.iter().any(|record| is_match(&record.name))
+                        || self.services.iter().any(|service| is_match(&service.name))
+                        || self.systems.iter().any(|system| is_match(&system.name))
+                };
+                if !is_defined {
+                    return Err(VerificationError::NoSuchDefinitionToUse {
+                        package: use_.package.to_excerpt(),
+                        name: use_.name.to_excerpt(),
+                    });
+                }
+            }
+        }
+
+        for method in self.services.iter().flat_map(|service| service.methods.iter()) {
+            for type_ref in method.input.iter().chain(method.output.iter()).chain(method.errors.iter()) {
+                record::verify_arity(self, type_ref, &[])?;
+            }
+            for (index, error) in method.errors.iter().enumerate() {
+                if let Some(original) = method.errors[..index].iter().find(|other| other.name == error.name) {
+                    return Err(VerificationError::MethodErrorDuplicate {
+                        duplicate: error.name.to_excerpt(),
+                        original: original.name.to_excerpt(),
+                    });
+                }
+            }
+        }
+        for system in &self.systems {
+            for service_ref in system.consumes.iter().chain(system.produces.iter()) {
+                self.verify_service_unambiguous(&service_ref.name)?;
+            }
+        }
+        for services in self.group_by_package(&self.services, |service| &service.name) {
+            verify::find_duplicate(&services)
+                .map(|dup| Err(VerificationError::ServiceNameDuplicate {
+                    duplicate: dup.duplicate.name.to_excerpt(),
+                    original: dup.original.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
+
+        for systems in self.group_by_package(&self.systems, |system| &system.name) {
+            verify::find_duplicate(&systems)
+                .map(|dup| Err(VerificationError::SystemNameDuplicate {
+                    duplicate: dup.duplicate.name.to_excerpt(),
+                    original: dup.original.name.to_excerpt(),
+                }))
+                .unwrap_or(Ok(()))?;
+        }
 
         Ok(())
     }
@@ -101,15 +361,32 @@ impl<'a> Specification<'a> {
 
 #[derive(Debug)]
 pub enum VerificationError {
+    AliasConstraintInvalid { constraint: Excerpt, reason: String },
+    AliasCycle { alias: Excerpt },
+    AliasNameDuplicate { duplicate: Excerpt, original: Excerpt },
+    DefinitionAmbiguous { name: Excerpt, definitions: Vec<Excerpt> },
     EnumNameDuplicate { duplicate: Excerpt, original: Excerpt },
     EnumVariantDuplicate { duplicate: Excerpt, original: Excerpt },
+    EnumVariantValueDuplicate { duplicate: Excerpt, original: Excerpt },
+    EnumVariantValueMismatch { variant: Excerpt, expected: &'static str },
+    ImplementErrorInvalid { error: Excerpt, reason: String },
+    ImplementErrorNotMapped { error: Excerpt, implementation: Excerpt },
     InterfaceNotImplemented { interface: Excerpt, implementation: Excerpt },
+    MethodErrorDuplicate { duplicate: Excerpt, original: Excerpt },
+    NoSuchDefinitionToUse { package: Excerpt, name: Excerpt },
     NoSuchInterfaceToImplement { service: Excerpt, interface: Excerpt },
+    NoSuchRecordToExtend { base: Excerpt },
     NoSuchServiceToImplement { service: Excerpt },
+    NoSuchType { type_ref: Excerpt },
     PrimitiveNameDuplicate { duplicate: Excerpt, original: Excerpt },
+    RecordBaseCycle { record: Excerpt },
+    RecordEntryDefaultInvalid { entry: Excerpt, reason: String },
+    RecordEntryDuplicate { duplicate: Excerpt, original: Excerpt },
+    RecordEntryXmlInvalid { entry: Excerpt, reason: String },
     RecordNameDuplicate { duplicate: Excerpt, original: Excerpt },
     ServiceNameDuplicate { duplicate: Excerpt, original: Excerpt },
     SystemNameDuplicate { duplicate: Excerpt, original: Excerpt },
+    TypeParameterCountMismatch { type_ref: Excerpt, expected: usize },
     UnknownServiceEncoding { encoding: Excerpt },
     UnknownServiceProtocol { protocol: Excerpt },
 }
@@ -117,12 +394,73 @@ pub enum VerificationError {
 impl<'a> fmt::Display for VerificationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         return match self {
+            &VerificationError::AliasConstraintInvalid { ref constraint, ref reason } => {
+                write!(f, "Invalid constraint of alias, {}.\n{}", reason, constraint)
+            }
+            &VerificationError::AliasCycle { ref alias } => {
+                write!(f, "Alias refers to itself, directly or via other aliases.\n{}", alias)
+            }
+            &VerificationError::AliasNameDuplicate { ref duplicate, ref original } => {
+                write_name_duplicate_message(f, "Alias", duplicate, original)
+            }
+            &VerificationError::DefinitionAmbiguous { ref name, ref definitions } => {
+                write!(
+                    f,
+                    concat!(
+                        "Ambiguous reference to `{}`, which is defined by more than one used or imported package.\n",
+                        "Reference located at:\n",
+                        "{}\n",
+                        "Definitions located at:",
+                    ),
+                    name.as_str(), name,
+                )?;
+                for definition in definitions {
+                    write!(f, "\n{}", definition)?;
+                }
+                Ok(())
+            }
             &VerificationError::EnumNameDuplicate { ref duplicate, ref original } => {
                 write_name_duplicate_message(f, "Enum", duplicate, original)
             }
             &VerificationError::EnumVariantDuplicate { ref duplicate, ref original } => {
                 write_name_duplicate_message(f, "Enum variant", duplicate, original)
             }
+            &VerificationError::EnumVariantValueDuplicate { ref duplicate, ref original } => {
+                write!(
+                    f,
+                    concat!(
+                        "Enum variant value `{}` is already used.\n",
+                        "Duplicate located at:\n",
+                        "{}\n",
+                        "Original located at:\n",
+                        "{}",
+                    ),
+                    original.as_str(), duplicate, original,
+                )
+            }
+            &VerificationError::EnumVariantValueMismatch { ref variant, ref expected } => {
+                write!(
+                    f,
+                    "Enum variant value must be {}, like those of other variants.\n{}",
+                    expected, variant,
+                )
+            }
+            &VerificationError::ImplementErrorInvalid { ref error, ref reason } => {
+                write!(f, "Invalid error mapping of method implementation, {}.\n{}", reason, error)
+            }
+            &VerificationError::ImplementErrorNotMapped { ref error, ref implementation } => {
+                write!(
+                    f,
+                    concat!(
+                        "Method error not mapped by implementation.\n",
+                        "The following method error is specified:\n",
+                        "{}\n",
+                        "That error is, however, not mapped by the `Errors` of this implementation:\n",
+                        "{}",
+                    ),
+                    error, implementation,
+                )
+            }
             &VerificationError::InterfaceNotImplemented { ref interface, ref implementation } => {
                 write!(
                     f,
@@ -136,6 +474,22 @@ impl<'a> fmt::Display for VerificationError {
                     interface, implementation,
                 )
             }
+            &VerificationError::MethodErrorDuplicate { ref duplicate, ref original } => {
+                write_name_duplicate_message(f, "Method error", duplicate, original)
+            }
+            &VerificationError::NoSuchDefinitionToUse { ref package, ref name } => {
+                write!(
+                    f,
+                    concat!(
+                        "Cannot use non-existing definition.\n",
+                        "No definition with the following name:\n",
+                        "{}\n",
+                        "Exists in the following package:\n",
+                        "{}",
+                    ),
+                    name, package,
+                )
+            }
             &VerificationError::NoSuchInterfaceToImplement { ref service, ref interface } => {
                 write!(
                     f,
@@ -149,12 +503,30 @@ impl<'a> fmt::Display for VerificationError {
                     interface, service,
                 )
             }
+            &VerificationError::NoSuchRecordToExtend { ref base } => {
+                write!(f, "Cannot extend non-existing record.\n{}", base)
+            }
             &VerificationError::NoSuchServiceToImplement { ref service } => {
                 write!(f, "Cannot implement non-existing service.\n{}", service)
             }
+            &VerificationError::NoSuchType { ref type_ref } => {
+                write!(f, "Cannot refer to non-existing type.\n{}", type_ref)
+            }
             &VerificationError::PrimitiveNameDuplicate { ref duplicate, ref original } => {
                 write_name_duplicate_message(f, "Primitive", duplicate, original)
             }
+            &VerificationError::RecordBaseCycle { ref record } => {
+                write!(f, "Record extends itself, directly or via other records.\n{}", record)
+            }
+            &VerificationError::RecordEntryDefaultInvalid { ref entry, ref reason } => {
+                write!(f, "Invalid default value of record entry, {}.\n{}", reason, entry)
+            }
+            &VerificationError::RecordEntryDuplicate { ref duplicate, ref original } => {
+                write_name_duplicate_message(f, "Record entry", duplicate, original)
+            }
+            &VerificationError::RecordEntryXmlInvalid { ref entry, ref reason } => {
+                write!(f, "Invalid XML mapping of record entry, {}.\n{}", reason, entry)
+            }
             &VerificationError::RecordNameDuplicate { ref duplicate, ref original } => {
                 write_name_duplicate_message(f, "Record", duplicate, original)
             }
@@ -164,6 +536,13 @@ impl<'a> fmt::Display for VerificationError {
             &VerificationError::SystemNameDuplicate { ref duplicate, ref original } => {
                 write_name_duplicate_message(f, "System", duplicate, original)
             }
+            &VerificationError::TypeParameterCountMismatch { ref type_ref, ref expected } => {
+                write!(
+                    f,
+                    "Wrong number of type parameters, expected {}.\n{}",
+                    expected, type_ref,
+                )
+            }
             &VerificationError::UnknownServiceEncoding { ref encoding } => {
                 write!(
                     f,
@@ -206,4 +585,415 @@ impl<'a> fmt::Display for VerificationError {
             )
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use super::{parser, RouteSegment};
+    use crate::payload::Data;
+
+    #[test]
+    fn packages() {
+        let corpus = Corpus {
+            texts: vec![
+                Text {
+                    name: "a.ahfs".into(),
+                    body: concat!(
+                        "package Team.A;\n",
+                        "record Response { Code: U16 }\n",
+                        "service ServiceA { method Get(): Response; }\n",
+                    ).into(),
+                },
+                Text {
+                    name: "b.ahfs".into(),
+                    body: concat!(
+                        "package Team.B;\n",
+                        "use Team.A::ServiceA;\n",
+                        "record Response { Message: String }\n",
+                        "implement ServiceA using HTTP/JSON {\n",
+                        "    method Get { Method: \"GET\", Path: \"/\" }\n",
+                        "}\n",
+                    ).into(),
+                },
+                Text {
+                    name: "c.ahfs".into(),
+                    body: concat!(
+                        "primitive U16;\n",
+                        "use Team.B::Missing;\n",
+                    ).into(),
+                },
+            ],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(spec.package_of(&corpus.texts[0]), "Team.A");
+        assert_eq!(spec.package_of(&corpus.texts[2]), "");
+
+        let field_type = &spec.records[0].entries[0].type_ref.name;
+        assert!(spec.resolve_primitive(field_type).is_some());
+
+        let output = spec.services[0].methods[0].output.as_ref().unwrap();
+        let record = spec.resolve_record(&output.name).unwrap();
+        assert_eq!(record.entries[0].name.as_str(), "Code");
+
+        let service = spec.resolve_service(&spec.implementations[0].name).unwrap();
+        assert_eq!(service.name.as_str(), "ServiceA");
+
+        match spec.verify() {
+            Err(super::VerificationError::NoSuchDefinitionToUse { name, .. }) => {
+                assert_eq!(name.as_str(), "Missing");
+            }
+            other => panic!("Expected NoSuchDefinitionToUse, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ambiguous_references() {
+        let verify = |body: &'static str| {
+            let corpus = Corpus {
+                texts: vec![
+                    Text {
+                        name: "a.ahfs".into(),
+                        body: "package Team.A;\nrecord Response { Code: U16 }\nservice S { method Get(); }\n".into(),
+                    },
+                    Text {
+                        name: "b.ahfs".into(),
+                        body: "package Team.B;\nrecord Response { Message: String }\nservice S { method Get(); }\n".into(),
+                    },
+                    Text { name: "c.ahfs".into(), body: body.into() },
+                ],
+            };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().map_err(|error| error.to_string())
+        };
+        assert!(verify("use Team.A::Response;\nrecord R { Response: Response }\n").is_ok());
+        assert!(verify("package Team.A;\nuse Team.B::Response;\nrecord R { Response: Response }\n").is_ok());
+        assert!(verify("use Team.A::Response;\nuse Team.B::Response;\nrecord R { Response: Response }\n")
+            .unwrap_err()
+            .starts_with("Ambiguous reference to `Response`, which is defined by more than one used or imported package."));
+        assert!(verify("import \"a.ahfs\";\nimport \"b.ahfs\";\nservice T { method Get(): Response; }\n")
+            .unwrap_err()
+            .starts_with("Ambiguous reference to `Response`"));
+        assert!(verify("import \"a.ahfs\";\nimport \"b.ahfs\";\nimplement S using HTTP/JSON { method Get {} }\n")
+            .unwrap_err()
+            .starts_with("Ambiguous reference to `S`"));
+    }
+
+    #[test]
+    fn record_inheritance() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "record System extends Named { Port: U16 }\n",
+                    "record Named extends Addressed { Name: String }\n",
+                    "record Addressed { Address: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let entries = spec.records[0].entries.iter()
+            .map(|entry| (entry.name.as_str(), entry.inherited_from.as_ref().map(|base| base.as_str())))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![("Address", Some("Addressed")), ("Name", Some("Named")), ("Port", None)]);
+        assert_eq!(spec.records[0].own_entries().count(), 1);
+
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "b.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            format!("{:?}", spec.verify().unwrap_err())
+        };
+        assert!(verify("record A extends B { X: U8 }\n")
+            .starts_with("NoSuchRecordToExtend"));
+        assert!(verify("record A extends B { X: U8 }\nrecord B extends A { Y: U8 }\n")
+            .starts_with("RecordBaseCycle"));
+        assert!(verify("record A extends B { X: U8 }\nrecord B { X: String }\n")
+            .starts_with("RecordEntryDuplicate"));
+    }
+
+    #[test]
+    fn record_entry_defaults() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "record Entry {\n",
+                    "    Name?: String = null,\n",
+                    "    Port: U16 = 8080,\n",
+                    "    Tags: List<Tag> = [\"Secure\"],\n",
+                    "}\n",
+                    "enum Tag { Secure, Insecure }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let entries = spec.records[0].entries.iter()
+            .map(|entry| (entry.is_optional, entry.default.as_ref().map(|value| value.to_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![
+            (true, Some("null".into())),
+            (false, Some("8080".into())),
+            (false, Some("[\"Secure\"]".into())),
+        ]);
+        assert!(spec.records[0].entries.iter().all(|entry| entry.may_be_absent()));
+
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "b.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().unwrap_err().to_string()
+        };
+        assert!(verify("record A { X: U8 = null }\n")
+            .starts_with("Invalid default value of record entry, expected integer, found null"));
+        assert!(verify("record A { X: U8 = 256 }\n")
+            .starts_with("Invalid default value of record entry, 256 is out of range for `U8`"));
+        assert!(verify("record A { X: List<Tag> = [\"Other\"] }\nenum Tag { Secure }\n")
+            .starts_with("Invalid default value of record entry, at `/0`, `Other` is not a variant"));
+        assert!(verify("record A<T> { X: T = 1 }\n")
+            .starts_with("Invalid default value of record entry, fields of generic parameter types"));
+    }
+
+    #[test]
+    fn enum_variant_values() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "enum Reason { TTL_EXPIRED = 2, LOST = 3 }\n",
+                    "enum Origin { LOCAL, FROM_OTHER_CLOUD = \"from-other-cloud\" }\n",
+                    "record A { R: Reason = 3, O: Origin = \"from-other-cloud\" }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let (reason, origin) = (&spec.enums[0], &spec.enums[1]);
+        assert!(reason.is_integer());
+        assert!(!origin.is_integer());
+        assert_eq!(reason.variant_of(&Data::Integer(3)).map(|variant| variant.name.as_str()), Some("LOST"));
+        assert_eq!(origin.variants[0].wire_value(), Data::String("LOCAL".into()));
+        assert_eq!(origin.variants[1].wire_value(), Data::String("from-other-cloud".into()));
+
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "b.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().unwrap_err().to_string()
+        };
+        assert!(verify("enum A { X = 1, Y = 1 }\n")
+            .starts_with("Enum variant value `1` is already used."));
+        assert!(verify("enum A { X = \"Y\", Y }\n")
+            .starts_with("Enum variant value `\"Y\"` is already used."));
+        assert!(verify("enum A { X = 1, Y }\n")
+            .starts_with("Enum variant value must be an integer, like those of other variants."));
+        assert!(verify("enum A { X, Y = 2 }\n")
+            .starts_with("Enum variant value must be a string, like those of other variants."));
+        assert!(verify("record A { R: B = \"X\" }\nenum B { X = 1 }\n")
+            .starts_with("Invalid default value of record entry, expected enum variant value"));
+    }
+
+    #[test]
+    fn aliases() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "type ServiceId = String;\n",
+                    "type Port = U16 where 1..=65535;\n",
+                    "type WellKnownPort = Port where ..1024;\n",
+                    "type Ratio = Float32 where 0.0..;\n",
+                    "type Name = String where len <= 64, pattern \"^[A-Za-z]+$\";\n",
+                    "record A { Port: WellKnownPort = 80, Name: Name = \"Alpha\" }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let aliases = spec.aliases.iter()
+            .map(|alias| (
+                alias.name.as_str(),
+                alias.definition.to_string(),
+                alias.constraints.iter().map(|constraint| constraint.to_string()).collect::<Vec<_>>(),
+            ))
+            .collect::<Vec<_>>();
+        assert_eq!(aliases, vec![
+            ("ServiceId", "String".to_string(), vec![]),
+            ("Port", "U16".into(), vec!["1..=65535".to_string()]),
+            ("WellKnownPort", "Port".into(), vec!["..1024".into()]),
+            ("Ratio", "Float32".into(), vec!["0.0..".into()]),
+            ("Name", "String".into(), vec!["len <= 64".into(), "pattern \"^[A-Za-z]+$\"".into()]),
+        ]);
+        let (target, chain) = spec.unalias(&spec.records[0].entries[0].type_ref);
+        assert_eq!(target.to_string(), "U16");
+        assert_eq!(chain.iter().map(|alias| alias.name.as_str()).collect::<Vec<_>>(), vec!["WellKnownPort", "Port"]);
+
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "b.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().unwrap_err().to_string()
+        };
+        assert!(verify("type A = B;\ntype B = A;\n")
+            .starts_with("Alias refers to itself, directly or via other aliases."));
+        assert!(verify("type A = String where 1..2;\n")
+            .starts_with("Invalid constraint of alias, ranges do not apply to `String`."));
+        assert!(verify("type A = U8 where len <= 2;\n")
+            .starts_with("Invalid constraint of alias, lengths do not apply to `U8`."));
+        assert!(verify("type A = U8 where 1..=256;\n")
+            .starts_with("Invalid constraint of alias, invalid range bound, 256 is out of range for `U8`"));
+        assert!(verify("type A = U8 where 1..256;\ntype A = U8;\n")
+            .starts_with("Alias named `A` already exists."));
+        assert!(verify("type A = U8 where 2..1;\n")
+            .starts_with("Invalid constraint of alias, the range is empty."));
+        assert!(verify("type A = I8 where 0.5..;\n")
+            .starts_with("Invalid constraint of alias, invalid range bound, expected integer, found float."));
+        assert!(verify("type A = String where len >= 3, len <= 2;\n")
+            .starts_with("Invalid constraint of alias, the maximum length is less than 3."));
+        assert!(verify("type A = String where pattern \"(a\";\n")
+            .starts_with("Invalid constraint of alias, invalid pattern, unmatched `(` at character 0."));
+        assert!(verify("type A = String where len <= 2;\nrecord B { A: A = \"abc\" }\n")
+            .starts_with("Invalid default value of record entry, `abc` violates constraint `len <= 2` of alias `A`."));
+    }
+
+    #[test]
+    fn method_errors() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "service S {\n",
+                    "    method Query(Form): Result throws NotFound, Unauthorized;\n",
+                    "    method Delete(String) throws NotFound;\n",
+                    "}\n",
+                    "implement S using HTTP/JSON {\n",
+                    "    method Query { Method: \"POST\", Path: \"/query\", Errors: { NotFound: 404, Unauthorized: 401 } }\n",
+                    "    method Delete { Method: \"DELETE\", Path: \"/{id}\", Errors: { NotFound: 404 } }\n",
+                    "}\n",
+                    "implement S using COAP/CBOR {\n",
+                    "    method Query { Method: \"POST\", Path: \"/query\", Errors: { NotFound: \"4.04\", Unauthorized: \"4.01\" } }\n",
+                    "    method Delete { Method: \"DELETE\", Path: \"/{id}\", Errors: { NotFound: \"4.04\" } }\n",
+                    "}\n",
+                    "record Form { Name: String }\n",
+                    "record Result { Names: List<String> }\n",
+                    "record NotFound { Message: String }\n",
+                    "record Unauthorized {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let query = &spec.services[0].methods[0];
+        assert_eq!(query.output.as_ref().map(|output| output.to_string()), Some("Result".into()));
+        assert_eq!(query.errors.iter().map(|error| error.to_string()).collect::<Vec<_>>(), vec!["NotFound", "Unauthorized"]);
+        assert_eq!(spec.services[0].methods[1].errors.len(), 1);
+        assert_eq!(
+            spec.implementations[1].methods[0].error_code("Unauthorized").map(|code| code.to_string()),
+            Some("\"4.01\"".into()),
+        );
+        let routes = spec.implementations[0].routes(&spec.services[0]);
+        assert_eq!(routes.iter().map(|route| (route.method.as_str(), route.path.as_str())).collect::<Vec<_>>(),
+            vec![("POST", "/query"), ("DELETE", "/{id}")]);
+        assert_eq!(routes[0].errors.iter().map(|(error, status)| (error.name.as_str(), *status)).collect::<Vec<_>>(),
+            vec![("NotFound", 404), ("Unauthorized", 401)]);
+        assert_eq!(routes[1].segments().collect::<Vec<_>>(), vec![RouteSegment::Parameter("id")]);
+
+        let verify = |body: &str| {
+            let corpus = Corpus {
+                texts: vec![Text {
+                    name: "b.ahfs".into(),
+                    body: format!("service S {{ method M() throws E; }}\nrecord E {{}}\n{}", body).into(),
+                }],
+            };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().unwrap_err().to_string()
+        };
+        assert!(verify("service T { method M() throws E, E; }\n")
+            .starts_with("Method error named `E` already exists."));
+        assert!(verify("implement S using HTTP/JSON { method M { Errors: {} } }\n")
+            .starts_with("Method error not mapped by implementation."));
+        assert!(verify("implement S using HTTP/JSON { method M { Errors: { E: 404, F: 500 } } }\n")
+            .starts_with("Invalid error mapping of method implementation, `F` is not an error of method `M`."));
+        assert!(verify("implement S using HTTP/JSON { method M { Errors: { E: 200 } } }\n")
+            .starts_with("Invalid error mapping of method implementation, expected a status code from 400 to 599, found `200`."));
+        assert!(verify("implement S using COAP/JSON { method M { Errors: { E: \"4.40\" } } }\n")
+            .starts_with("Invalid error mapping of method implementation, expected a response code from \"4.00\" to \"5.31\""));
+        assert!(verify("implement S using MQTT/JSON { method M { Errors: [\"e\"] } }\n")
+            .starts_with("Invalid error mapping of method implementation, expected map of error names to topics."));
+    }
+
+    #[test]
+    fn generic_records() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "record Page<Item> { Items: List<Item>, Total: U32 }\n",
+                    "record Pair<A, B> { First: A, Second: Option<B> }\n",
+                    "record Systems extends Page<Pair<String, U16>> {}\n",
+                    "service S { method List(): Page<String>; }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let page = &spec.records[0];
+        assert_eq!(page.generic_parameters.iter().map(|p| p.as_str()).collect::<Vec<_>>(), vec!["Item"]);
+
+        let output = spec.services[0].methods[0].output.as_ref().unwrap();
+        let entries = page.entries_of(output).iter()
+            .map(|entry| (entry.name.as_str(), entry.type_ref.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![("Items", "List<String>".into()), ("Total", "U32".into())]);
+
+        let pair = &spec.records[1];
+        let instance = &spec.records[2].entries[0].type_ref.params[0];
+        let entries = pair.entries_of(instance).iter()
+            .map(|entry| entry.type_ref.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec!["String", "Option<U16>"]);
+        assert_eq!(spec.records[2].entries[0].type_ref.to_string(), "List<Pair<String, U16>>");
+
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "b.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            format!("{:?}", spec.verify().unwrap_err())
+        };
+        assert!(verify("record A<T> { X: T }\nrecord B { Y: A }\n")
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A<T> { X: T<U8> }\n")
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A<T> { X: T }\nservice S { method M(): List<A<U8, U8>>; }\n")
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A<T> { X: T }\nrecord B extends A {}\n")
+            .starts_with("TypeParameterCountMismatch"));
+    }
+
+    #[test]
+    fn primitive_arity_and_unknown_types() {
+        let verify = |body: &'static str| {
+            let corpus = Corpus { texts: vec![Text { name: "a.ahfs".into(), body: body.into() }] };
+            let spec = parser::parse(&corpus).unwrap();
+            spec.verify().map_err(|error| format!("{:?}", error))
+        };
+        verify(concat!(
+            "primitive<K, V> Table<K, V>;\n",
+            "enum E { A }\n",
+            "type N = U8;\n",
+            "record A { X: Map<String, List<E>>, Y: Option<Set<N>>, Z: Table<String, Boolean> }\n",
+        )).unwrap();
+
+        assert!(verify("record A { X: List<String, String> }\n").unwrap_err()
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A { X: Map<String> }\n").unwrap_err()
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A { X: Option<U8<U8>> }\n").unwrap_err()
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("primitive<T> Box<T>;\nrecord A { X: Box }\n").unwrap_err()
+            .starts_with("TypeParameterCountMismatch"));
+        assert!(verify("record A { X: ArrowheadSystem }\n").unwrap_err()
+            .starts_with("NoSuchType"));
+        assert!(verify("type N = List<Unknown>;\n").unwrap_err()
+            .starts_with("NoSuchType"));
+        assert!(verify("service S { method M(Unknown); }\n").unwrap_err()
+            .starts_with("NoSuchType"));
+    }
+}
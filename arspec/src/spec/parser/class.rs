@@ -14,8 +14,14 @@ pub enum Class {
     BraceRight,
     Colon,
     Comma,
+    Dot,
+    DoubleColon,
+    DoubleDot,
+    DoubleDotEquals,
+    Equals,
     ParenLeft,
     ParenRight,
+    QuestionMark,
     Slash,
     SquareLeft,
     SquareRight,
@@ -31,16 +37,23 @@ pub enum Class {
     // Keywords.
     Consumes,
     Enum,
+    Extends,
     Implement,
+    Import,
     Interface,
     Method,
+    Package,
     Primitive,
     Produces,
     Property,
     Record,
     Service,
     System,
+    Throws,
+    Type,
+    Use,
     Using,
+    Where,
 
     // Other.
     Identifier,
@@ -61,8 +74,14 @@ impl fmt::Display for Class {
             Class::BraceRight => "}",
             Class::Colon => ":",
             Class::Comma => ",",
+            Class::Dot => ".",
+            Class::DoubleColon => "::",
+            Class::DoubleDot => "..",
+            Class::DoubleDotEquals => "..=",
+            Class::Equals => "=",
             Class::ParenLeft => "(",
             Class::ParenRight => ")",
+            Class::QuestionMark => "?",
             Class::Slash => "/",
             Class::SquareLeft => "[",
             Class::SquareRight => "]",
@@ -76,16 +95,23 @@ impl fmt::Display for Class {
 
             Class::Consumes => "consumes",
             Class::Enum => "enum",
+            Class::Extends => "extends",
             Class::Implement => "implement",
+            Class::Import => "import",
             Class::Interface => "interface",
             Class::Method => "method",
+            Class::Package => "package",
             Class::Primitive => "primitive",
             Class::Produces => "produces",
             Class::Property => "property",
             Class::Record => "record",
             Class::Service => "service",
             Class::System => "system",
+            Class::Throws => "throws",
+            Class::Type => "type",
+            Class::Use => "use",
             Class::Using => "using",
+            Class::Where => "where",
 
             Class::Identifier => "{Identifier}",
 
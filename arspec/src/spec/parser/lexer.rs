@@ -31,10 +31,13 @@ fn scan_all<'a>(scanner: &mut Scanner<'a>, out: &mut Vec<Token<'a, Class>>) -> O
             '@' => Class::At,
             '{' => Class::BraceLeft,
             '}' => Class::BraceRight,
-            ':' => Class::Colon,
+            ':' => scan_colon_or_double_colon(scanner),
             ',' => Class::Comma,
+            '.' => scan_dot_or_double_dot(scanner),
+            '=' => Class::Equals,
             '(' => Class::ParenLeft,
             ')' => Class::ParenRight,
+            '?' => Class::QuestionMark,
             '[' => Class::SquareLeft,
             ']' => Class::SquareRight,
             ';' => Class::Semicolon,
@@ -53,6 +56,37 @@ fn scan_all<'a>(scanner: &mut Scanner<'a>, out: &mut Vec<Token<'a, Class>>) -> O
     }
 }
 
+#[inline]
+fn scan_colon_or_double_colon(scanner: &mut Scanner) -> Class {
+    match scanner.next() {
+        Some(':') => Class::DoubleColon,
+        Some(_) => {
+            scanner.unwind();
+            Class::Colon
+        }
+        None => Class::Colon,
+    }
+}
+
+#[inline]
+fn scan_dot_or_double_dot(scanner: &mut Scanner) -> Class {
+    match scanner.next() {
+        Some('.') => match scanner.next() {
+            Some('=') => Class::DoubleDotEquals,
+            Some(_) => {
+                scanner.unwind();
+                Class::DoubleDot
+            }
+            None => Class::DoubleDot,
+        },
+        Some(_) => {
+            scanner.unwind();
+            Class::Dot
+        }
+        None => Class::Dot,
+    }
+}
+
 #[inline]
 fn scan_radix_number(scanner: &mut Scanner) -> Option<Class> {
     let mut ch = scanner.next()?;
@@ -81,6 +115,10 @@ fn scan_radix_number(scanner: &mut Scanner) -> Option<Class> {
         '0'..='9' => {
             return scan_number(scanner);
         }
+        '.' => {
+            scanner.unwind();
+            return scan_number(scanner);
+        }
         _ => {}
     };
     scanner.unwind();
@@ -100,12 +138,20 @@ fn scan_number(scanner: &mut Scanner) -> Option<Class> {
         }
     }
 
-    // Fraction.
+    // Fraction, unless the dot is the first of a range operator.
     if ch == '.' {
+        ch = scanner.next()?;
+        if ch == '.' {
+            scanner.unwind();
+            scanner.unwind();
+            return Some(Class::Integer);
+        }
         loop {
-            ch = scanner.next()?;
             match ch {
-                '0'..='9' => continue,
+                '0'..='9' => {
+                    ch = scanner.next()?;
+                    continue;
+                }
                 _ => break,
             }
         }
@@ -240,16 +286,23 @@ fn scan_symbol(scanner: &mut Scanner, mut ch: char) -> Option<Class> {
         // Keywords.
         "consumes" => Class::Consumes,
         "enum" => Class::Enum,
+        "extends" => Class::Extends,
         "implement" => Class::Implement,
+        "import" => Class::Import,
         "interface" => Class::Interface,
         "method" => Class::Method,
+        "package" => Class::Package,
         "primitive" => Class::Primitive,
         "produces" => Class::Produces,
         "property" => Class::Property,
         "record" => Class::Record,
         "service" => Class::Service,
         "system" => Class::System,
+        "throws" => Class::Throws,
+        "type" => Class::Type,
+        "use" => Class::Use,
         "using" => Class::Using,
+        "where" => Class::Where,
 
         // Null.
         "null" => Class::Null,
@@ -278,21 +331,22 @@ mod tests {
         let source = Text {
             name: "alpha.ahfs".into(),
             body: concat!(
-                "consumes enum implement interface method\n",
-                "produces property record service system using\n",
+                "consumes enum implement import interface method\n",
+                "package produces property record service system throws type use using where\n",
                 "\n",
-                "<>{}:,()/[];\n",
+                "<>{}:,.::....=()/[];\n",
                 "\n",
                 "null\n",
                 "true false\n",
                 "0 1 202 -30 +40\n",
-                "50.0 6.1234 7.e+20 8e-10 1e9\n",
+                "50.0 6.1234 7.e+20 8e-10 1e9 0.5\n",
+                "1..=2 0..3\n",
                 "inf +inf -inf NaN\n",
                 "\"Hello, World!\" \"\\uBad\" \"\\uFree\"\n",
                 "\"123\\uXYZ456\"\n",
                 "\n",
                 "IdentifierName smallCaps _underscore\n",
-                "+ - * # ! ^ ~ ..\n",
+                "+ - * # ! ^ ~\n",
                 "/// This is an ignored doc comment.\n",
                 "/** This too! */\n",
                 "// This is an ignored plain comment.\n",
@@ -305,19 +359,20 @@ mod tests {
         // Check token strings.
         assert_eq!(
             vec![
-                "consumes", "enum", "implement", "interface", "method",
-                "produces", "property", "record", "service",
-                "system", "using",
-                "<", ">", "{", "}", ":", ",", "(", ")", "/", "[", "]", ";",
+                "consumes", "enum", "implement", "import", "interface", "method",
+                "package", "produces", "property", "record", "service",
+                "system", "throws", "type", "use", "using", "where",
+                "<", ">", "{", "}", ":", ",", ".", "::", "..", "..=", "(", ")", "/", "[", "]", ";",
                 "null",
                 "true", "false",
                 "0", "1", "202", "-30", "+40",
-                "50.0", "6.1234", "7.e+20", "8e-10", "1e9",
+                "50.0", "6.1234", "7.e+20", "8e-10", "1e9", "0.5",
+                "1", "..=", "2", "0", "..", "3",
                 "inf", "+inf", "-inf", "NaN",
                 "\"Hello, World!\"", "\"\\uBad\"", "\"\\uFree\"",
                 "\"123\\uXYZ456\"",
                 "IdentifierName", "smallCaps", "_underscore",
-                "+", "-", "*", "#", "!", "^", "~", ".", ".",
+                "+", "-", "*", "#", "!", "^", "~",
             ],
             tokens.iter().map(|item| item.span.as_str()).collect::<Vec<_>>()
         );
@@ -325,12 +380,14 @@ mod tests {
         // Check token classes.
         assert_eq!(
             vec![
-                Class::Consumes, Class::Enum, Class::Implement, Class::Interface, Class::Method,
-                Class::Produces, Class::Property, Class::Record, Class::Service,
-                Class::System, Class::Using,
+                Class::Consumes, Class::Enum, Class::Implement, Class::Import,
+                Class::Interface, Class::Method,
+                Class::Package, Class::Produces, Class::Property, Class::Record, Class::Service,
+                Class::System, Class::Throws, Class::Type, Class::Use, Class::Using, Class::Where,
                 Class::AngleLeft, Class::AngleRight,
                 Class::BraceLeft, Class::BraceRight,
-                Class::Colon, Class::Comma,
+                Class::Colon, Class::Comma, Class::Dot, Class::DoubleColon,
+                Class::DoubleDot, Class::DoubleDotEquals,
                 Class::ParenLeft, Class::ParenRight,
                 Class::Slash,
                 Class::SquareLeft, Class::SquareRight,
@@ -340,14 +397,16 @@ mod tests {
                 Class::Integer, Class::Integer, Class::Integer,
                 Class::Integer, Class::Integer,
                 Class::Float, Class::Float, Class::Float,
-                Class::Float, Class::Float,
+                Class::Float, Class::Float, Class::Float,
+                Class::Integer, Class::DoubleDotEquals, Class::Integer,
+                Class::Integer, Class::DoubleDot, Class::Integer,
                 Class::Float, Class::Float, Class::Float, Class::Float,
                 Class::String, Class::InvalidStringEscape, Class::InvalidStringEscape,
                 Class::InvalidStringEscape,
                 Class::Identifier, Class::Identifier, Class::Identifier,
                 Class::InvalidSymbolChar, Class::InvalidSymbolChar, Class::InvalidSymbolChar,
                 Class::InvalidSymbolChar, Class::InvalidSymbolChar, Class::InvalidSymbolChar,
-                Class::InvalidSymbolChar, Class::InvalidSymbolChar, Class::InvalidSymbolChar,
+                Class::InvalidSymbolChar,
             ],
             tokens.iter().map(|item| item.class).collect::<Vec<_>>(),
         );
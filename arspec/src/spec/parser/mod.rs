@@ -4,16 +4,33 @@ mod lexer;
 
 pub use self::class::Class;
 
-use arspec_parser::{Corpus, Error, Matcher, Parser, Scanner, Token};
-use crate::spec::Specification;
+use arspec_parser::{Corpus, Error, Matcher, Parser, Scanner, Text, Token};
+use crate::spec::{Import, Specification, record};
 
 /// Attempt to create [`Specification`][spc] from given source [`text`][txt].
 ///
 /// [spc]: ../struct.Specification.html
 /// [txt]: ../../../arspec_parser/struct.Text.html
-#[inline]
+///
+/// The entries of the records extended by each parsed record are copied into
+/// it, preceding its own entries.
 pub fn parse(corpus: &Corpus) -> Result<Specification, Error<Class>> {
-    SpecParser::parse(corpus)
+    let mut spec = SpecParser::parse(corpus)?;
+    record::inherit_entries(&mut spec);
+    Ok(spec)
+}
+
+/// Collects all [`Import`][imp] declarations in given source [`text`][txt],
+/// without parsing it entirely.
+///
+/// [imp]: ../struct.Import.html
+/// [txt]: ../../../arspec_parser/struct.Text.html
+pub fn imports(text: &Text) -> Vec<Import<'_>> {
+    let tokens = lexer::scan(Scanner::new(text));
+    tokens.windows(2)
+        .filter(|pair| pair[0].class == Class::Import && pair[1].class == Class::String)
+        .map(|pair| Import { path: pair[1].span.clone(), attributes: Vec::new() })
+        .collect()
 }
 
 struct SpecParser;
@@ -1,8 +1,10 @@
-use arspec_parser::{Error, Matcher, Span};
+use arspec_parser::{Error, Matcher, Range, Span, Text, Token};
 use crate::spec::{
+    Alias, Constraint,
     Attribute,
     Enum, EnumVariant,
     Implement, ImplementMethod,
+    Import, Package, Scope, Use,
     Primitive,
     Property,
     Record, RecordEntry,
@@ -28,10 +30,14 @@ pub fn root<'a>(spec: &mut Specification<'a>, mut m: &mut M<'a>) -> R<()> {
             Class::At,
             Class::Enum,
             Class::Implement,
+            Class::Import,
+            Class::Package,
             Class::Primitive,
             Class::Record,
             Class::Service,
             Class::System,
+            Class::Type,
+            Class::Use,
         ])?;
         match token.class {
             Class::At => {
@@ -40,10 +46,20 @@ pub fn root<'a>(spec: &mut Specification<'a>, mut m: &mut M<'a>) -> R<()> {
             }
             Class::Enum => enum_(m, t, a)?,
             Class::Implement => implement(m, t, a)?,
+            Class::Import => import(m, scope(t, token.span.source), a)?,
+            Class::Package => {
+                let scope = scope(t, token.span.source);
+                if scope.package.is_some() {
+                    return Err(Error::unexpected_token(&token, vec![]));
+                }
+                package(m, scope, a)?
+            }
             Class::Primitive => primitive(m, t, a)?,
             Class::Record => record(m, t, a)?,
             Class::Service => service(m, t, a)?,
             Class::System => system(m, t, a)?,
+            Class::Type => type_(m, t, a)?,
+            Class::Use => use_(m, scope(t, token.span.source), a)?,
             _ => unreachable!(),
         }
         if m.at_end() {
@@ -97,7 +113,22 @@ fn enum_<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) ->
             }
         };
 
-        t.variants.push(EnumVariant { name, attributes: a });
+        let value = match m.one_optional(Class::Equals) {
+            Some(_) => {
+                let token = m.any(&[
+                    Class::Integer,
+                    Class::String,
+                ])?;
+                Some(match token.class {
+                    Class::Integer => Value::Integer(token.span.clone()),
+                    Class::String => Value::String(token.span.clone()),
+                    _ => unreachable!(),
+                })
+            }
+            None => None,
+        };
+
+        t.variants.push(EnumVariant { name, value, attributes: a });
 
         let token = m.any(&[
             Class::Comma,
@@ -155,6 +186,16 @@ fn implement<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>
     }
 }
 
+fn import<'a>(m: &mut M<'a>, t: &mut Scope<'a>, a: Vec<Attribute<'a>>) -> R<()> {
+    let path = m
+        .all(&[Class::String, Class::Semicolon])
+        .map(|tokens| tokens[0].span.clone())?;
+
+    t.imports.push(Import { path, attributes: a });
+
+    Ok(())
+}
+
 fn implement_method<'a>(m: &mut M<'a>, t: &mut Vec<ImplementMethod<'a>>, a: Vec<Attribute<'a>>) -> R<()> {
     let mut method = m
         .all(&[Class::Identifier, Class::BraceLeft])
@@ -166,6 +207,28 @@ fn implement_method<'a>(m: &mut M<'a>, t: &mut Vec<ImplementMethod<'a>>, a: Vec<
     Ok(())
 }
 
+fn package<'a>(m: &mut M<'a>, t: &mut Scope<'a>, a: Vec<Attribute<'a>>) -> R<()> {
+    let name = package_name(m)?;
+
+    m.one(Class::Semicolon)?;
+
+    t.package = Some(Package { name, attributes: a });
+
+    Ok(())
+}
+
+fn package_name<'a>(m: &mut M<'a>) -> R<Span<'a>> {
+    let first = m.one(Class::Identifier)?;
+    let mut end = first.span.range.end;
+    while m.one_optional(Class::Dot).is_some() {
+        end = m.one(Class::Identifier)?.span.range.end;
+    }
+    Ok(Span {
+        source: first.span.source,
+        range: Range { start: first.span.range.start, end },
+    })
+}
+
 fn primitive<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -> R<()> {
     let mut generic_parameters = Vec::new();
 
@@ -175,7 +238,7 @@ fn primitive<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>
     ])?;
     let token = match token.class {
         Class::AngleLeft => {
-            parameters(m, &mut generic_parameters)?;
+            generic_params(m, &mut generic_parameters)?;
             m.one(Class::Identifier)?
         }
         Class::Identifier => token,
@@ -190,30 +253,30 @@ fn primitive<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>
         attributes: a,
     });
 
-    return m.one(Class::Semicolon)
-        .map(|_token| ());
+    m.one(Class::Semicolon)
+        .map(|_token| ())
+}
 
-    fn parameters<'a>(m: &mut M<'a>, t: &mut Vec<Span<'a>>) -> R<()> {
-        let token = m.any(&[
-            Class::Identifier,
-            Class::AngleRight,
-        ])?;
-        match token.class {
-            Class::Identifier => {
-                t.push(token.span.clone());
-                let token = m.any(&[
-                    Class::Comma,
-                    Class::AngleRight,
-                ])?;
-                match token.class {
-                    Class::Comma => parameters(m, t),
-                    Class::AngleRight => Ok(()),
-                    _ => unreachable!(),
-                }
+fn generic_params<'a>(m: &mut M<'a>, t: &mut Vec<Span<'a>>) -> R<()> {
+    let token = m.any(&[
+        Class::Identifier,
+        Class::AngleRight,
+    ])?;
+    match token.class {
+        Class::Identifier => {
+            t.push(token.span.clone());
+            let token = m.any(&[
+                Class::Comma,
+                Class::AngleRight,
+            ])?;
+            match token.class {
+                Class::Comma => generic_params(m, t),
+                Class::AngleRight => Ok(()),
+                _ => unreachable!(),
             }
-            Class::AngleRight => Ok(()),
-            _ => unreachable!(),
         }
+        Class::AngleRight => Ok(()),
+        _ => unreachable!(),
     }
 }
 
@@ -286,11 +349,36 @@ fn property<'a>(m: &mut M<'a>, t: &mut Vec<Property<'a>>, a: Vec<Attribute<'a>>)
 
 fn record<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -> R<()> {
     let name = m
-        .all(&[Class::Identifier, Class::BraceLeft])
-        .map(|tokens| tokens[0].span.clone())?;
+        .one(Class::Identifier)
+        .map(|token| token.span.clone())?;
 
     let mut record = Record::new(name, a);
 
+    let mut token = m.any(&[
+        Class::AngleLeft,
+        Class::Extends,
+        Class::BraceLeft,
+    ])?;
+    if token.class == Class::AngleLeft {
+        generic_params(m, &mut record.generic_parameters)?;
+
+        token = m.any(&[
+            Class::Extends,
+            Class::BraceLeft,
+        ])?;
+    }
+    if token.class == Class::Extends {
+        let mut base = m
+            .one(Class::Identifier)
+            .map(|token| TypeRef::new(token.span.clone()))?;
+
+        type_params(m, &mut base.params)?;
+
+        m.one(Class::BraceLeft)?;
+
+        record.base = Some(base);
+    }
+
     entry(m, &mut record, vec![])?;
     t.records.push(record);
 
@@ -314,6 +402,8 @@ fn record<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -
             }
         };
 
+        let is_optional = m.one_optional(Class::QuestionMark).is_some();
+
         let type_ref = {
             let mut type_ref = m
                 .all(&[Class::Colon, Class::Identifier])
@@ -324,7 +414,12 @@ fn record<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -
             type_ref
         };
 
-        t.entries.push(RecordEntry { name, type_ref, attributes: a });
+        let default = match m.one_optional(Class::Equals) {
+            Some(_) => Some(value(m)?),
+            None => None,
+        };
+
+        t.entries.push(RecordEntry { name, type_ref, is_optional, default, inherited_from: None, attributes: a });
 
         let token = m.any(&[
             Class::Comma,
@@ -369,6 +464,17 @@ fn system<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -
     }
 }
 
+fn scope<'a, 'b>(t: &'b mut Specification<'a>, source: &'a Text) -> &'b mut Scope<'a> {
+    let index = match t.scopes.iter().position(|scope| std::ptr::eq(scope.source, source)) {
+        Some(index) => index,
+        None => {
+            t.scopes.push(Scope::new(source));
+            t.scopes.len() - 1
+        }
+    };
+    &mut t.scopes[index]
+}
+
 fn service<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -> R<()> {
     let mut service = m
         .all(&[Class::Identifier, Class::BraceLeft])
@@ -424,30 +530,56 @@ fn service_method<'a>(m: &mut M<'a>, t: &mut Vec<ServiceMethod<'a>>, a: Vec<Attr
         _ => unreachable!(),
     }
 
-    let token = m.any(&[Class::Colon, Class::Semicolon])?;
-    match token.class {
-        Class::Colon => {
-            let token = m.one(Class::Identifier)?;
+    let mut token = m.any(&[Class::Colon, Class::Throws, Class::Semicolon])?;
+    if token.class == Class::Colon {
+        let output = m.one(Class::Identifier)?;
 
-            let mut type_ref = TypeRef::new(token.span.clone());
-            type_params(m, &mut type_ref.params)?;
+        let mut type_ref = TypeRef::new(output.span.clone());
+        type_params(m, &mut type_ref.params)?;
 
-            if let Err(mut error) = m.one(Class::Semicolon) {
+        token = match m.any(&[Class::Throws, Class::Semicolon]) {
+            Ok(token) => token,
+            Err(mut error) => {
                 if type_ref.params.len() == 0 {
                     error.expected.push(Class::AngleLeft);
                 }
                 return Err(error);
             }
+        };
 
-            method.output = Some(type_ref);
-        }
-        Class::Semicolon => {}
-        _ => unreachable!(),
+        method.output = Some(type_ref);
+    }
+    if token.class == Class::Throws {
+        errors(m, &mut method.errors)?;
     }
 
     t.push(method);
 
-    Ok(())
+    return Ok(());
+
+    fn errors<'a>(m: &mut M<'a>, t: &mut Vec<TypeRef<'a>>) -> R<()> {
+        let token = m.one(Class::Identifier)?;
+
+        let mut type_ref = TypeRef::new(token.span.clone());
+        type_params(m, &mut type_ref.params)?;
+        let is_generic = !type_ref.params.is_empty();
+        t.push(type_ref);
+
+        let token = match m.any(&[Class::Comma, Class::Semicolon]) {
+            Ok(token) => token,
+            Err(mut error) => {
+                if !is_generic {
+                    error.expected.push(Class::AngleLeft);
+                }
+                return Err(error);
+            }
+        };
+        match token.class {
+            Class::Comma => errors(m, t),
+            Class::Semicolon => Ok(()),
+            _ => unreachable!(),
+        }
+    }
 }
 
 fn service_ref<'a>(m: &mut M<'a>, s: &mut Vec<ServiceRef<'a>>, a: Vec<Attribute<'a>>) -> R<()> {
@@ -460,6 +592,121 @@ fn service_ref<'a>(m: &mut M<'a>, s: &mut Vec<ServiceRef<'a>>, a: Vec<Attribute<
     Ok(())
 }
 
+fn type_<'a>(m: &mut M<'a>, t: &mut Specification<'a>, a: Vec<Attribute<'a>>) -> R<()> {
+    let name = m
+        .all(&[Class::Identifier, Class::Equals])
+        .map(|tokens| tokens[0].span.clone())?;
+
+    let mut definition = m
+        .one(Class::Identifier)
+        .map(|token| TypeRef::new(token.span.clone()))?;
+
+    type_params(m, &mut definition.params)?;
+
+    let mut alias = Alias::new(name, definition, a);
+
+    let token = m.any(&[
+        Class::Where,
+        Class::Semicolon,
+    ])?;
+    if token.class == Class::Where {
+        constraints(m, &mut alias.constraints)?;
+    }
+    t.aliases.push(alias);
+
+    return Ok(());
+
+    fn constraints<'a>(m: &mut M<'a>, t: &mut Vec<Constraint<'a>>) -> R<()> {
+        t.push(constraint(m)?);
+
+        let token = m.any(&[
+            Class::Comma,
+            Class::Semicolon,
+        ])?;
+        match token.class {
+            Class::Comma => constraints(m, t),
+            Class::Semicolon => Ok(()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn constraint<'a>(m: &mut M<'a>) -> R<Constraint<'a>> {
+        let first = m.any(&[
+            Class::Integer,
+            Class::Float,
+            Class::DoubleDot,
+            Class::DoubleDotEquals,
+            Class::Identifier,
+        ])?;
+        let span_to = |last: &Span<'a>| Span {
+            source: first.span.source,
+            range: Range { start: first.span.range.start, end: last.range.end },
+        };
+        match first.class {
+            Class::Integer | Class::Float => {
+                let start = number(&first);
+                let operator = m.any(&[
+                    Class::DoubleDot,
+                    Class::DoubleDotEquals,
+                ])?;
+                let end = match operator.class {
+                    Class::DoubleDotEquals => Some(m.any(&[Class::Integer, Class::Float])?),
+                    _ => m.one_optional(Class::Integer).or_else(|| m.one_optional(Class::Float)),
+                };
+                Ok(Constraint::Range {
+                    span: span_to(&end.as_ref().unwrap_or(&operator).span),
+                    start: Some(start),
+                    end: end.as_ref().map(number),
+                    is_inclusive: operator.class == Class::DoubleDotEquals,
+                })
+            }
+            Class::DoubleDot | Class::DoubleDotEquals => {
+                let end = m.any(&[Class::Integer, Class::Float])?;
+                Ok(Constraint::Range {
+                    span: span_to(&end.span),
+                    start: None,
+                    end: Some(number(&end)),
+                    is_inclusive: first.class == Class::DoubleDotEquals,
+                })
+            }
+            Class::Identifier if first.span.as_str() == "len" => {
+                let operator = m.any(&[
+                    Class::AngleLeft,
+                    Class::AngleRight,
+                ])?;
+                m.one(Class::Equals)?;
+                let length = m.one(Class::Integer)?;
+                let span = span_to(&length.span);
+                let length = Value::Integer(length.span);
+                Ok(match operator.class {
+                    Class::AngleLeft => Constraint::LengthMax { span, length },
+                    _ => Constraint::LengthMin { span, length },
+                })
+            }
+            Class::Identifier if first.span.as_str() == "pattern" => {
+                let pattern = m.one(Class::String)?;
+                Ok(Constraint::Pattern {
+                    span: span_to(&pattern.span),
+                    pattern: Value::String(pattern.span),
+                })
+            }
+            _ => Err(Error::unexpected_token(&first, vec![
+                Class::Integer,
+                Class::Float,
+                Class::DoubleDot,
+                Class::DoubleDotEquals,
+            ])),
+        }
+    }
+
+    fn number<'a>(token: &Token<'a, Class>) -> Value<'a> {
+        match token.class {
+            Class::Float => Value::Float(token.span.clone()),
+            _ => Value::Integer(token.span.clone()),
+        }
+    }
+}
+
 fn type_params<'a>(m: &mut M<'a>, t: &mut Vec<TypeRef<'a>>) -> R<()> {
     if let None = m.one_optional(Class::AngleLeft) {
         return Ok(());
@@ -473,20 +720,30 @@ fn type_params<'a>(m: &mut M<'a>, t: &mut Vec<TypeRef<'a>>) -> R<()> {
 
         type_params(m, &mut type_ref.params)?;
 
+        t.push(type_ref);
+
         let token = m.any(&[
             Class::Comma,
             Class::AngleRight,
         ])?;
         match token.class {
-            Class::Comma => entry(m, t)?,
-            Class::AngleRight => {}
+            Class::Comma => entry(m, t),
+            Class::AngleRight => Ok(()),
             _ => unreachable!(),
-        };
+        }
+    }
+}
 
-        t.push(type_ref);
+fn use_<'a>(m: &mut M<'a>, t: &mut Scope<'a>, a: Vec<Attribute<'a>>) -> R<()> {
+    let package = package_name(m)?;
 
-        Ok(())
-    }
+    let name = m
+        .all(&[Class::DoubleColon, Class::Identifier, Class::Semicolon])
+        .map(|tokens| tokens[1].span.clone())?;
+
+    t.uses.push(Use { package, name, attributes: a });
+
+    Ok(())
 }
 
 fn value<'a>(m: &mut M<'a>) -> R<Value<'a>> {
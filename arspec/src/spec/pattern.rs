@@ -0,0 +1,383 @@
+//! Regular expressions of alias `pattern` constraints.
+//!
+//! The supported syntax is the subset of ECMAScript regular expressions most
+//! commonly used in JSON Schemas, which consists of literal characters, `.`,
+//! the anchors `^` and `$`, character classes such as `[A-Za-z_]` and
+//! `[^0-9]`, the escapes `\d`, `\w`, `\s`, `\D`, `\W`, `\S`, `\n`, `\r` and
+//! `\t`, capturing and non-capturing groups, alternations, as well as the
+//! quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, which may be made
+//! lazy by a trailing `?`. Like in JSON Schemas, patterns are not implicitly
+//! anchored, which means that they match any text containing a match.
+
+use std::fmt;
+
+/// A parsed regular expression.
+#[derive(Debug)]
+pub struct Pattern {
+    node: Node,
+}
+
+impl Pattern {
+    /// Parses regular expression `source`.
+    pub fn parse(source: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser { chars: source.chars().collect(), offset: 0 };
+        let node = parser.alternation()?;
+        if parser.offset < parser.chars.len() {
+            return Err(PatternError::new("unmatched `)`", parser.offset));
+        }
+        Ok(Pattern { node })
+    }
+
+    /// Determines whether `text` contains a match of this pattern.
+    pub fn is_match(&self, text: &str) -> bool {
+        let chars = text.chars().collect::<Vec<_>>();
+        (0..=chars.len()).any(|start| matches(&self.node, &chars, start, &mut |_| true))
+    }
+}
+
+/// A regular expression syntax error.
+#[derive(Debug)]
+pub struct PatternError {
+    message: &'static str,
+    offset: usize,
+}
+
+impl PatternError {
+    fn new(message: &'static str, offset: usize) -> Self {
+        PatternError { message, offset }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.offset)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, is_negated: bool },
+    Start,
+    End,
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat { node: Box<Node>, min: usize, max: Option<usize>, is_lazy: bool },
+}
+
+#[derive(Debug)]
+enum ClassItem {
+    Range(char, char),
+    Digit { is_negated: bool },
+    Word { is_negated: bool },
+    Space { is_negated: bool },
+}
+
+impl ClassItem {
+    fn contains(&self, ch: char) -> bool {
+        match *self {
+            ClassItem::Range(first, last) => first <= ch && ch <= last,
+            ClassItem::Digit { is_negated } => ch.is_ascii_digit() != is_negated,
+            ClassItem::Word { is_negated } => (ch.is_ascii_alphanumeric() || ch == '_') != is_negated,
+            ClassItem::Space { is_negated } => ch.is_whitespace() != is_negated,
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    offset: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.offset += 1;
+        Some(ch)
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        let is_match = expected.chars()
+            .enumerate()
+            .all(|(index, ch)| self.chars.get(self.offset + index) == Some(&ch));
+        if is_match {
+            self.offset += expected.chars().count();
+        }
+        is_match
+    }
+
+    fn alternation(&mut self) -> Result<Node, PatternError> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.eat("|") {
+            alternatives.push(self.sequence()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    fn sequence(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.repeat(atom)?);
+        }
+        Ok(Node::Sequence(nodes))
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        let offset = self.offset;
+        Ok(match self.next().unwrap() {
+            '(' => {
+                self.eat("?:");
+                let node = self.alternation()?;
+                if !self.eat(")") {
+                    return Err(PatternError::new("unmatched `(`", offset));
+                }
+                node
+            }
+            '[' => self.class()?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => match self.escape()? {
+                Some(item) => Node::Class { items: vec![item], is_negated: false },
+                None => Node::Char(self.chars[self.offset - 1]),
+            },
+            '*' | '+' | '?' => return Err(PatternError::new("nothing to repeat", offset)),
+            ch => Node::Char(ch),
+        })
+    }
+
+    /// Parses the escape following a backslash, producing a class item if
+    /// the escape denotes a set of characters, or `None` if it denotes the
+    /// character preceding the current offset.
+    fn escape(&mut self) -> Result<Option<ClassItem>, PatternError> {
+        let offset = self.offset;
+        let ch = self.next()
+            .ok_or_else(|| PatternError::new("trailing backslash", offset))?;
+        Ok(Some(match ch {
+            'd' => ClassItem::Digit { is_negated: false },
+            'D' => ClassItem::Digit { is_negated: true },
+            'w' => ClassItem::Word { is_negated: false },
+            'W' => ClassItem::Word { is_negated: true },
+            's' => ClassItem::Space { is_negated: false },
+            'S' => ClassItem::Space { is_negated: true },
+            'n' => ClassItem::Range('\n', '\n'),
+            'r' => ClassItem::Range('\r', '\r'),
+            't' => ClassItem::Range('\t', '\t'),
+            ch if ch.is_ascii_alphanumeric() => {
+                return Err(PatternError::new("unsupported escape", offset - 1));
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    fn class(&mut self) -> Result<Node, PatternError> {
+        let offset = self.offset - 1;
+        let is_negated = self.eat("^");
+        let mut items = Vec::new();
+        loop {
+            let first = match self.next() {
+                Some(']') if !items.is_empty() => break,
+                Some('\\') => match self.escape()? {
+                    Some(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                    None => self.chars[self.offset - 1],
+                },
+                Some(ch) => ch,
+                None => return Err(PatternError::new("unterminated character class", offset)),
+            };
+            let is_range = self.peek() == Some('-')
+                && self.chars.get(self.offset + 1).is_some_and(|ch| *ch != ']');
+            if !is_range {
+                items.push(ClassItem::Range(first, first));
+                continue;
+            }
+            self.offset += 1;
+            let last = match self.next() {
+                Some('\\') => match self.escape()? {
+                    Some(ClassItem::Range(last, _)) => last,
+                    Some(_) => return Err(PatternError::new("invalid character class range", offset)),
+                    None => self.chars[self.offset - 1],
+                },
+                Some(ch) => ch,
+                None => return Err(PatternError::new("unterminated character class", offset)),
+            };
+            if last < first {
+                return Err(PatternError::new("invalid character class range", offset));
+            }
+            items.push(ClassItem::Range(first, last));
+        }
+        Ok(Node::Class { items, is_negated })
+    }
+
+    fn repeat(&mut self, node: Node) -> Result<Node, PatternError> {
+        let offset = self.offset;
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.bounds() {
+                Some(bounds) => bounds,
+                None => return Ok(node),
+            },
+            _ => return Ok(node),
+        };
+        if self.offset == offset {
+            self.offset += 1;
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(PatternError::new("invalid repetition range", offset));
+        }
+        if let Node::Start | Node::End = node {
+            return Err(PatternError::new("nothing to repeat", offset));
+        }
+        let is_lazy = self.eat("?");
+        let node = Node::Repeat { node: Box::new(node), min, max, is_lazy };
+        match self.peek() {
+            Some('*') | Some('+') | Some('?') => Err(PatternError::new("nothing to repeat", self.offset)),
+            _ => Ok(node),
+        }
+    }
+
+    /// Parses `{n}`, `{n,}` or `{n,m}`, or leaves the offset unchanged and
+    /// returns `None` if no such bounds are at the current offset, in which
+    /// case the brace is treated as a literal character.
+    fn bounds(&mut self) -> Option<(usize, Option<usize>)> {
+        let offset = self.offset;
+        self.offset += 1;
+        let bounds = self.number().and_then(|min| {
+            if self.eat("}") {
+                return Some((min, Some(min)));
+            }
+            if !self.eat(",") {
+                return None;
+            }
+            if self.eat("}") {
+                return Some((min, None));
+            }
+            let max = self.number()?;
+            if self.eat("}") { Some((min, Some(max))) } else { None }
+        });
+        if bounds.is_none() {
+            self.offset = offset;
+        }
+        bounds
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.offset;
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.offset += 1;
+        }
+        self.chars[start..self.offset].iter().collect::<String>().parse().ok()
+    }
+}
+
+/// Determines whether `node` matches `text` at offset `at`, such that
+/// `next` accepts the offset right after the match.
+fn matches(node: &Node, text: &[char], at: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    match *node {
+        Node::Char(ch) => text.get(at) == Some(&ch) && next(at + 1),
+        Node::Any => text.get(at).is_some_and(|ch| *ch != '\n' && *ch != '\r') && next(at + 1),
+        Node::Class { ref items, is_negated } => match text.get(at) {
+            Some(ch) => items.iter().any(|item| item.contains(*ch)) != is_negated && next(at + 1),
+            None => false,
+        },
+        Node::Start => at == 0 && next(at),
+        Node::End => at == text.len() && next(at),
+        Node::Sequence(ref nodes) => matches_sequence(nodes, text, at, next),
+        Node::Alternation(ref nodes) => nodes.iter().any(|node| matches(node, text, at, next)),
+        Node::Repeat { ref node, min, max, is_lazy } => {
+            matches_repeat(node, min, max, is_lazy, 0, text, at, next)
+        }
+    }
+}
+
+fn matches_sequence(nodes: &[Node], text: &[char], at: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    match nodes.split_first() {
+        Some((first, rest)) => matches(first, text, at, &mut |at| matches_sequence(rest, text, at, next)),
+        None => next(at),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn matches_repeat(
+    node: &Node,
+    min: usize,
+    max: Option<usize>,
+    is_lazy: bool,
+    count: usize,
+    text: &[char],
+    at: usize,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let more = |next: &mut dyn FnMut(usize) -> bool| {
+        max.is_none_or(|max| count < max) && matches(node, text, at, &mut |end| {
+            // Empty matches cannot make progress, which is why they only
+            // count towards the minimum number of repetitions.
+            (end != at || count < min) && matches_repeat(node, min, max, is_lazy, count + 1, text, end, next)
+        })
+    };
+    if count < min {
+        return more(next);
+    }
+    if is_lazy {
+        return next(at) || more(next);
+    }
+    more(next) || next(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    #[test]
+    fn is_match() {
+        let cases: &[(&str, &[&str], &[&str])] = &[
+            ("^[A-Za-z]+$", &["Name", "x"], &["", "Name1", "two words"]),
+            ("ab", &["ab", "cabd"], &["a", "ba"]),
+            ("^\\d{3}-\\d{2,}$", &["123-45", "123-4567"], &["12-34", "123-4", "abc-de"]),
+            ("^(?:http|coap)s?://", &["https://a", "coap://b"], &["mqtt://c", "xhttp://"]),
+            ("^[^\\s.]*\\.txt$", &["a.txt", ".txt"], &["a b.txt", "a.b.txt", "a.txt.gz"]),
+            ("^a(b|)*c$", &["ac", "abbc"], &["abd"]),
+            ("^x{2}?$", &["xx"], &["x", "xxx"]),
+        ];
+        for (source, matching, other) in cases {
+            let pattern = Pattern::parse(source).unwrap();
+            for text in matching.iter() {
+                assert!(pattern.is_match(text), "`{}` should match `{}`", source, text);
+            }
+            for text in other.iter() {
+                assert!(!pattern.is_match(text), "`{}` should not match `{}`", source, text);
+            }
+        }
+
+        let errors = ["(a", "a)", "*a", "[a", "[z-a]", "a{3,2}", "\\b", "a**"].iter()
+            .map(|source| Pattern::parse(source).unwrap_err().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(errors, vec![
+            "unmatched `(` at character 0",
+            "unmatched `)` at character 1",
+            "nothing to repeat at character 0",
+            "unterminated character class at character 0",
+            "invalid character class range at character 0",
+            "invalid repetition range at character 1",
+            "unsupported escape at character 0",
+            "nothing to repeat at character 2",
+        ]);
+    }
+}
@@ -1,5 +1,9 @@
 use arspec_parser::Span;
-use crate::spec::{Attribute, TypeRef};
+use crate::payload::{validate, xml, Data};
+use crate::spec::{Attribute, Specification, TypeRef, Value};
+use std::borrow::Cow;
+use std::ptr;
+use super::{VerificationError, verify};
 
 /// A record type definition.
 #[derive(Debug)]
@@ -7,7 +11,14 @@ pub struct Record<'a> {
     /// Name of record type.
     pub name: Span<'a>,
 
-    /// Field definitions.
+    /// Names of generic type parameters, if any.
+    pub generic_parameters: Vec<Span<'a>>,
+
+    /// Record extended by this record, if any.
+    pub base: Option<TypeRef<'a>>,
+
+    /// Field definitions, starting with those inherited from any extended
+    /// records.
     pub entries: Vec<RecordEntry<'a>>,
 
     /// Any attributes.
@@ -20,10 +31,92 @@ impl<'a> Record<'a> {
     pub fn new(name: Span<'a>, attributes: Vec<Attribute<'a>>) -> Self {
         Record {
             name,
+            generic_parameters: Vec::new(),
+            base: None,
             entries: Vec::new(),
             attributes,
         }
     }
+
+    /// Iterates through the entries defined by this record itself, excluding
+    /// any inherited entries.
+    pub fn own_entries(&self) -> impl Iterator<Item = &RecordEntry<'a>> {
+        self.entries.iter().filter(|entry| entry.inherited_from.is_none())
+    }
+
+    /// Gets the entries of the record referred to by `instance`, which must
+    /// refer to this record, with any generic parameters replaced by the type
+    /// parameters of `instance`.
+    pub fn entries_of(&self, instance: &TypeRef<'a>) -> Cow<'_, [RecordEntry<'a>]> {
+        if self.generic_parameters.is_empty() {
+            return Cow::Borrowed(&self.entries);
+        }
+        Cow::Owned(self.entries.iter()
+            .map(|entry| RecordEntry {
+                type_ref: self.substitute(&entry.type_ref, instance),
+                ..entry.clone()
+            })
+            .collect())
+    }
+
+    /// Replaces any generic parameters in `type_ref`, which must be part of
+    /// the definition of this record, with the corresponding type parameters
+    /// of `instance`, which must refer to this record.
+    pub fn substitute(&self, type_ref: &TypeRef<'a>, instance: &TypeRef<'a>) -> TypeRef<'a> {
+        if type_ref.params.is_empty() {
+            let argument = self.generic_parameters.iter()
+                .position(|parameter| parameter.as_str() == type_ref.name.as_str())
+                .and_then(|index| instance.params.get(index));
+            if let Some(argument) = argument {
+                return argument.clone();
+            }
+        }
+        TypeRef {
+            name: type_ref.name.clone(),
+            params: type_ref.params.iter()
+                .map(|param| self.substitute(param, instance))
+                .collect(),
+        }
+    }
+
+    pub fn verify(&self, spec: &Specification<'a>) -> Result<(), VerificationError> {
+        for entry in self.own_entries() {
+            verify_arity(spec, &entry.type_ref, &self.generic_parameters)?;
+            entry.verify_default(spec, &self.generic_parameters)?;
+        }
+        if let Some(ref base) = self.base {
+            let mut record = spec.resolve_record(&base.name)
+                .ok_or_else(|| VerificationError::NoSuchRecordToExtend {
+                    base: base.name.to_excerpt(),
+                })?;
+            verify_arity(spec, base, &self.generic_parameters)?;
+            let mut visited = vec![record];
+            while let Some(ref base) = record.base {
+                record = match spec.resolve_record(&base.name) {
+                    Some(record) => record,
+                    None => break,
+                };
+                if ptr::eq(record, self) {
+                    return Err(VerificationError::RecordBaseCycle {
+                        record: self.name.to_excerpt(),
+                    });
+                }
+                if visited.iter().any(|other| ptr::eq(*other, record)) {
+                    break;
+                }
+                visited.push(record);
+            }
+        }
+
+        verify::find_duplicate(&self.entries)
+            .map(|dup| Err(VerificationError::RecordEntryDuplicate {
+                original: dup.original.name.to_excerpt(),
+                duplicate: dup.duplicate.name.to_excerpt(),
+            }))
+            .unwrap_or(Ok(()))?;
+
+        xml::verify_record(spec, self)
+    }
 }
 
 impl<'a> AsRef<str> for Record<'a> {
@@ -34,7 +127,7 @@ impl<'a> AsRef<str> for Record<'a> {
 }
 
 /// A record type definition field.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RecordEntry<'a> {
     /// Field name.
     pub name: Span<'a>,
@@ -42,6 +135,182 @@ pub struct RecordEntry<'a> {
     /// Field type reference.
     pub type_ref: TypeRef<'a>,
 
+    /// Whether the field may be absent, as indicated by a question mark
+    /// following its name.
+    pub is_optional: bool,
+
+    /// Value assumed if the field is absent, if any.
+    pub default: Option<Value<'a>>,
+
+    /// Name of the extended record defining this field, if inherited.
+    pub inherited_from: Option<Span<'a>>,
+
     /// Any attributes.
     pub attributes: Vec<Attribute<'a>>,
-}
\ No newline at end of file
+}
+
+impl<'a> RecordEntry<'a> {
+    /// Whether payloads may lack this field, which is the case if it is
+    /// optional, has a default value or is of type `Option<T>`.
+    pub fn may_be_absent(&self) -> bool {
+        self.is_optional || self.default.is_some() || self.is_option()
+    }
+
+    /// Whether payloads may give `null` as the value of this field, which is
+    /// the case if it is optional or of type `Option<T>`.
+    pub fn may_be_null(&self) -> bool {
+        self.is_optional || self.is_option()
+    }
+
+    /// Type of the values of this field when present and not `null`, which is
+    /// `T` if of type `Option<T>`.
+    pub fn value_type(&self) -> &TypeRef<'a> {
+        if self.is_option() {
+            &self.type_ref.params[0]
+        } else {
+            &self.type_ref
+        }
+    }
+
+    fn is_option(&self) -> bool {
+        self.type_ref.name.as_str() == "Option" && self.type_ref.params.len() == 1
+    }
+
+    /// Verifies that the default value of this field, if any, is a valid
+    /// value of its type.
+    fn verify_default(
+        &self,
+        spec: &Specification<'a>,
+        generic_parameters: &[Span<'a>],
+    ) -> Result<(), VerificationError> {
+        let default = match self.default {
+            Some(Value::Null) if self.may_be_null() => return Ok(()),
+            Some(ref default) => default,
+            None => return Ok(()),
+        };
+        let reason = if self.type_ref.params.is_empty() && generic_parameters.iter()
+            .any(|parameter| parameter.as_str() == self.type_ref.name.as_str())
+        {
+            Some("fields of generic parameter types cannot have default values".into())
+        } else {
+            validate(spec, &self.type_ref, &Data::from(default))
+                .into_iter()
+                .next()
+                .map(|violation| if violation.pointer.is_empty() {
+                    violation.message
+                } else {
+                    format!("at `{}`, {}", violation.pointer, violation.message)
+                })
+        };
+        match reason {
+            Some(reason) => Err(VerificationError::RecordEntryDefaultInvalid {
+                entry: self.name.to_excerpt(),
+                reason,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> AsRef<str> for RecordEntry<'a> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// Verifies that every type referred to by `type_ref` or its type parameters
+/// exists and is given as many type parameters as it has generic parameters.
+///
+/// Names among `generic_parameters` refer to those parameters rather than to
+/// any defined types, and take no type parameters.
+pub(crate) fn verify_arity<'a>(
+    spec: &Specification<'a>,
+    type_ref: &TypeRef<'a>,
+    generic_parameters: &[Span<'a>],
+) -> Result<(), VerificationError> {
+    let name = type_ref.name.as_str();
+    let expected = if generic_parameters.iter().any(|parameter| parameter.as_str() == name) {
+        0
+    } else {
+        spec.verify_type_unambiguous(&type_ref.name)?;
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            record.generic_parameters.len()
+        } else if let Some(primitive) = spec.resolve_primitive(&type_ref.name) {
+            primitive.generic_parameters.len()
+        } else if spec.resolve_enum(&type_ref.name).is_some() || spec.resolve_alias(&type_ref.name).is_some() {
+            0
+        } else {
+            builtin_arity(name).ok_or_else(|| VerificationError::NoSuchType {
+                type_ref: type_ref.name.to_excerpt(),
+            })?
+        }
+    };
+    if expected != type_ref.params.len() {
+        return Err(VerificationError::TypeParameterCountMismatch {
+            type_ref: type_ref.as_span().to_excerpt(),
+            expected,
+        });
+    }
+    for param in &type_ref.params {
+        verify_arity(spec, param, generic_parameters)?;
+    }
+    Ok(())
+}
+
+/// Determines the number of type parameters taken by built-in primitive
+/// `name`, unless no such primitive exists.
+fn builtin_arity(name: &str) -> Option<usize> {
+    Some(match name {
+        "Boolean" | "Float32" | "Float64" | "String" => 0,
+        "I8" | "I16" | "I32" | "I64" | "U8" | "U16" | "U32" | "U64" => 0,
+        "List" | "Set" | "Option" => 1,
+        "Map" => 2,
+        _ => return None,
+    })
+}
+
+/// Copies the entries of all records extended by each record in `spec` into
+/// it, preceding its own entries, with the entries of the most distant
+/// extended record coming first.
+///
+/// Any generic parameters of extended records are replaced by the type
+/// parameters given where those records are extended. Extended records that
+/// do not exist are ignored, as are any repeated records of cyclic
+/// extensions.
+pub(crate) fn inherit_entries(spec: &mut Specification) {
+    let inherited = spec.records.iter()
+        .map(|record| {
+            let mut bases: Vec<(&Record, TypeRef)> = Vec::new();
+            let mut base = record.base.clone();
+            while let Some(type_ref) = base {
+                let base_record = match spec.resolve_record(&type_ref.name) {
+                    Some(base_record) => base_record,
+                    None => break,
+                };
+                if ptr::eq(base_record, record) || bases.iter().any(|(other, _)| ptr::eq(*other, base_record)) {
+                    break;
+                }
+                base = base_record.base.as_ref()
+                    .map(|next| base_record.substitute(next, &type_ref));
+                bases.push((base_record, type_ref));
+            }
+            bases.iter().rev()
+                .flat_map(|(base_record, type_ref)| base_record.entries_of(type_ref)
+                    .into_owned()
+                    .into_iter()
+                    .map(move |entry| RecordEntry {
+                        inherited_from: Some(base_record.name.clone()),
+                        ..entry
+                    }))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for (record, mut entries) in spec.records.iter_mut().zip(inherited) {
+        if !entries.is_empty() {
+            entries.append(&mut record.entries);
+            record.entries = entries;
+        }
+    }
+}
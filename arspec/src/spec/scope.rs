@@ -0,0 +1,133 @@
+use arspec_parser::{Span, Text};
+use std::path::{Component, Path, PathBuf};
+use super::{Attribute, value};
+
+/// Package, import and use declarations of a single source [`Text`][txt].
+///
+/// Source texts without any such declarations belong to the root package,
+/// which has the empty string as name.
+///
+/// [txt]: ../../arspec_parser/struct.Text.html
+#[derive(Debug)]
+pub struct Scope<'a> {
+    /// Source text to which the declarations apply.
+    pub source: &'a Text,
+
+    /// Package declaration, if any.
+    pub package: Option<Package<'a>>,
+
+    /// Import declarations.
+    pub imports: Vec<Import<'a>>,
+
+    /// Use declarations.
+    pub uses: Vec<Use<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    /// Create new empty scope for given `source` text.
+    #[inline]
+    pub fn new(source: &'a Text) -> Self {
+        Scope {
+            source,
+            package: None,
+            imports: Vec::new(),
+            uses: Vec::new(),
+        }
+    }
+
+    /// Name of declared package, or `""` if no package is declared.
+    #[inline]
+    pub fn package_name(&self) -> &'a str {
+        self.package.as_ref().map_or("", |package| package.name.as_str())
+    }
+
+    /// Whether or not given `text` is imported into this scope.
+    pub fn imports_text(&self, text: &Text) -> bool {
+        if self.imports.is_empty() {
+            return false;
+        }
+        let path = normalize(Path::new(&*text.name));
+        self.imports.iter().any(|import| import.resolve() == path)
+    }
+}
+
+/// A package declaration, such as `package Arrowhead.Core;`.
+#[derive(Debug)]
+pub struct Package<'a> {
+    /// Dot-separated package name.
+    pub name: Span<'a>,
+
+    /// Any attributes.
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+/// An import declaration, such as `import "common.ahfs";`.
+#[derive(Debug)]
+pub struct Import<'a> {
+    /// Quoted path of imported source file, relative to importing file.
+    pub path: Span<'a>,
+
+    /// Any attributes.
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> Import<'a> {
+    /// Unquoted path of imported source file, relative to importing file.
+    #[inline]
+    pub fn path(&self) -> String {
+        value::unescape(self.path.as_str())
+    }
+
+    /// Path of imported source file, resolved relative to the name of the
+    /// source text containing the import.
+    pub fn resolve(&self) -> PathBuf {
+        let importer = Path::new(&*self.path.source.name);
+        let base = importer.parent().unwrap_or_else(|| Path::new(""));
+        normalize(&base.join(self.path()))
+    }
+}
+
+/// A use declaration, such as `use Arrowhead.Core::ArrowheadSystem;`.
+#[derive(Debug)]
+pub struct Use<'a> {
+    /// Name of package containing used definition.
+    pub package: Span<'a>,
+
+    /// Name of used definition.
+    pub name: Span<'a>,
+
+    /// Any attributes.
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+/// Removes any `.` components of `path`, and resolves any `..` components
+/// that are preceded by regular components.
+///
+/// Paths are normalized before being compared, making sure that paths such
+/// as `a/../b/c.ahfs` and `./b/c.ahfs` are regarded as equal.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => { out.pop(); }
+                _ => out.push(".."),
+            },
+            component => out.push(component.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(super::normalize(Path::new("./a/b/../c.ahfs")), Path::new("a/c.ahfs"));
+        assert_eq!(super::normalize(Path::new("../a/./c.ahfs")), Path::new("../a/c.ahfs"));
+        assert_eq!(super::normalize(Path::new("/a/b/../../c.ahfs")), Path::new("/c.ahfs"));
+    }
+}
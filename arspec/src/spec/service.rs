@@ -45,6 +45,10 @@ pub struct ServiceMethod<'a> {
     /// Method output type, unless no output is provided.
     pub output: Option<TypeRef<'a>>,
 
+    /// Types of the error payloads that may be provided instead of the
+    /// output, as listed after `throws`.
+    pub errors: Vec<TypeRef<'a>>,
+
     /// Any attributes.
     pub attributes: Vec<Attribute<'a>>,
 }
@@ -57,9 +61,16 @@ impl<'a> ServiceMethod<'a> {
             name,
             input: None,
             output: None,
+            errors: Vec::new(),
             attributes,
         }
     }
+
+    /// Looks up error type with given `name` among the errors of this
+    /// method.
+    pub fn error(&self, name: &str) -> Option<&TypeRef<'a>> {
+        self.errors.iter().find(|error| error.name.as_str() == name)
+    }
 }
 
 /// A named abstract [`Service`][srv] reference.
@@ -1,4 +1,5 @@
 use arspec_parser::{Span, Range};
+use std::fmt;
 
 /// A type reference.
 ///
@@ -6,12 +7,12 @@ use arspec_parser::{Span, Range};
 /// other type.
 ///
 /// [rec]: struct.Record.html
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TypeRef<'a> {
     /// Name of type referred to.
     pub name: Span<'a>,
 
-    /// Generic type parameters, if any.
+    /// Generic type parameters, if any, in the order they are given.
     pub params: Vec<TypeRef<'a>>,
 }
 
@@ -61,6 +62,24 @@ impl<'a> TypeRef<'a> {
     }
 }
 
+/// Renders referred type without any insignificant whitespace.
+///
+/// Unlike [`TypeRef::as_str`], this works also for type references with
+/// substituted type parameters, which may come from different places.
+impl<'a> fmt::Display for TypeRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name.as_str())?;
+        for (index, param) in self.params.iter().enumerate() {
+            f.write_str(if index == 0 { "<" } else { ", " })?;
+            fmt::Display::fmt(param, f)?;
+        }
+        if !self.params.is_empty() {
+            f.write_str(">")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use arspec_parser::Text;
@@ -117,11 +136,11 @@ mod tests {
                 name: span(54..57),
                 params: vec![
                     TypeRef {
-                        name: span(66..72),
+                        name: span(58..64),
                         params: Vec::new(),
                     },
                     TypeRef {
-                        name: span(58..64),
+                        name: span(66..72),
                         params: Vec::new(),
                     }
                 ],
@@ -132,5 +151,6 @@ mod tests {
         assert_eq!("Option<Integer>", b.as_str());
         assert_eq!("Any<Integer, Option<Integer>>", c.as_str());
         assert_eq!("Map<String, String>", d.as_str());
+        assert_eq!("Map<String, String>", d.to_string());
     }
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 use arspec_parser::Span;
+use std::fmt;
 
 /// Value specification.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Value<'a> {
     /// The absence of a meaningful value.
     Null,
@@ -24,3 +25,76 @@ pub enum Value<'a> {
     /// A map of values, which is really an ordered list of name/value pairs.
     Map(Box<[(Span<'a>, Value<'a>)]>),
 }
+
+impl<'a> Value<'a> {
+    /// Returns unescaped string contents, if this is a `Value::String`.
+    pub fn as_string(&self) -> Option<String> {
+        match *self {
+            Value::String(ref span) => Some(unescape(span.as_str())),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Value<'a> {
+    /// Writes value as it would appear in a specification, without any
+    /// insignificant whitespace.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Null => f.write_str("null"),
+            Value::Boolean(ref span) |
+            Value::Integer(ref span) |
+            Value::Float(ref span) |
+            Value::String(ref span) => f.write_str(span.as_str()),
+            Value::List(ref items) => {
+                f.write_str("[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Value::Map(ref entries) => {
+                f.write_str("{")?;
+                for (index, (name, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", name.as_str(), value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// Removes surrounding quotes from string `literal` and resolves any escape
+/// sequences in it.
+pub(crate) fn unescape(literal: &str) -> String {
+    let input = literal.get(1..literal.len().saturating_sub(1)).unwrap_or("");
+    let mut output = String::with_capacity(input.len());
+
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        output.push(match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let code = chars.by_ref().take(4).collect::<String>();
+                u32::from_str_radix(&code, 16).ok()
+                    .and_then(std::char::from_u32)
+                    .unwrap_or(std::char::REPLACEMENT_CHARACTER)
+            }
+            Some(ch) => ch,
+            None => break,
+        });
+    }
+    output
+}
@@ -0,0 +1,235 @@
+use arspec::payload;
+use arspec::spec::{parser, Route, Specification};
+use crate::http::{Request, Response};
+use crate::log;
+use std::net::TcpStream;
+use std::time::Duration;
+use super::{Error, find_http_json_routes, find_service, locate_project};
+
+/// Checks whether the HTTP/JSON service at `base_url` conforms to the
+/// specification of the service named in `args` at index 0.
+///
+/// Every method of the service implementation is called once with a
+/// generated example request, after which the status code and body of its
+/// response are checked. Responses with status codes mapped to method
+/// errors are checked against those errors rather than method outputs.
+/// Path parameters, such as `{id}`, are substituted with `1`.
+pub fn conform(args: &[&str], base_url: &str) -> arspec::Result {
+    let name = match args {
+        &[name] => name,
+        _ => return Err(Error::ConformArgCountNot1.into()),
+    };
+    let base = BaseUrl::parse(base_url)
+        .ok_or_else(|| Error::BaseUrlInvalid(base_url.into()))?;
+
+    let project = locate_project()?;
+    let corpus = project.corpus()?;
+    let spec = parser::parse(&corpus)?;
+    spec.verify()?;
+
+    let service = find_service(&spec, name)?;
+    let routes = find_http_json_routes(&spec, service)?;
+
+    let mut failures = 0;
+    for route in &routes {
+        let path = format!("{}{}", base.path, substitute_parameters(&route.path));
+        let label = format!("{} ({} {})", route.service_method.name.as_str(), route.method, path);
+        let problems = match call(&spec, &base, route, &path) {
+            Ok((status, problems)) => {
+                if problems.is_empty() {
+                    log::completion(&format!("[pass] {} -> {}", label, status));
+                    continue;
+                }
+                log::anomaly(&format!("[fail] {} -> {}", label, status));
+                problems
+            }
+            Err(error) => {
+                log::anomaly(&format!("[fail] {}", label));
+                vec![format!("Request failed: {}", error)]
+            }
+        };
+        for problem in problems {
+            log::anomaly(&format!("         {}", problem));
+        }
+        failures += 1;
+    }
+
+    log::completion(&format!("Methods passed: {} of {}", routes.len() - failures, routes.len()));
+    if failures > 0 {
+        return Err(Error::ConformanceFailed(failures).into());
+    }
+    Ok(())
+}
+
+/// Calls `route` at `path` of `base`, returning the response status code
+/// and any problems found with the response.
+fn call(spec: &Specification, base: &BaseUrl, route: &Route, path: &str) -> arspec::Result<(u16, Vec<String>)> {
+    let body = route.service_method.input.as_ref()
+        .map(|input| payload::json::encode(&payload::example(spec, input)).into_bytes())
+        .unwrap_or_default();
+    let request = Request { method: route.method.clone(), path: path.into(), body };
+
+    let mut stream = TcpStream::connect((base.host.as_str(), base.port))?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(10)))?;
+    request.write_to(&base.authority(), &mut stream)?;
+    let response = Response::read_from(&mut stream)?;
+
+    let mut problems = Vec::new();
+    let error = route.errors.iter()
+        .find(|(_, status)| *status == response.status)
+        .map(|(error, _)| *error);
+    if error.is_none() && (response.status < 200 || response.status > 299) {
+        problems.push(format!("Expected status 2xx, found {}", response.status));
+        return Ok((response.status, problems));
+    }
+    let output = match error.or(route.service_method.output.as_ref()) {
+        Some(output) => output,
+        None => return Ok((response.status, problems)),
+    };
+    if let Some(content_type) = response.header("content-type") {
+        if !content_type.to_ascii_lowercase().contains("json") {
+            problems.push(format!("Expected JSON content type, found `{}`", content_type));
+        }
+    }
+    let body = match String::from_utf8(response.body) {
+        Ok(body) => body,
+        Err(_) => {
+            problems.push("Response body is not valid UTF-8".into());
+            return Ok((response.status, problems));
+        }
+    };
+    match payload::json::decode(&body) {
+        Ok(data) => problems.extend(payload::validate(spec, output, &data)
+            .into_iter()
+            .map(|violation| violation.to_string())),
+        Err(error) => problems.push(error.to_string()),
+    }
+    Ok((response.status, problems))
+}
+
+/// Replaces every path segment enclosed in braces, such as `{id}`, with `1`.
+fn substitute_parameters(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "1" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A parsed `http` URL.
+struct BaseUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl BaseUrl {
+    /// Parses `url`, such as `http://localhost:8080/prefix`.
+    fn parse(url: &str) -> Option<BaseUrl> {
+        let rest = url.strip_prefix("http://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rfind(':') {
+            Some(index) if !authority.ends_with(']') => {
+                (&authority[..index], authority[index + 1..].parse().ok()?)
+            }
+            _ => (authority, 80),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(BaseUrl {
+            host: host.trim_start_matches('[').trim_end_matches(']').into(),
+            port,
+            path: path.trim_end_matches('/').into(),
+        })
+    }
+
+    /// Host and port, as given in HTTP `Host` headers.
+    fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::thread;
+    use super::*;
+
+    /// Serves one connection on a local port, answering with a response
+    /// made up of `head` and `body` once a request has been read.
+    fn serve(head: &str, body: &str) -> BaseUrl {
+        let response = format!("{}\r\nContent-Length: {}\r\n\r\n{}", head, body.len(), body);
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            Request::read_from(&mut stream).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        BaseUrl::parse(&format!("http://127.0.0.1:{}/", port)).unwrap()
+    }
+
+    #[test]
+    fn call_routes() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Query { Name: String }\n",
+                    "record Entry { Name: String, Port: U16 }\n",
+                    "record NotFound { Reason: String }\n",
+                    "service Registry {\n",
+                    "    method Find(Query): Entry throws NotFound;\n",
+                    "}\n",
+                    "implement Registry using HTTP/JSON {\n",
+                    "    method Find { Method: \"POST\", Path: \"/find\", Errors: { NotFound: 404 } }\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let routes = find_http_json_routes(&spec, find_service(&spec, "Registry").unwrap()).unwrap();
+        let call = |head, body| call(&spec, &serve(head, body), &routes[0], "/find").unwrap();
+
+        let json = "HTTP/1.1 200 OK\r\nContent-Type: application/json";
+        assert_eq!(call(json, "{\"Name\":\"A\",\"Port\":8080}"), (200, Vec::new()));
+
+        let (status, problems) = call(json, "{\"Name\":\"A\",\"Port\":-1}");
+        assert_eq!(status, 200);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("/Port"), "{}", problems[0]);
+
+        let (_, problems) = call("HTTP/1.1 200 OK\r\nContent-Type: text/plain", "{}");
+        assert!(problems[0].contains("`text/plain`"));
+        assert!(problems.len() > 1);
+
+        assert_eq!(call("HTTP/1.1 404 Not Found", "{\"Reason\":\"none\"}"), (404, Vec::new()));
+        assert_eq!(call("HTTP/1.1 404 Not Found", "{}").1.len(), 1);
+        assert_eq!(call("HTTP/1.1 500 Internal Server Error", ""),
+            (500, vec!["Expected status 2xx, found 500".to_string()]));
+
+        assert!(find_service(&spec, "Unknown").is_err());
+    }
+
+    #[test]
+    fn parse_base_url() {
+        let base = BaseUrl::parse("http://localhost:8080/prefix/").unwrap();
+        assert_eq!((base.host.as_str(), base.port, base.path.as_str()), ("localhost", 8080, "/prefix"));
+        let base = BaseUrl::parse("http://[::1]").unwrap();
+        assert_eq!((base.authority().as_str(), base.path.as_str()), ("[::1]:80", ""));
+        assert!(BaseUrl::parse("https://localhost").is_none());
+        assert!(BaseUrl::parse("http://:80").is_none());
+        assert_eq!(substitute_parameters("/entries/{id}/name"), "/entries/1/name");
+    }
+}
@@ -1,32 +1,113 @@
+use arspec::project::GENERATORS;
 use std::fmt;
 
 /// Describes a project application error.
 #[derive(Debug)]
 pub enum Error {
+    BaseUrlInvalid(String),
+    ConformArgCountNot1,
+    ConformanceFailed(usize),
+    DiffArgCountNot2,
     DocArgCountNot0,
+    EncodingUnknown(String),
+    ExampleArgCountNot1,
+    GenArgCountNot1,
+    GeneratorUnknown(String),
+    HttpJsonImplementationNotFound(String),
+    ImportArgCountNot2,
+    ImportFormatUnknown(String),
+    JavaPackageInvalid(String),
+    JavaPackageMissing,
     ListArgCountNot0,
+    MockArgCountNot1,
     NewArgCountNot1,
+    PayloadInvalid(usize),
+    RecordNotFound(String),
+    ServiceNotFound(String),
     StatusArgCountNot0,
+    ValidateArgCountNot2,
 }
 
 impl arspec::Error for Error {
     fn code(&self) -> &'static str {
         match *self {
+            Error::BaseUrlInvalid(_) => "R802",
+            Error::ConformArgCountNot1 => "R801",
+            Error::ConformanceFailed(_) => "R803",
+            Error::DiffArgCountNot2 => "R301",
             Error::DocArgCountNot0 => "R201",
+            Error::EncodingUnknown(_) => "R003",
+            Error::ExampleArgCountNot1 => "R601",
+            Error::GenArgCountNot1 => "R901",
+            Error::GeneratorUnknown(_) => "R902",
+            Error::HttpJsonImplementationNotFound(_) => "R502",
+            Error::ImportArgCountNot2 => "RA01",
+            Error::ImportFormatUnknown(_) => "RA02",
+            Error::JavaPackageInvalid(_) => "R904",
+            Error::JavaPackageMissing => "R903",
             Error::ListArgCountNot0 => "R101",
+            Error::MockArgCountNot1 => "R501",
             Error::NewArgCountNot1 => "R401",
+            Error::PayloadInvalid(_) => "R702",
+            Error::RecordNotFound(_) => "R002",
+            Error::ServiceNotFound(_) => "R001",
             Error::StatusArgCountNot0 => "RC01",
+            Error::ValidateArgCountNot2 => "R701",
         }
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(match *self {
-            Error::DocArgCountNot0 => "`doc` takes not arguments",
-            Error::ListArgCountNot0 => "`list` takes no arguments",
-            Error::NewArgCountNot1 => "`new` requires <path> argument",
-            Error::StatusArgCountNot0 => "`status` takes no arguments",
-        })
+        match *self {
+            Error::BaseUrlInvalid(ref url) => {
+                write!(f, "Invalid base URL `{}`, expected `http://<host>[:<port>][/<path>]`", url)
+            }
+            Error::ConformArgCountNot1 => f.write_str("`conform` requires <service> argument"),
+            Error::ConformanceFailed(count) => {
+                write!(f, "{} method{} failed to conform", count, if count == 1 { "" } else { "s" })
+            }
+            Error::DiffArgCountNot2 => f.write_str("`diff` requires <old> and <new> arguments"),
+            Error::DocArgCountNot0 => f.write_str("`doc` takes not arguments"),
+            Error::EncodingUnknown(ref name) => {
+                write!(f, "Unknown encoding `{}`, expected `CBOR`, `JSON` or `XML`", name)
+            }
+            Error::ExampleArgCountNot1 => f.write_str("`example` requires <record> argument"),
+            Error::GenArgCountNot1 => f.write_str("`gen` requires <generator> argument"),
+            Error::GeneratorUnknown(ref name) => {
+                let generators = GENERATORS.iter()
+                    .filter(|generator| **generator != "doc")
+                    .map(|generator| format!("`{}`", generator))
+                    .collect::<Vec<_>>();
+                write!(f, "Unknown generator `{}`, expected one of {}", name, generators.join(", "))
+            }
+            Error::HttpJsonImplementationNotFound(ref name) => {
+                write!(f, "Service `{}` has no HTTP/JSON implementation", name)
+            }
+            Error::ImportArgCountNot2 => {
+                f.write_str("`import` requires <format> and <file> arguments")
+            }
+            Error::ImportFormatUnknown(ref name) => {
+                write!(f, "Unknown import format `{}`, expected `jsonschema` or `openapi`", name)
+            }
+            Error::JavaPackageInvalid(ref name) => {
+                write!(f, "Invalid Java package name `{}`", name)
+            }
+            Error::JavaPackageMissing => f.write_str(
+                "`gen java` requires --package flag or `java: { Package: \"<name>\" }` generator setting",
+            ),
+            Error::ListArgCountNot0 => f.write_str("`list` takes no arguments"),
+            Error::MockArgCountNot1 => f.write_str("`mock` requires <service> argument"),
+            Error::NewArgCountNot1 => f.write_str("`new` requires <path> argument"),
+            Error::PayloadInvalid(count) => {
+                write!(f, "Payload has {} violation{}", count, if count == 1 { "" } else { "s" })
+            }
+            Error::RecordNotFound(ref name) => write!(f, "No record named `{}` exists", name),
+            Error::ServiceNotFound(ref name) => write!(f, "No service named `{}` exists", name),
+            Error::StatusArgCountNot0 => f.write_str("`status` takes no arguments"),
+            Error::ValidateArgCountNot2 => {
+                f.write_str("`validate` requires <record> and <file> arguments")
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,39 @@
+use arspec::payload::{self, Encoding};
+use arspec::spec::{parser, TypeRef};
+use super::{Error, find_record, locate_project};
+
+/// Prints example payload of record named in `args` at index 0, using the
+/// named `encoding`.
+///
+/// CBOR payloads are printed both in diagnostic notation and as hexadecimal
+/// digits.
+pub fn example(args: &[&str], encoding: Option<String>) -> arspec::Result {
+    let name = match args {
+        &[name] => name,
+        _ => return Err(Error::ExampleArgCountNot1.into()),
+    };
+    let encoding = match encoding {
+        Some(encoding) => Encoding::parse(&encoding)
+            .ok_or(Error::EncodingUnknown(encoding))?,
+        None => Encoding::Json,
+    };
+
+    let project = locate_project()?;
+    let corpus = project.corpus()?;
+    let spec = parser::parse(&corpus)?;
+
+    let record = find_record(&spec, name)?;
+    let data = payload::record_example(&spec, record);
+    match encoding {
+        Encoding::Cbor => {
+            println!("{}", payload::cbor::diagnostic(&data));
+            println!("{}", payload::cbor::hex(&payload::cbor::encode(&data)));
+        }
+        Encoding::Json => println!("{}", payload::json::encode(&data)),
+        Encoding::Xml => {
+            let type_ref = TypeRef::new(record.name.clone());
+            print!("{}", payload::xml::encode(&spec, &type_ref, &data));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use arspec::project::{GENERATORS, Project, Setting};
+use arspec::spec::{parser, Implement, Service, Specification};
+use arspec_gen::{asyncapi, cddl, java, openapi, rust, typescript, xsd};
+use crate::log;
+use std::fs;
+use std::path::Path;
+use std::ptr;
+use super::{Error, locate_project};
+
+/// Runs generator named in `args` at index 0, writing its output files to a
+/// folder of the same name in the project target folder.
+///
+/// Only services and implementations defined by the project itself, rather
+/// than by its dependencies, are considered. `package` is used by the `java`
+/// generator, which otherwise reads it from the project configuration.
+pub fn gen(args: &[&str], package: Option<String>) -> arspec::Result {
+    let generator = match args {
+        &[generator] => generator,
+        _ => return Err(Error::GenArgCountNot1.into()),
+    };
+    if generator == "doc" || !GENERATORS.contains(&generator) {
+        return Err(Error::GeneratorUnknown(generator.into()).into());
+    }
+
+    let project = locate_project()?;
+    let corpus = project.corpus()?;
+    let spec = parser::parse(&corpus)?;
+    spec.verify()?;
+
+    let target_path = project.target().join(generator);
+    match generator {
+        "asyncapi" => gen_asyncapi(&project, &spec, &target_path),
+        "cddl" => gen_per_service(&project, &spec, &target_path, "CBOR", "cddl", cddl::generate),
+        "java" => gen_java(&project, &spec, &target_path, package),
+        "openapi" => gen_openapi(&project, &spec, &target_path),
+        "rust-client" => gen_rust_client(&project, &spec, &target_path),
+        "rust-server" => gen_rust_server(&project, &spec, &target_path),
+        "typescript" => gen_typescript(&project, &spec, &target_path),
+        "xsd" => gen_per_service(&project, &spec, &target_path, "XML", "xsd", xsd::generate),
+        _ => unreachable!(),
+    }
+}
+
+/// Generates one AsyncAPI file per MQTT implementation.
+fn gen_asyncapi(project: &Project, spec: &Specification, target_path: &Path) -> arspec::Result {
+    let implementations = spec.implementations.iter()
+        .filter(|implementation| implementation.protocol.as_str() == "MQTT")
+        .filter(|implementation| project.contains(implementation.name.source))
+        .collect::<Vec<_>>();
+    if implementations.is_empty() {
+        log::anomaly(&"No MQTT service implementations found, nothing generated.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    let version = &project.configuration().version;
+    for implementation in implementations {
+        let name = spec.qualify(&implementation.name).replace("::", ".");
+        let path = target_path.join(format!("{}-{}.json", name, implementation.encoding.as_str()));
+        fs::write(&path, asyncapi::generate(spec, implementation, version))?;
+        log::completion(&format!("Generated {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Generates one OpenAPI file per HTTP implementation.
+fn gen_openapi(project: &Project, spec: &Specification, target_path: &Path) -> arspec::Result {
+    let implementations = spec.implementations.iter()
+        .filter(|implementation| implementation.protocol.as_str() == "HTTP")
+        .filter(|implementation| project.contains(implementation.name.source))
+        .collect::<Vec<_>>();
+    if implementations.is_empty() {
+        log::anomaly(&"No HTTP service implementations found, nothing generated.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    let version = &project.configuration().version;
+    for implementation in implementations {
+        let name = spec.qualify(&implementation.name).replace("::", ".");
+        let path = target_path.join(format!("{}-{}.json", name, implementation.encoding.as_str()));
+        fs::write(&path, openapi::generate(spec, implementation, version))?;
+        log::completion(&format!("Generated {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Generates one Java source file per record, enum and service, placing
+/// each in the folder of its `package`.
+///
+/// The package and any primitive type mappings not given as arguments are
+/// read from the `java` entry of the project `ProjectGenerators` section,
+/// which may look as follows:
+///
+/// ```text
+/// java: {
+///     Package: "eu.arrowhead.example",
+///     Primitives: { U64: "java.math.BigInteger" },
+/// }
+/// ```
+fn gen_java(
+    project: &Project,
+    spec: &Specification,
+    target_path: &Path,
+    package: Option<String>,
+) -> arspec::Result {
+    let settings = project.configuration().generator("java");
+    let package = package
+        .or_else(|| settings
+            .and_then(|settings| settings.get("Package"))
+            .and_then(|package| package.as_str())
+            .map(|package| package.into()))
+        .ok_or(Error::JavaPackageMissing)?;
+    let is_valid = package.split('.').all(|segment| {
+        let mut chars = segment.chars();
+        chars.next().is_some_and(|ch| ch.is_alphabetic() || ch == '_')
+            && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+    });
+    if !is_valid {
+        return Err(Error::JavaPackageInvalid(package).into());
+    }
+    let primitives = match settings.and_then(|settings| settings.get("Primitives")) {
+        Some(Setting::Map(entries)) => entries.iter()
+            .filter_map(|(name, type_)| type_.as_str().map(|type_| (name.clone(), type_.into())))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let options = java::Options { package, primitives };
+    let java = java::Java::new(spec, &options);
+
+    let mut files = Vec::new();
+    for record in spec.records.iter().filter(|record| project.contains(record.name.source)) {
+        files.push((record.name.as_str(), java.record(record)));
+    }
+    for enum_ in spec.enums.iter().filter(|enum_| project.contains(enum_.name.source)) {
+        files.push((enum_.name.as_str(), java.enum_(enum_)));
+    }
+    for service in spec.services.iter().filter(|service| project.contains(service.name.source)) {
+        files.push((service.name.as_str(), java.service(service)));
+    }
+    if files.is_empty() {
+        log::anomaly(&"No records, enums or services found, nothing generated.");
+        return Ok(());
+    }
+
+    let package_path = options.package.split('.')
+        .fold(target_path.to_path_buf(), |path, segment| path.join(segment));
+    fs::create_dir_all(&package_path)?;
+    for (name, source) in files {
+        let path = package_path.join(format!("{}.java", name));
+        fs::write(&path, source)?;
+        log::completion(&format!("Generated {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Generates a Rust module declaring a client per HTTP/JSON implementation.
+fn gen_rust_client(project: &Project, spec: &Specification, target_path: &Path) -> arspec::Result {
+    let implementations = http_json_implementations(project, spec);
+    if implementations.is_empty() {
+        log::anomaly(&"No HTTP/JSON service implementations found, nothing generated.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    let path = target_path.join("client.rs");
+    fs::write(&path, rust::client::generate(spec, &implementations))?;
+    log::completion(&format!("Generated {}", path.display()));
+    Ok(())
+}
+
+/// Generates a Rust module declaring a server skeleton per system.
+fn gen_rust_server(project: &Project, spec: &Specification, target_path: &Path) -> arspec::Result {
+    let systems = spec.systems.iter()
+        .filter(|system| project.contains(system.name.source))
+        .collect::<Vec<_>>();
+    if systems.is_empty() {
+        log::anomaly(&"No systems found, nothing generated.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    for system in systems {
+        for service_ref in &system.produces {
+            let is_served = spec.resolve_service(&service_ref.name)
+                .is_some_and(|service| rust::server::implementation_of(spec, service).is_some());
+            if !is_served {
+                log::anomaly(&format!(
+                    "Service `{}` of system `{}` has no HTTP/CBOR or HTTP/JSON implementation, leaving it out.",
+                    service_ref.name.as_str(),
+                    system.name.as_str(),
+                ));
+            }
+        }
+        let path = target_path.join(rust::server::file_name(spec, system));
+        fs::write(&path, rust::server::generate(spec, system))?;
+        log::completion(&format!("Generated {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Generates a TypeScript module declaring every record and enum, as well
+/// as a client class per HTTP/JSON implementation.
+fn gen_typescript(project: &Project, spec: &Specification, target_path: &Path) -> arspec::Result {
+    let records = spec.records.iter()
+        .filter(|record| project.contains(record.name.source))
+        .collect::<Vec<_>>();
+    let enums = spec.enums.iter()
+        .filter(|enum_| project.contains(enum_.name.source))
+        .collect::<Vec<_>>();
+    let implementations = http_json_implementations(project, spec);
+    if records.is_empty() && enums.is_empty() && implementations.is_empty() {
+        log::anomaly(&"No records, enums or HTTP/JSON service implementations found, nothing generated.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    let path = target_path.join("index.ts");
+    fs::write(&path, typescript::generate(spec, &records, &enums, &implementations))?;
+    log::completion(&format!("Generated {}", path.display()));
+    Ok(())
+}
+
+/// Collects the HTTP/JSON implementations defined by `project`.
+fn http_json_implementations<'a, 'b>(project: &Project, spec: &'b Specification<'a>) -> Vec<&'b Implement<'a>> {
+    spec.implementations.iter()
+        .filter(|implementation| {
+            implementation.protocol.as_str() == "HTTP" && implementation.encoding.as_str() == "JSON"
+        })
+        .filter(|implementation| project.contains(implementation.name.source))
+        .collect()
+}
+
+/// Uses `generate` to produce one file with given `extension` per service
+/// with an implementation using the named payload `encoding`.
+fn gen_per_service<'a>(
+    project: &Project,
+    spec: &Specification<'a>,
+    target_path: &Path,
+    encoding: &str,
+    extension: &str,
+    generate: fn(&Specification<'a>, &Service<'a>) -> String,
+) -> arspec::Result {
+    let mut services: Vec<&Service> = Vec::new();
+    for implementation in &spec.implementations {
+        if implementation.encoding.as_str() != encoding || !project.contains(implementation.name.source) {
+            continue;
+        }
+        if let Some(service) = spec.resolve_service(&implementation.name) {
+            if !services.iter().any(|other| ptr::eq(*other, service)) {
+                services.push(service);
+            }
+        }
+    }
+    if services.is_empty() {
+        log::anomaly(&format!("No {} service implementations found, nothing generated.", encoding));
+        return Ok(());
+    }
+
+    fs::create_dir_all(target_path)?;
+    for service in services {
+        let name = spec.qualify(&service.name).replace("::", ".");
+        let path = target_path.join(format!("{}.{}", name, extension));
+        fs::write(&path, generate(spec, service))?;
+        log::completion(&format!("Generated {}", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use arspec_import::{jsonschema, openapi};
+use crate::log;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use super::{Error, locate_project};
+
+/// Names of document formats supported by `import`.
+const FORMATS: &[&str] = &["jsonschema", "openapi"];
+
+/// Imports document at path in `args` at index 1, of the format named in
+/// `args` at index 0, writing the resulting specification source to the
+/// project root folder.
+///
+/// The source file is named after the imported service, and is never
+/// overwritten if it already exists.
+pub fn import(args: &[&str]) -> arspec::Result {
+    let (format, path) = match args {
+        &[format, path] => (format, path),
+        _ => return Err(Error::ImportArgCountNot2.into()),
+    };
+    if !FORMATS.contains(&format) {
+        return Err(Error::ImportFormatUnknown(format.into()).into());
+    }
+
+    let project = locate_project()?;
+    let input = fs::read_to_string(path)?;
+    let document = arspec_import::decode(&input)?;
+    let import = match format {
+        "jsonschema" => {
+            let name = Path::new(path).file_stem()
+                .map_or("Schema".into(), |stem| stem.to_string_lossy());
+            jsonschema::import(&document, &name)?
+        }
+        "openapi" => openapi::import(&document)?,
+        _ => unreachable!(),
+    };
+    let target_path = project.root().join(format!("{}.ahfs", import.name));
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&target_path)?;
+    for warning in &import.warnings {
+        log::anomaly(warning);
+    }
+    file.write_all(import.source.as_bytes())?;
+    log::completion(&format!(
+        "Imported {} into {} ({} warning{}).",
+        path, target_path.display(), import.warnings.len(),
+        if import.warnings.len() == 1 { "" } else { "s" },
+    ));
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use arspec::payload::{self, Data};
+use arspec::spec::{parser, Route, Specification};
+use crate::http::{Request, Response};
+use crate::log;
+use std::net::TcpListener;
+use std::time::Duration;
+use super::{Error, find_http_json_routes, find_service, locate_project};
+
+/// How long the mock server waits for a client to send or receive data
+/// before giving up on its connection, letting other clients be served.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves HTTP/JSON implementation of service named in `args` at index 0,
+/// at given local `port`, until the process is terminated.
+pub fn mock(args: &[&str], port: u16) -> arspec::Result {
+    let name = match args {
+        &[name] => name,
+        _ => return Err(Error::MockArgCountNot1.into()),
+    };
+
+    let project = locate_project()?;
+    let corpus = project.corpus()?;
+    let spec = parser::parse(&corpus)?;
+    spec.verify()?;
+
+    let service = find_service(&spec, name)?;
+    let routes = find_http_json_routes(&spec, service)?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    log::completion(&format!("Mocking `{}` at http://127.0.0.1:{}", service.name.as_str(), port));
+    for route in &routes {
+        log::completion(&format!("  {:<7} {}", route.method, route.path));
+    }
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::anomaly(&format!("Failed to accept connection: {}", error));
+                continue;
+            }
+        };
+        let timeout = stream.set_read_timeout(Some(CONNECTION_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(CONNECTION_TIMEOUT)));
+        if let Err(error) = timeout {
+            log::anomaly(&format!("Failed to configure connection: {}", error));
+            continue;
+        }
+        let request = match Request::read_from(&mut stream) {
+            Ok(request) => request,
+            Err(error) => {
+                log::anomaly(&format!("Failed to read request: {}", error));
+                continue;
+            }
+        };
+        let response = respond(&spec, &routes, &request);
+        let message = format!("{} {} -> {}", request.method, request.path, response.status);
+        if response.status < 400 {
+            log::completion(&message);
+        } else {
+            log::anomaly(&message);
+        }
+        if let Err(error) = response.write_to(&mut stream) {
+            log::anomaly(&format!("Failed to write response: {}", error));
+        }
+    }
+    Ok(())
+}
+
+/// Produces response to `request` by routing it to one of `routes`.
+fn respond(spec: &Specification, routes: &[Route], request: &Request) -> Response {
+    let candidates = routes.iter()
+        .filter(|route| is_path_match(&route.path, &request.path))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return error(404, "no such route", Vec::new());
+    }
+    let route = match candidates.into_iter().find(|route| route.method == request.method) {
+        Some(route) => route,
+        None => return error(405, "method not allowed", Vec::new()),
+    };
+
+    if let Some(ref input) = route.service_method.input {
+        let body = match String::from_utf8(request.body.clone()) {
+            Ok(body) => body,
+            Err(_) => return error(400, "request body is not valid UTF-8", Vec::new()),
+        };
+        let data = match payload::json::decode(&body) {
+            Ok(data) => data,
+            Err(err) => return error(400, &err.to_string(), Vec::new()),
+        };
+        let violations = payload::validate(spec, input, &data);
+        if !violations.is_empty() {
+            return error(400, "request body does not match input type", violations);
+        }
+    }
+
+    match route.service_method.output {
+        Some(ref output) => {
+            Response::json(200, payload::json::encode(&payload::example(spec, output)))
+        }
+        None => Response {
+            status: 204,
+            headers: Vec::new(),
+            body: Vec::new(),
+        },
+    }
+}
+
+/// Creates JSON error response.
+fn error(status: u16, message: &str, violations: Vec<payload::Violation>) -> Response {
+    let mut entries = vec![("error".to_string(), Data::String(message.into()))];
+    if !violations.is_empty() {
+        entries.push(("violations".into(), Data::List(violations.into_iter()
+            .map(|violation| Data::Map(vec![
+                ("pointer".into(), Data::String(violation.pointer)),
+                ("message".into(), Data::String(violation.message)),
+            ]))
+            .collect())));
+    }
+    Response::json(status, payload::json::encode(&Data::Map(entries)))
+}
+
+/// Whether or not `path` matches route `template`, in which any segment
+/// enclosed in braces, such as `{id}`, matches any one path segment.
+fn is_path_match(template: &str, path: &str) -> bool {
+    let mut template = template.trim_end_matches('/').split('/');
+    let mut path = path.trim_end_matches('/').split('/');
+    loop {
+        match (template.next(), path.next()) {
+            (Some(a), Some(b)) => {
+                let is_parameter = a.starts_with('{') && a.ends_with('}') && !b.is_empty();
+                if !is_parameter && a != b {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn path_match() {
+        assert!(is_path_match("/registry/find", "/registry/find"));
+        assert!(is_path_match("/registry/find", "/registry/find/"));
+        assert!(is_path_match("/entries/{id}", "/entries/42"));
+        assert!(is_path_match("/entries/{id}/name", "/entries/42/name"));
+        assert!(is_path_match("/", ""));
+        assert!(!is_path_match("/entries/{id}", "/entries/"));
+        assert!(!is_path_match("/entries/{id}", "/entries/42/name"));
+        assert!(!is_path_match("/entries/{id}", "/entry/42"));
+        assert!(!is_path_match("/registry/find", "/registry"));
+    }
+
+    #[test]
+    fn respond_to_requests() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Query { Name: String }\n",
+                    "record Entry { Name: String, Port: U16 }\n",
+                    "service Registry {\n",
+                    "    method Find(Query): Entry;\n",
+                    "    method Remove();\n",
+                    "}\n",
+                    "implement Registry using HTTP/JSON {\n",
+                    "    property BasePath: \"/registry/\";\n",
+                    "    method Find { Method: \"POST\", Path: \"/find\" }\n",
+                    "    method Remove { Method: \"DELETE\", Path: \"/entries/{id}\" }\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let routes = find_http_json_routes(&spec, &spec.services[0]).unwrap();
+        let status = |method: &str, path: &str, body: &str| respond(&spec, &routes, &Request {
+            method: method.into(),
+            path: path.into(),
+            body: body.as_bytes().to_vec(),
+        }).status;
+
+        assert_eq!(status("POST", "/registry/find", "{\"Name\":\"A\"}"), 200);
+        assert_eq!(status("POST", "/registry/find", "{\"Name\":1}"), 400);
+        assert_eq!(status("POST", "/registry/find", "{"), 400);
+        assert_eq!(status("GET", "/registry/find", ""), 405);
+        assert_eq!(status("DELETE", "/registry/entries/42", ""), 204);
+        assert_eq!(status("DELETE", "/registry/entries", ""), 404);
+        assert_eq!(status("POST", "/find", "{\"Name\":\"A\"}"), 404);
+    }
+}
@@ -1,32 +1,160 @@
+mod conform;
 mod error;
+mod example;
+mod gen;
+mod import;
+mod mock;
+mod validate;
 
+pub use self::conform::conform;
 pub use self::error::Error;
-
-use arspec::spec::parser;
-use arspec::project::Project;
+pub use self::example::example;
+pub use self::gen::gen;
+pub use self::import::import;
+pub use self::mock::mock;
+pub use self::validate::validate;
+
+use arspec::spec::{parser, Diff, Impact, Record, Route, Service, Specification};
+use arspec::project::{Project, Version};
 use arspec_doc::{fonts, FontStyle, FontWeight, html, scripts, styles, svg};
-use arspec_parser::Corpus;
 use crate::log;
 use std::fs;
 use std::io;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::ptr;
+
+/// Compares the specifications of the projects at the paths in `args` at
+/// indices 0 and 1, and recommends a new version for the latter.
+pub fn diff(args: &[&str]) -> arspec::Result {
+    let (old_path, new_path) = match args {
+        &[old_path, new_path] => (old_path, new_path),
+        _ => return Err(Error::DiffArgCountNot2.into()),
+    };
+
+    let old_project = Project::locate(old_path)?;
+    let new_project = Project::locate(new_path)?;
+    let old_corpus = old_project.corpus()?;
+    let new_corpus = new_project.corpus()?;
+    let old_spec = parser::parse(&old_corpus)?;
+    let new_spec = parser::parse(&new_corpus)?;
+
+    let diff = compare(&old_project, &old_spec, &new_project, &new_spec);
+    for change in &diff.changes {
+        match change.impact {
+            Impact::Breaking => log::anomaly(&format!("[breaking]   {}", change)),
+            Impact::Compatible => log::completion(&format!("[compatible] {}", change)),
+        }
+    }
+    let breaking = diff.changes.iter()
+        .filter(|change| change.impact == Impact::Breaking)
+        .count();
+    log::completion(&format!(
+        "Changes found: {} ({} breaking)",
+        diff.changes.len(), breaking,
+    ));
+
+    let old_conf = old_project.configuration();
+    let new_conf = new_project.configuration();
+    match Version::parse(&old_conf.version) {
+        Some(old_version) => {
+            let recommended = diff.recommend_version(&old_version);
+            log::suggestion(&format!(
+                "Recommended ProjectVersion: {} (previously {})",
+                recommended, old_version,
+            ));
+            let is_sufficient = diff.changes.is_empty() || Version::parse(&new_conf.version)
+                .is_some_and(|new_version| new_version >= recommended);
+            if !is_sufficient {
+                log::anomaly(&format!(
+                    "ProjectVersion of new project is {}, which is less than recommended.",
+                    new_conf.version,
+                ));
+            }
+        }
+        None => log::anomaly(&format!(
+            "ProjectVersion `{}` of old project is not a valid semantic version.",
+            old_conf.version,
+        )),
+    }
+    Ok(())
+}
+
+/// Compares `old_spec` of `old_project` with `new_spec` of `new_project`.
+///
+/// Changes to projects depended upon are ignored, as those are the concern
+/// of their own projects.
+fn compare(
+    old_project: &Project,
+    old_spec: &Specification,
+    new_project: &Project,
+    new_spec: &Specification,
+) -> Diff {
+    let mut diff = Diff::new(old_spec, new_spec);
+    diff.changes.retain(|change| {
+        old_project.contains(&change.excerpt.text) || new_project.contains(&change.excerpt.text)
+    });
+    diff
+}
 
 /// Generates documentation files.
-pub fn doc(args: &[&str], skip_verification: bool) -> arspec::Result {
+///
+/// If a `since` path is given, the documentation also describes any changes
+/// made since the version of the project at that path.
+pub fn doc(args: &[&str], skip_verification: bool, since: Option<String>) -> arspec::Result {
     if args.len() != 0 {
         return Err(Error::DocArgCountNot0.into());
     }
 
     // Load project.
-    let project = Project::locate(".")?;
+    let project = locate_project()?;
     let target_path = &project.target();
     fs::create_dir_all(&target_path)?;
 
-    // Load project specification.
-    let corpus = Corpus::read_from(project.files()?.iter())?;
+    // Load project specification, including that of any dependencies.
+    let dependencies = project.dependencies()?;
+    let corpus = project.corpus_with(&dependencies)?;
     let spec = parser::parse(&corpus)?;
 
+    // Load earlier project version and compare it with the current.
+    let old_project = since.map(Project::locate).transpose()?;
+    let old_corpus = old_project.as_ref().map(|project| project.corpus()).transpose()?;
+    let old_spec = old_corpus.as_ref().map(parser::parse).transpose()?;
+    let diff = match (&old_project, &old_spec) {
+        (Some(old_project), Some(old_spec)) => Some(compare(old_project, old_spec, &project, &spec)),
+        _ => None,
+    };
+    let changes = old_project.as_ref().zip(diff.as_ref()).map(|(old_project, diff)| {
+        html::Changes::new(&old_project.configuration().version, &diff.changes)
+    });
+
+    // Documentation of dependencies is referred to rather than regenerated.
+    let externals = {
+        let target_path = target_path.canonicalize()?;
+        let mut externals = Vec::with_capacity(dependencies.len());
+        for dependency in &dependencies {
+            let dependency_target = dependency.root()
+                .canonicalize()?
+                .join(&dependency.configuration().target);
+            let mut prefix = relative_path(&target_path, &dependency_target)
+                .to_string_lossy()
+                .replace('\\', "/");
+            prefix.push('/');
+            externals.push((dependency.root().to_path_buf(), prefix));
+        }
+        externals
+    };
+    let context = html::Context {
+        spec: &spec,
+        externals: &externals,
+        theme: project.configuration()
+            .generator("doc")
+            .and_then(|doc| doc.get("Theme"))
+            .and_then(|theme| theme.as_str())
+            .unwrap_or("light"),
+        changes: changes.as_ref(),
+    };
+
     // Verify specification correctness.
     if !skip_verification {
         &spec.verify()?;
@@ -40,37 +168,49 @@ pub fn doc(args: &[&str], skip_verification: bool) -> arspec::Result {
         fs::create_dir_all(figures_path)?;
 
         for enum_ in &spec.enums {
+            if context.is_external(enum_.name.source) {
+                continue;
+            }
             buffer.clear();
             svg::render(&enum_, &mut buffer)?;
             let target_path = figures_path
-                .join(format!("enum-{}.svg", enum_.name.as_str()));
+                .join(format!("enum-{}.svg", html::file_stem_of(&spec.qualify(&enum_.name))));
 
             fs::write(target_path, &mut buffer)?;
         }
 
         for record in &spec.records {
+            if context.is_external(record.name.source) {
+                continue;
+            }
             buffer.clear();
             svg::render(&record, &mut buffer)?;
             let target_path = figures_path
-                .join(format!("record-{}.svg", record.name.as_str()));
+                .join(format!("record-{}.svg", html::file_stem_of(&spec.qualify(&record.name))));
 
             fs::write(target_path, &mut buffer)?;
         }
 
         for service in &spec.services {
+            if context.is_external(service.name.source) {
+                continue;
+            }
             buffer.clear();
             svg::render(&service, &mut buffer)?;
             let target_path = figures_path
-                .join(format!("service-{}.svg", service.name.as_str()));
+                .join(format!("service-{}.svg", html::file_stem_of(&spec.qualify(&service.name))));
 
             fs::write(target_path, &mut buffer)?;
         }
 
         for system in &spec.systems {
+            if context.is_external(system.name.source) {
+                continue;
+            }
             buffer.clear();
             svg::render(&system, &mut buffer)?;
             let target_path = figures_path
-                .join(format!("system-{}.svg", system.name.as_str()));
+                .join(format!("system-{}.svg", html::file_stem_of(&spec.qualify(&system.name))));
 
             fs::write(target_path, &mut buffer)?;
         }
@@ -145,24 +285,107 @@ pub fn doc(args: &[&str], skip_verification: bool) -> arspec::Result {
         ];
 
         for system in &spec.systems {
+            if context.is_external(system.name.source) {
+                continue;
+            }
+            buffer.clear();
+            html::render(&system, &context, scripts, styles, &mut buffer)?;
+            let target_path = target_path
+                .join(format!("{}-SysD.html", html::file_stem_of(&spec.qualify(&system.name))));
+
+            fs::write(target_path, &mut buffer)?;
+        }
+
+        for service in &spec.services {
+            if context.is_external(service.name.source) {
+                continue;
+            }
             buffer.clear();
-            html::render(&system, scripts, styles, &mut buffer)?;
+            html::render(&service, &context, scripts, styles, &mut buffer)?;
             let target_path = target_path
-                .join(format!("{}-SysD.html", system.name.as_str()));
+                .join(format!("{}-SD.html", html::file_stem_of(&spec.qualify(&service.name))));
 
             fs::write(target_path, &mut buffer)?;
         }
+
+        if let Some(ref changes) = changes {
+            buffer.clear();
+            html::render(changes, &context, scripts, styles, &mut buffer)?;
+            fs::write(target_path.join("Changes.html"), &mut buffer)?;
+        }
     }
 
     Ok(())
 }
 
+/// Locates project in current folder or any of its parents, and logs any
+/// issues with its configuration.
+fn locate_project() -> arspec::Result<Project> {
+    let project = Project::locate(".")?;
+    for diagnostic in &project.configuration().diagnostics {
+        log::anomaly(diagnostic);
+    }
+    Ok(project)
+}
+
+/// Finds record with given `name` in `spec`, which may be qualified with the
+/// name of its package, as in `Team.A::RecordA`.
+fn find_record<'a, 'b>(spec: &'b Specification<'a>, name: &str) -> arspec::Result<&'b Record<'a>> {
+    spec.records.iter()
+        .find(|record| record.name.as_str() == name || spec.qualify(&record.name) == name)
+        .ok_or_else(|| Error::RecordNotFound(name.into()).into())
+}
+
+/// Finds service with given `name` in `spec`, which may be qualified with
+/// the name of its package, as in `Team.A::ServiceA`.
+fn find_service<'a, 'b>(spec: &'b Specification<'a>, name: &str) -> arspec::Result<&'b Service<'a>> {
+    spec.services.iter()
+        .find(|service| service.name.as_str() == name || spec.qualify(&service.name) == name)
+        .ok_or_else(|| Error::ServiceNotFound(name.into()).into())
+}
+
+/// Collects the routes of the HTTP/JSON implementation of `service` in
+/// `spec`.
+///
+/// Implementation methods lacking `Method` or `Path` data, or not matching
+/// any method of `service`, are ignored.
+fn find_http_json_routes<'a, 'b>(
+    spec: &'b Specification<'a>,
+    service: &'b Service<'a>,
+) -> arspec::Result<Vec<Route<'a, 'b>>> {
+    let implementation = spec.implementations.iter()
+        .filter(|implementation| {
+            implementation.protocol.as_str() == "HTTP" && implementation.encoding.as_str() == "JSON"
+        })
+        .find(|implementation| spec.resolve_service(&implementation.name)
+            .is_some_and(|implemented| ptr::eq(implemented, service)))
+        .ok_or_else(|| Error::HttpJsonImplementationNotFound(service.name.as_str().into()))?;
+
+    Ok(implementation.routes(service))
+}
+
+/// Determines path of `to` relative to `from`, assuming both are canonical.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let mut from = from.components().peekable();
+    let mut to = to.components().peekable();
+    while let (Some(a), Some(b)) = (from.peek(), to.peek()) {
+        if a != b {
+            break;
+        }
+        from.next();
+        to.next();
+    }
+    let mut path: PathBuf = from.map(|_| Component::ParentDir).collect();
+    path.extend(to);
+    path
+}
+
 /// Prints list of all project source files and exits.
 pub fn list(args: &[&str]) -> arspec::Result {
     if args.len() != 0 {
         return Err(Error::ListArgCountNot0.into());
     }
-    let project = Project::locate(".")?;
+    let project = locate_project()?;
     let files = project.files()?;
     for file in files.iter() {
         log::completion(&file.canonicalize()?.to_string_lossy());
@@ -205,19 +428,31 @@ pub fn status(args: &[&str]) -> arspec::Result {
     if args.len() != 0 {
         return Err(Error::StatusArgCountNot0.into());
     }
-    let project = Project::locate(".")?;
+    let project = locate_project()?;
     let conf = project.configuration();
     log::completion(&format!(
         concat!(
               "Project:     {}\n",
             "  Version:     {}\n",
             "  Path:        {}\n",
+            "  Target:      {}\n",
             "  Description: {}\n",
         ),
         conf.name,
         conf.version,
         project.root().canonicalize()?.to_string_lossy(),
+        conf.target,
         conf.description.as_ref().unwrap_or(&"<none>".to_string()),
     ));
+    if !conf.dependencies.is_empty() {
+        let mut dependencies = String::from("Dependencies:\n");
+        for dependency in &conf.dependencies {
+            dependencies.push_str(&format!(
+                "  {:<12} {}\n",
+                dependency.version, dependency.path,
+            ));
+        }
+        log::completion(&dependencies);
+    }
     Ok(())
 }
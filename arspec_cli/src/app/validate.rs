@@ -0,0 +1,45 @@
+use arspec::payload::{self, Encoding};
+use arspec::spec::{parser, TypeRef};
+use crate::log;
+use std::fs;
+use std::path::Path;
+use super::{Error, find_record, locate_project};
+
+/// Validates payload in file at path in `args` at index 1 against record
+/// named in `args` at index 0.
+///
+/// If no `encoding` is named, it is determined from the extension of the
+/// file, or assumed to be JSON if the extension is not recognized.
+pub fn validate(args: &[&str], encoding: Option<String>) -> arspec::Result {
+    let (name, path) = match args {
+        &[name, path] => (name, Path::new(path)),
+        _ => return Err(Error::ValidateArgCountNot2.into()),
+    };
+    let encoding = match encoding {
+        Some(encoding) => Encoding::parse(&encoding)
+            .ok_or(Error::EncodingUnknown(encoding))?,
+        None => path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Encoding::parse)
+            .unwrap_or(Encoding::Json),
+    };
+
+    let project = locate_project()?;
+    let corpus = project.corpus()?;
+    let spec = parser::parse(&corpus)?;
+
+    let record = find_record(&spec, name)?;
+    let type_ref = TypeRef::new(record.name.clone());
+    let input = fs::read(path)?;
+    let data = payload::decode(&spec, &type_ref, encoding, &input)?;
+    let violations = payload::validate(&spec, &type_ref, &data);
+    for violation in &violations {
+        log::anomaly(violation);
+    }
+    if !violations.is_empty() {
+        return Err(Error::PayloadInvalid(violations.len()).into());
+    }
+    log::completion(&format!("{} payload {} is a valid `{}`.",
+        encoding, path.display(), record.name.as_str()));
+    Ok(())
+}
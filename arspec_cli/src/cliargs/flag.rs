@@ -21,12 +21,15 @@ pub struct Flag {
     pub out: FlagOut,
 }
 
+/// Formats flag, padding its long form to the formatter width, if any, or to
+/// 19 characters otherwise.
 impl fmt::Display for Flag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = f.width().unwrap_or(19);
         if let Some(short) = self.short {
             write!(f, "-{:2} ", short)?;
         } else {
-            f.write_str("  ")?;
+            f.write_str("    ")?;
         }
         let mut len = self.long.len();
         write!(f, "--{}", self.long)?;
@@ -35,7 +38,7 @@ impl fmt::Display for Flag {
             len += 1 + value_name.len();
         }
         write!(f, "{:offset$} {}",
-               "", self.description, offset = 19usize.saturating_sub(len))
+               "", self.description, offset = width.saturating_sub(len))
     }
 }
 
@@ -102,6 +105,18 @@ impl FlagOut {
         }
     }
 
+    /// Creates new flag cell, holding a `u16` value.
+    pub fn new_u16(cell: &FlagCell<u16>) -> Self {
+        let cell: Rc<_> = cell.0.clone();
+        FlagOut {
+            name: None,
+            out: Box::new(move |s| {
+                cell.set(Some(s.parse().map_err(Box::new)?));
+                Ok(())
+            }),
+        }
+    }
+
     /// Name of flag out value.
     #[inline]
     pub fn name(&self) -> Option<&str> {
@@ -50,4 +50,46 @@ mod tests {
         ]).unwrap();
         assert_eq!(Some(true), verbose.take());
     }
+
+    #[test]
+    fn help_columns() {
+        let flag = FlagCell::<String>::new();
+        let parser = Parser {
+            description: "A CLI application.",
+            rules: &[
+                Rule {
+                    name: "doc",
+                    name_details: "",
+                    description: "Doc.",
+                    flags: &[
+                        Flag { short: Some("s"), long: "skip", description: "Skip.", out: FlagOut::new_string(&flag) },
+                        Flag { short: None, long: "since", description: "Since.", out: FlagOut::new_string(&flag) },
+                    ],
+                    callback: &|_args| Ok(()),
+                },
+                Rule {
+                    name: "validate",
+                    name_details: "<record> <file>",
+                    description: "Validate.",
+                    flags: &[],
+                    callback: &|_args| Ok(()),
+                },
+            ],
+        };
+        let mut help = String::new();
+        let text = parser.to_string();
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                chars.by_ref().find(|ch| ch.is_ascii_alphabetic());
+            } else {
+                help.push(ch);
+            }
+        }
+        let columns = help.lines()
+            .skip(1)
+            .map(|line| line.find(char::is_uppercase).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![28, 30, 30, 28], "{}", help);
+    }
 }
\ No newline at end of file
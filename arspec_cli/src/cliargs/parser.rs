@@ -1,6 +1,9 @@
 use crate::cliargs::{Result, Rule, Error};
 use std::fmt;
 
+/// Minimum width of the column of rule names and name details.
+const RULE_NAME_WIDTH_MIN: usize = 23;
+
 /// A description of how command line arguments are to be parsed.
 pub struct Parser<'a> {
     /// Parser description.
@@ -43,8 +46,12 @@ impl<'a> Parser<'a> {
 impl<'a> fmt::Display for Parser<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "{}", self.description)?;
+        // Leaves at least two spaces between rule names and descriptions.
+        let width = self.rules.iter()
+            .map(|rule| rule.name.len() + 1 + rule.name_details.len() + 1)
+            .fold(RULE_NAME_WIDTH_MIN, usize::max);
         for rule in self.rules {
-            writeln!(f, "{}", rule)?;
+            writeln!(f, "{:width$}", rule, width = width)?;
         }
         Ok(())
     }
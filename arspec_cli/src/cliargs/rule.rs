@@ -61,9 +61,13 @@ fn parse_flags(flags: &[Flag], args: &[&str]) -> Result {
     Ok(())
 }
 
+/// Formats rule, padding its name and name details to the formatter width,
+/// if any, or to 23 characters otherwise. The flags of the rule are padded
+/// accordingly, making their descriptions line up.
 impl<'a> fmt::Display for Rule<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let width = 22usize.saturating_sub(self.name.len());
+        let column = f.width().unwrap_or(23);
+        let width = (column - 1).saturating_sub(self.name.len());
         write!(
             f, concat!("- ", color!(g: "{} {:<width$}"), " {}"),
             self.name, self.name_details, self.description,
@@ -71,7 +75,7 @@ impl<'a> fmt::Display for Rule<'a> {
         )?;
         if self.flags.len() > 0 {
             for flag in self.flags {
-                write!(f, "\n  {}", flag)?;
+                write!(f, "\n  {:width$}", flag, width = column - 4)?;
             }
         }
         Ok(())
@@ -0,0 +1,278 @@
+//! Minimal HTTP/1.1 support, sufficient for serving and consuming mocked
+//! services.
+
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// The largest request or response body accepted, in bytes.
+pub const BODY_LENGTH_MAX: usize = 16 * 1024 * 1024;
+
+/// An HTTP request.
+#[derive(Debug)]
+pub struct Request {
+    /// Request method, such as `GET`.
+    pub method: String,
+
+    /// Request path, excluding any query string.
+    pub path: String,
+
+    /// Request body.
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads HTTP request from `stream`.
+    pub fn read_from<R: Read>(stream: R) -> io::Result<Request> {
+        let mut reader = BufReader::new(stream);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+                (method.to_string(), target.to_string())
+            }
+            _ => return Err(invalid_data("malformed HTTP request line")),
+        };
+        let path = target.split('?').next().unwrap_or("").to_string();
+
+        let headers = read_headers(&mut reader)?;
+        let body = read_body(&mut reader, &headers)?;
+
+        Ok(Request { method, path, body })
+    }
+
+    /// Writes HTTP request to `stream`, addressed to `host`.
+    ///
+    /// The body, if any, is assumed to be JSON.
+    pub fn write_to<W: Write>(&self, host: &str, mut stream: W) -> io::Result<()> {
+        write!(stream, "{} {} HTTP/1.1\r\nHost: {}\r\n", self.method, self.path, host)?;
+        if !self.body.is_empty() {
+            write!(stream, "Content-Type: application/json\r\n")?;
+        }
+        write!(stream, "Accept: application/json\r\n")?;
+        write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+/// An HTTP response.
+#[derive(Debug)]
+pub struct Response {
+    /// Status code, such as `200`.
+    pub status: u16,
+
+    /// Response headers.
+    pub headers: Vec<(String, String)>,
+
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Creates new response with given `status` and JSON `body`.
+    pub fn json(status: u16, body: String) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.into_bytes(),
+        }
+    }
+
+    /// Reads HTTP response from `stream`.
+    ///
+    /// Bodies may be delimited by a `Content-Length` header, by chunked
+    /// transfer encoding or by the stream being closed.
+    pub fn read_from<R: Read>(stream: R) -> io::Result<Response> {
+        let mut reader = BufReader::new(stream);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let status = match (parts.next(), parts.next()) {
+            (Some(version), Some(status)) if version.starts_with("HTTP/1.") => status.parse()
+                .map_err(|_| invalid_data("malformed HTTP status code"))?,
+            _ => return Err(invalid_data("malformed HTTP status line")),
+        };
+
+        let headers = read_headers(&mut reader)?;
+        let is_chunked = headers.iter()
+            .any(|(name, value)| name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked"));
+        let body = if is_chunked {
+            read_chunked_body(&mut reader)?
+        } else if headers.iter().any(|(name, _)| name == "content-length") {
+            read_body(&mut reader, &headers)?
+        } else if status == 204 || status == 304 {
+            Vec::new()
+        } else {
+            let mut body = Vec::new();
+            reader.by_ref().take(BODY_LENGTH_MAX as u64 + 1).read_to_end(&mut body)?;
+            if body.len() > BODY_LENGTH_MAX {
+                return Err(body_too_large());
+            }
+            body
+        };
+
+        Ok(Response { status, headers, body })
+    }
+
+    /// Looks up value of first header with given lowercase `name`, if any.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Writes HTTP response to `stream`.
+    pub fn write_to<W: Write>(&self, mut stream: W) -> io::Result<()> {
+        write!(stream, "HTTP/1.1 {} {}\r\n", self.status, reason_of(self.status))?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn body_too_large() -> io::Error {
+    invalid_data("HTTP body exceeds maximum length")
+}
+
+fn read_headers<R: BufRead>(reader: &mut R) -> io::Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        let (name, value) = line.split_once(':')
+            .ok_or_else(|| invalid_data("malformed HTTP header"))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+}
+
+/// Reads body of the length given by the `content-length` header in
+/// `headers`, if any.
+///
+/// Lengths larger than [`BODY_LENGTH_MAX`] are rejected before any memory is
+/// allocated for the body.
+fn read_body<R: BufRead>(reader: &mut R, headers: &[(String, String)]) -> io::Result<Vec<u8>> {
+    let length = headers.iter()
+        .find(|(name, _)| name == "content-length")
+        .map_or(Ok(0), |(_, value)| value.parse::<usize>())
+        .map_err(|_| invalid_data("malformed HTTP Content-Length header"))?;
+    if length > BODY_LENGTH_MAX {
+        return Err(body_too_large());
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+/// Reads body encoded using chunked transfer encoding, ignoring any chunk
+/// extensions and trailers.
+fn read_chunked_body<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let size = line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| invalid_data("malformed HTTP chunk size"))?;
+        if size == 0 {
+            read_headers(reader)?;
+            return Ok(body);
+        }
+        let offset = body.len();
+        if size > BODY_LENGTH_MAX - offset {
+            return Err(body_too_large());
+        }
+        body.resize(offset + size, 0);
+        reader.read_exact(&mut body[offset..])?;
+        let mut crlf = [0; 2];
+        reader.read_exact(&mut crlf)?;
+    }
+}
+
+/// Standard reason phrase of HTTP status code `status`.
+fn reason_of(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_request() {
+        let request = Request::read_from(concat!(
+            "POST /registry/find?limit=2 HTTP/1.1\r\n",
+            "Host: localhost\r\n",
+            "content-LENGTH: 12\r\n",
+            "\r\n",
+            "{\"Name\":\"A\"}",
+        ).as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/registry/find");
+        assert_eq!(request.body, b"{\"Name\":\"A\"}");
+
+        let request = Request::read_from(&b"GET / HTTP/1.0\r\n\r\n"[..]).unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("GET", "/"));
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn read_invalid_request() {
+        let is_invalid = |request: &str| Request::read_from(request.as_bytes())
+            .is_err_and(|error| error.kind() == io::ErrorKind::InvalidData);
+
+        assert!(is_invalid("GET /\r\n\r\n"));
+        assert!(is_invalid("GET / SPDY/3\r\n\r\n"));
+        assert!(is_invalid("GET / HTTP/1.1\r\nHost\r\n\r\n"));
+        assert!(is_invalid("POST / HTTP/1.1\r\nContent-Length: -1\r\n\r\n"));
+        assert!(is_invalid(&format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", BODY_LENGTH_MAX + 1)));
+        assert!(is_invalid("POST / HTTP/1.1\r\nContent-Length: 18446744073709551615\r\n\r\n"));
+        assert!(Request::read_from(&b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\n{}"[..]).is_err());
+    }
+
+    #[test]
+    fn read_chunked_response() {
+        let response = Response::read_from(concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Transfer-Encoding: chunked\r\n",
+            "\r\n",
+            "3;ext=1\r\n{\"A\r\n",
+            "4\r\n\":1}\r\n",
+            "0\r\n",
+            "\r\n",
+        ).as_bytes()).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("Transfer-Encoding"), Some("chunked"));
+        assert_eq!(response.body, b"{\"A\":1}");
+
+        let response = Response::read_from(format!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n",
+            BODY_LENGTH_MAX + 1,
+        ).as_bytes());
+        assert!(response.is_err_and(|error| error.kind() == io::ErrorKind::InvalidData));
+    }
+}
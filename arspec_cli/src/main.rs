@@ -1,5 +1,6 @@
 mod app;
 mod cliargs;
+mod http;
 mod log;
 
 use arspec::Error;
@@ -8,16 +9,56 @@ use std::env;
 use std::process;
 
 fn main() {
+    let conform_b = cliargs::FlagCell::new();
     let doc_s = cliargs::FlagCell::new();
+    let doc_since = cliargs::FlagCell::new();
+    let example_e = cliargs::FlagCell::new();
+    let gen_p = cliargs::FlagCell::new();
     let help = cliargs::FlagCell::new();
+    let mock_p = cliargs::FlagCell::new();
     let new_i = cliargs::FlagCell::new();
     let new_n = cliargs::FlagCell::new();
+    let validate_e = cliargs::FlagCell::new();
 
     let cli = cliargs::Parser {
         description: concat!(
             color!(g: "Available AHFS commands:")
         ),
         rules: &[
+            cliargs::Rule {
+                name: "conform",
+                name_details: "<service>",
+                description: concat!(
+                    "Test HTTP/JSON implementation of ",
+                    color!(g: "<service>"),
+                    " for conformance.",
+                ),
+                flags: &[
+                    cliargs::Flag {
+                        short: Some("b"),
+                        long: "base-url",
+                        description: "Set service base URL (http://localhost:8080).",
+                        out: cliargs::FlagOut::new_string(&conform_b),
+                    },
+                ],
+                callback: &|args| {
+                    let base_url = conform_b.take();
+                    app::conform(args, base_url.as_deref().unwrap_or("http://localhost:8080"))
+                },
+            },
+            cliargs::Rule {
+                name: "diff",
+                name_details: "<old> <new>",
+                description: concat!(
+                    "Compare specifications of projects at ",
+                    color!(g: "<old>"),
+                    " and ",
+                    color!(g: "<new>"),
+                    ".",
+                ),
+                flags: &[],
+                callback: &|args| app::diff(args),
+            },
             cliargs::Rule {
                 name: "doc",
                 name_details: "",
@@ -29,8 +70,52 @@ fn main() {
                         description: "Skip some source file verifications.",
                         out: cliargs::FlagOut::new_bool(&doc_s),
                     },
+                    cliargs::Flag {
+                        short: None,
+                        long: "since",
+                        description: "Document changes since project at path.",
+                        out: cliargs::FlagOut::new_string(&doc_since),
+                    },
+                ],
+                callback: &|args| {
+                    app::doc(args, doc_s.take_or(false), doc_since.take())
+                },
+            },
+            cliargs::Rule {
+                name: "example",
+                name_details: "<record>",
+                description: concat!(
+                    "Print example payload of ",
+                    color!(g: "<record>"),
+                    ".",
+                ),
+                flags: &[
+                    cliargs::Flag {
+                        short: Some("e"),
+                        long: "encoding",
+                        description: "Set encoding, CBOR, JSON or XML (JSON).",
+                        out: cliargs::FlagOut::new_string(&example_e),
+                    },
+                ],
+                callback: &|args| app::example(args, example_e.take()),
+            },
+            cliargs::Rule {
+                name: "gen",
+                name_details: "<generator>",
+                description: concat!(
+                    "Generate files using ",
+                    color!(g: "<generator>"),
+                    ", which may be `asyncapi`, `cddl`, `java`, `openapi`, `rust-client`, `rust-server`, `typescript` or `xsd`.",
+                ),
+                flags: &[
+                    cliargs::Flag {
+                        short: Some("p"),
+                        long: "package",
+                        description: "Set package of generated Java sources.",
+                        out: cliargs::FlagOut::new_string(&gen_p),
+                    },
                 ],
-                callback: &|args| app::doc(args, doc_s.take_or(false)),
+                callback: &|args| app::gen(args, gen_p.take()),
             },
             cliargs::Rule {
                 name: "help",
@@ -42,6 +127,19 @@ fn main() {
                     Ok(())
                 },
             },
+            cliargs::Rule {
+                name: "import",
+                name_details: "<format> <file>",
+                description: concat!(
+                    "Import ",
+                    color!(g: "<file>"),
+                    " of ",
+                    color!(g: "<format>"),
+                    ", which may be `jsonschema` or `openapi`, into project.",
+                ),
+                flags: &[],
+                callback: &|args| app::import(args),
+            },
             cliargs::Rule {
                 name: "list",
                 name_details: "",
@@ -49,6 +147,24 @@ fn main() {
                 flags: &[],
                 callback: &|args| app::list(args),
             },
+            cliargs::Rule {
+                name: "mock",
+                name_details: "<service>",
+                description: concat!(
+                    "Serve mock HTTP/JSON implementation of ",
+                    color!(g: "<service>"),
+                    ".",
+                ),
+                flags: &[
+                    cliargs::Flag {
+                        short: Some("p"),
+                        long: "port",
+                        description: "Set local port to listen on (8080).",
+                        out: cliargs::FlagOut::new_u16(&mock_p),
+                    },
+                ],
+                callback: &|args| app::mock(args, mock_p.take_or(8080)),
+            },
             cliargs::Rule {
                 name: "new",
                 name_details: "<path>",
@@ -82,6 +198,26 @@ fn main() {
                 callback: &|args| app::status(args),
                 flags: &[],
             },
+            cliargs::Rule {
+                name: "validate",
+                name_details: "<record> <file>",
+                description: concat!(
+                    "Check payload in ",
+                    color!(g: "<file>"),
+                    " against ",
+                    color!(g: "<record>"),
+                    ".",
+                ),
+                flags: &[
+                    cliargs::Flag {
+                        short: Some("e"),
+                        long: "encoding",
+                        description: "Set encoding, CBOR, JSON or XML (by extension).",
+                        out: cliargs::FlagOut::new_string(&validate_e),
+                    },
+                ],
+                callback: &|args| app::validate(args, validate_e.take()),
+            },
         ],
     };
     let args = env::args().collect::<Vec<String>>();
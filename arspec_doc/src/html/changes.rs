@@ -0,0 +1,192 @@
+use arspec::spec::{Change, ChangeKind, Element, Impact};
+use std::{fmt, io};
+use super::{escape, file_stem_of, Context, Encode};
+
+/// Changes made since some earlier version of a specification.
+pub struct Changes<'b> {
+    /// Version of earlier specification.
+    pub version: &'b str,
+
+    /// Changes made since the earlier specification.
+    pub changes: &'b [Change],
+
+    title: String,
+}
+
+impl<'b> Changes<'b> {
+    /// Creates new change log, listing `changes` made since `version`.
+    pub fn new(version: &'b str, changes: &'b [Change]) -> Self {
+        Changes {
+            version,
+            changes,
+            title: format!("Changes since {}", version),
+        }
+    }
+}
+
+impl<'b> Encode for Changes<'b> {
+    fn encode<W>(&self, _context: &Context, w: &mut W) -> io::Result<()>
+        where W: io::Write
+    {
+        writeln!(w, "<h1>{}</h1>", escape(&self.title))?;
+        if self.changes.is_empty() {
+            return writeln!(w, "<p>No changes.</p>");
+        }
+        for title in &["Added", "Removed", "Modified"] {
+            let changes = self.changes.iter()
+                .filter(|change| group_of(&change.kind) == *title)
+                .collect::<Vec<_>>();
+            if changes.is_empty() {
+                continue;
+            }
+            write!(w, "<h2>{}</h2>\n<ul>\n", title)?;
+            for change in changes {
+                writeln!(w, "  <li>{}</li>", Line { change, link: link_of(change) })?;
+            }
+            writeln!(w, "</ul>")?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        &self.title
+    }
+}
+
+/// Title of change log section listing changes of given `kind`.
+fn group_of(kind: &ChangeKind) -> &'static str {
+    match *kind {
+        ChangeKind::Added => "Added",
+        ChangeKind::Removed => "Removed",
+        ChangeKind::Changed { .. } => "Modified",
+    }
+}
+
+/// Determines hyperlink leading to current documentation of the element
+/// affected by `change`, unless no such documentation exists.
+fn link_of(change: &Change) -> Option<String> {
+    let definition = change.element.definition();
+    if change.kind == ChangeKind::Removed && change.element == definition {
+        return None;
+    }
+    let name = file_stem_of(change.owner());
+    match definition {
+        Element::Enum => Some(format!("figures/enum-{}.svg", name)),
+        Element::Record => Some(format!("figures/record-{}.svg", name)),
+        Element::Service => Some(format!("{}-SD.html", name)),
+        Element::System => Some(format!("{}-SysD.html", name)),
+        _ => None,
+    }
+}
+
+/// A single change, rendered as HTML.
+pub(super) struct Line<'b> {
+    pub change: &'b Change,
+    pub link: Option<String>,
+}
+
+impl<'b> fmt::Display for Line<'b> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let change = self.change;
+        if change.impact == Impact::Breaking {
+            f.write_str("<strong class=\"breaking\">Breaking</strong> ")?;
+        }
+        f.write_str(match change.kind {
+            ChangeKind::Added => "Added",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Changed { .. } => "Changed",
+        })?;
+        write!(f, " {} ", change.element)?;
+        let path = escape(&change.path.join("."));
+        match self.link {
+            Some(ref link) => write!(f, "<a href=\"{}\"><code>{}</code></a>", link, path)?,
+            None => write!(f, "<code>{}</code>", path)?,
+        }
+        if let ChangeKind::Changed { ref from, ref to } = change.kind {
+            f.write_str(" from ")?;
+            write_value(f, from)?;
+            f.write_str(" to ")?;
+            write_value(f, to)?;
+        }
+        return f.write_str(".");
+
+        fn write_value(f: &mut fmt::Formatter, value: &Option<String>) -> fmt::Result {
+            match *value {
+                Some(ref value) => write!(f, "<code>{}</code>", escape(value)),
+                None => f.write_str("nothing"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::{parser, Diff, Specification};
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    fn corpus(body: &str) -> Corpus {
+        Corpus { texts: vec![Text { name: "test.ahfs".into(), body: body.into() }] }
+    }
+
+    fn encode<E: Encode>(element: &E, spec: &Specification, changes: Option<&Changes>) -> String {
+        let context = Context { spec, externals: &[], theme: "light", changes };
+        let mut buffer = Vec::new();
+        element.encode(&context, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn encode_change_log_and_sections() {
+        let old = corpus(concat!(
+            "service S { method Get(Query): Answer; }\n",
+            "record Query { Name: String }\n",
+            "record Answer { Value: Map<String, U16> }\n",
+            "record Gone { Name: String }\n",
+        ));
+        let new = corpus(concat!(
+            "service S { method Get(Query): Answer; method Put(Answer); }\n",
+            "record Query { Name: String }\n",
+            "record Answer { Value: Map<String, U32> }\n",
+            "record Added { Name: String }\n",
+        ));
+        let (old, new) = (parser::parse(&old).unwrap(), parser::parse(&new).unwrap());
+        let diff = Diff::new(&old, &new);
+        let changes = Changes::new("1.0.0", &diff.changes);
+
+        assert_eq!(encode(&changes, &new, Some(&changes)), concat!(
+            "<h1>Changes since 1.0.0</h1>\n",
+            "<h2>Added</h2>\n",
+            "<ul>\n",
+            "  <li>Added record <a href=\"figures/record-Added.svg\"><code>Added</code></a>.</li>\n",
+            "  <li>Added method <a href=\"S-SD.html\"><code>S.Put</code></a>.</li>\n",
+            "</ul>\n",
+            "<h2>Removed</h2>\n",
+            "<ul>\n",
+            "  <li><strong class=\"breaking\">Breaking</strong> Removed record <code>Gone</code>.</li>\n",
+            "</ul>\n",
+            "<h2>Modified</h2>\n",
+            "<ul>\n",
+            "  <li><strong class=\"breaking\">Breaking</strong> Changed field ",
+            "<a href=\"figures/record-Answer.svg\"><code>Answer.Value</code></a> ",
+            "from <code>Map&lt;String, U16&gt;</code> to <code>Map&lt;String, U32&gt;</code>.</li>\n",
+            "</ul>\n",
+        ));
+
+        let service = &new.services[0];
+        assert!(encode(&service, &new, Some(&changes)).ends_with(concat!(
+            "<section class=\"changes\">\n",
+            "<h2><a href=\"Changes.html\">Changes since 1.0.0</a></h2>\n",
+            "<ul>\n",
+            "  <li>Added method <code>S.Put</code>.</li>\n",
+            "</ul>\n",
+            "</section>\n",
+        )));
+        assert!(!encode(&service, &new, None).contains("<section class=\"changes\">"));
+
+        let unchanged = Changes::new("1.0.0", &[]);
+        assert_eq!(encode(&unchanged, &new, Some(&unchanged)), "<h1>Changes since 1.0.0</h1>\n<p>No changes.</p>\n");
+        assert!(!encode(&service, &new, Some(&unchanged)).contains("<section class=\"changes\">"));
+    }
+}
@@ -1,10 +1,16 @@
+pub mod changes;
+pub mod service;
 pub mod system;
 
+pub use self::changes::Changes;
+
+use arspec::spec::{self, Change, Element, Specification};
+use arspec_parser::Text;
 use std::{fmt, io};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub trait Encode {
-    fn encode<W>(&self, w: &mut W) -> io::Result<()>
+    fn encode<W>(&self, context: &Context, w: &mut W) -> io::Result<()>
         where W: io::Write;
 
     fn name(&self) -> &str;
@@ -13,6 +19,7 @@ pub trait Encode {
 /// Creates HTML documentation file for given `element`.
 pub fn render<E, W>(
     element: &E,
+    context: &Context,
     scripts: &[&'_ Path],
     styles: &[Style<'_>],
     w: &mut W,
@@ -38,10 +45,10 @@ pub fn render<E, W>(
 
     write!(w, concat!(
         "</head>\n",
-        "<body>\n",
-    ))?;
+        "<body class=\"theme-{}\">\n",
+    ), escape(context.theme))?;
 
-    element.encode(w)?;
+    element.encode(context, w)?;
 
     write!(w, concat!(
         "</body>\n",
@@ -49,6 +56,101 @@ pub fn render<E, W>(
     ))
 }
 
+/// Information required to render HTML documentation files.
+pub struct Context<'a: 'b, 'b> {
+    /// Specification containing all documented elements.
+    pub spec: &'b Specification<'a>,
+
+    /// Root folders of external projects, paired with hyperlink paths leading
+    /// to their documentation folders.
+    ///
+    /// Elements defined in source files inside any of the root folders are
+    /// documented by their respective projects, and are linked to rather
+    /// than documented again.
+    pub externals: &'b [(PathBuf, String)],
+
+    /// Name of documentation theme, such as `"light"` or `"dark"`.
+    pub theme: &'b str,
+
+    /// Changes made since some earlier version of the specification, if
+    /// they are to be documented.
+    pub changes: Option<&'b Changes<'b>>,
+}
+
+impl<'a: 'b, 'b> Context<'a, 'b> {
+    /// Hyperlink prefix leading to the documentation folder of the project
+    /// defining elements in source `text`.
+    ///
+    /// The prefix is empty for elements of the documented project.
+    pub fn prefix_of(&self, text: &Text) -> &'b str {
+        let path = spec::normalize(Path::new(&*text.name));
+        self.externals.iter()
+            .find(|(root, _)| path.starts_with(spec::normalize(root)))
+            .map_or("", |(_, prefix)| prefix.as_str())
+    }
+
+    /// Whether or not elements in source `text` are documented by some
+    /// external project.
+    #[inline]
+    pub fn is_external(&self, text: &Text) -> bool {
+        !self.prefix_of(text).is_empty()
+    }
+
+    /// Any changes made to the elements of top-level `definition` with
+    /// qualified `name`.
+    pub fn changes_of(&self, definition: Element, name: &str) -> Vec<&'b Change> {
+        self.changes.map_or_else(Vec::new, |changes| changes.changes.iter()
+            .filter(|change| change.element.definition() == definition && change.owner() == name)
+            .collect())
+    }
+}
+
+/// Writes section listing changes made to the documented element, unless
+/// there are no such changes.
+fn encode_changes<W>(context: &Context, changes: &[&Change], w: &mut W) -> io::Result<()>
+    where W: io::Write
+{
+    let version = match context.changes {
+        Some(changes) => changes.version,
+        None => return Ok(()),
+    };
+    if changes.is_empty() {
+        return Ok(());
+    }
+    write!(
+        w,
+        "<section class=\"changes\">\n<h2><a href=\"Changes.html\">Changes since {}</a></h2>\n<ul>\n",
+        escape(version),
+    )?;
+    for change in changes {
+        writeln!(w, "  <li>{}</li>", changes::Line { change, link: None })?;
+    }
+    writeln!(w, "</ul>\n</section>")
+}
+
+/// Determines the file name stem of the documentation of the element with
+/// package-qualified `name`, such as `Team.A.Response` for
+/// `Team.A::Response`, preventing elements with the same name in different
+/// packages from being documented in the same files.
+pub fn file_stem_of(name: &str) -> String {
+    name.replace("::", ".")
+}
+
+/// Escapes any characters in `string` with special meaning in HTML.
+pub fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for ch in string.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 pub struct Style<'a> {
     pub path: &'a Path,
     pub media: StyleMedia,
@@ -75,3 +177,23 @@ pub enum StyleMedia {
     PRINT,
     SCREEN,
 }
+
+#[cfg(test)]
+mod tests {
+    use arspec_parser::Corpus;
+    use super::*;
+
+    #[test]
+    fn prefix_of_normalized_paths() {
+        let corpus = Corpus::default();
+        let spec = arspec::spec::parser::parse(&corpus).unwrap();
+        let externals = [(PathBuf::from("./project/../core"), "../../core/target/".to_string())];
+        let context = Context { spec: &spec, externals: &externals, theme: "light", changes: None };
+
+        let text = |name: &str| Text { name: name.into(), body: "".into() };
+        assert_eq!(context.prefix_of(&text("core/Orchestrator.ahfs")), "../../core/target/");
+        assert_eq!(context.prefix_of(&text("project/../core/./Orchestrator.ahfs")), "../../core/target/");
+        assert_eq!(context.prefix_of(&text("project/Service.ahfs")), "");
+        assert!(!context.is_external(&text("project/Service.ahfs")));
+    }
+}
@@ -0,0 +1,77 @@
+use arspec::spec::{Element, Service};
+use crate::svg;
+use std::io;
+use std::ptr;
+use super::{Context, Encode, encode_changes, escape};
+
+impl<'a: 'b, 'b> Encode for &'b Service<'a> {
+    fn encode<W>(&self, context: &Context, w: &mut W) -> io::Result<()>
+        where W: io::Write
+    {
+        svg::render(self, w)?;
+        let changes = context.changes_of(Element::Service, &context.spec.qualify(&self.name));
+        encode_changes(context, &changes, w)?;
+        encode_implementations(context, self, w)
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+fn encode_implementations<W>(context: &Context, service: &Service, w: &mut W) -> io::Result<()>
+    where W: io::Write
+{
+    let implementations = context.spec.implementations.iter()
+        .filter(|implementation| context.spec.resolve_service(&implementation.name)
+            .is_some_and(|implemented| ptr::eq(implemented, service)));
+
+    for implementation in implementations {
+        writeln!(
+            w,
+            "<h2>Implementation using {}/{}</h2>",
+            implementation.protocol.as_str(),
+            implementation.encoding.as_str(),
+        )?;
+        if !implementation.properties.is_empty() {
+            writeln!(w, "<dl class=\"properties\">")?;
+            for property in &implementation.properties {
+                writeln!(
+                    w,
+                    "  <dt>{}</dt><dd><code>{}</code></dd>",
+                    property.name.as_str(),
+                    escape(&property.value.to_string()),
+                )?;
+            }
+            writeln!(w, "</dl>")?;
+        }
+        writeln!(w, "<dl class=\"methods\">")?;
+        for method in &implementation.methods {
+            let data = method.data.iter()
+                .filter(|(name, _)| name.as_str() != "Errors")
+                .map(|(name, value)| format!("{}: {}", name.as_str(), value))
+                .collect::<Vec<_>>();
+            writeln!(
+                w,
+                "  <dt>{}</dt><dd><code>{}</code></dd>",
+                method.name.as_str(),
+                escape(&data.join(", ")),
+            )?;
+            let service_method = service.methods.iter()
+                .find(|service_method| service_method.name.as_str() == method.name.as_str());
+            for error in service_method.iter().flat_map(|service_method| &service_method.errors) {
+                if let Some(code) = method.error_code(error.name.as_str()) {
+                    writeln!(
+                        w,
+                        "  <dd class=\"error\"><code>{}</code> as <code>{}</code></dd>",
+                        escape(&error.to_string()),
+                        escape(&code.to_string()),
+                    )?;
+                }
+            }
+        }
+        writeln!(w, "</dl>")?;
+    }
+    Ok(())
+}
@@ -1,17 +1,55 @@
-use arspec::spec::System;
+use arspec::spec::{Element, ServiceRef, System};
 use crate::svg;
 use std::io;
-use super::Encode;
+use super::{Context, Encode, encode_changes, file_stem_of};
 
 impl<'a: 'b, 'b> Encode for &'b System<'a> {
-    fn encode<W>(&self, w: &mut W) -> io::Result<()>
+    fn encode<W>(&self, context: &Context, w: &mut W) -> io::Result<()>
         where W: io::Write
     {
-        svg::render(self, w)
+        svg::render(self, w)?;
+        let changes = context.changes_of(Element::System, &context.spec.qualify(&self.name));
+        encode_changes(context, &changes, w)?;
+        encode_service_refs(context, "Consumed Services", &self.consumes, w)?;
+        encode_service_refs(context, "Produced Services", &self.produces, w)
     }
 
     #[inline]
     fn name(&self) -> &str {
         self.name.as_str()
     }
-}
\ No newline at end of file
+}
+
+fn encode_service_refs<W>(
+    context: &Context,
+    title: &str,
+    service_refs: &[ServiceRef],
+    w: &mut W,
+) -> io::Result<()>
+    where W: io::Write
+{
+    if service_refs.is_empty() {
+        return Ok(());
+    }
+    write!(w, "<h2>{}</h2>\n<ul>\n", title)?;
+    for service_ref in service_refs {
+        let name = service_ref.name.as_str();
+        match context.spec.resolve_service(&service_ref.name) {
+            Some(service) => {
+                let is_changed = !context
+                    .changes_of(Element::Service, &context.spec.qualify(&service.name))
+                    .is_empty();
+                writeln!(
+                    w,
+                    "  <li{}><a href=\"{}{}-SD.html\" class=\"service-ref\">{}</a></li>",
+                    if is_changed { " class=\"changed\"" } else { "" },
+                    context.prefix_of(service.name.source),
+                    file_stem_of(&context.spec.qualify(&service.name)),
+                    name,
+                )?
+            }
+            None => writeln!(w, "  <li class=\"service-ref\">{}</li>", name)?,
+        }
+    }
+    writeln!(w, "</ul>")
+}
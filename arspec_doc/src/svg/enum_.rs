@@ -1,5 +1,6 @@
-use arspec::spec::Enum;
+use arspec::spec::{doc_of, Enum, EnumVariant};
 use crate::fonts;
+use crate::html::escape;
 use std::io;
 use super::{color, Encode, Vector};
 
@@ -25,7 +26,7 @@ impl<'a: 'b, 'b> Encode for &'b Enum<'a> {
                 " font-weight=\"bold\" class=\"enum-name\">{name}</text>",
                 "</g>",
                 "",
-                "<g fill=\"{color_name}\" font-size=\"16\" font-style=\"italic\">",
+                "<g font-size=\"16\">",
             ),
             color_meta = color::META,
             color_name = color::GAMMA,
@@ -45,16 +46,40 @@ impl<'a: 'b, 'b> Encode for &'b Enum<'a> {
             y_rect1 = offset.y + 3.0,
             y_rect2 = offset.y + 53.0,
         )?;
-        let variant_height = fonts::SANS.line_height() * 16.0;
+        let variant_height = fonts::SANS_ITALIC.line_height() * 16.0;
+        let doc_height = fonts::SANS.line_height() * DOC_FONT_SIZE;
         for variant in &self.variants {
             write!(
                 w,
-                "<text x=\"{}\" y=\"{}\" class=\"enum-variant\">{}</text>",
+                concat!(
+                    "<text x=\"{}\" y=\"{}\">",
+                    "<tspan fill=\"{}\" font-style=\"italic\" class=\"enum-variant\">{}</tspan>",
+                    "<tspan fill=\"{}\" class=\"enum-variant-value\">{}</tspan>",
+                    "</text>",
+                ),
                 offset.x + 10.0,
                 offset_y as usize,
+                color::GAMMA,
                 variant.name.as_str(),
+                color::TEXT,
+                escape(&value_of(variant)),
             )?;
             offset_y += variant_height;
+            for line in doc_lines_of(variant) {
+                write!(
+                    w,
+                    concat!(
+                        "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-size=\"{}\"",
+                        " class=\"enum-variant-doc\">{}</text>",
+                    ),
+                    offset.x + 20.0,
+                    (offset_y - 2.0) as usize,
+                    color::META,
+                    DOC_FONT_SIZE,
+                    escape(&line),
+                )?;
+                offset_y += doc_height;
+            }
         }
         write!(w, "</g>")
     }
@@ -64,7 +89,15 @@ impl<'a: 'b, 'b> Encode for &'b Enum<'a> {
             x: {
                 let variant_width_max = self.variants.iter()
                     .map(|variant| {
-                        fonts::SANS_ITALIC.line_width_of(variant.name.as_str()) * 16.0 * 1000.0
+                        let name_width = fonts::SANS_ITALIC
+                            .line_width_of(variant.name.as_str()) * 16.0;
+                        let value_width = fonts::SANS
+                            .line_width_of(&value_of(variant)) * 16.0;
+                        let doc_width = doc_lines_of(variant).iter()
+                            .map(|line| fonts::SANS.line_width_of(line) * DOC_FONT_SIZE + 10.0)
+                            .fold(0.0, f32::max);
+
+                        (name_width + value_width).max(doc_width) * 1000.0
                     } as usize)
                     .max()
                     .unwrap_or(0) as f32 / 1000.0;
@@ -74,8 +107,54 @@ impl<'a: 'b, 'b> Encode for &'b Enum<'a> {
 
                 (variant_width_max.max(name_width) + 20.0).round()
             },
-            y: (self.variants.len() as f32 * fonts::SANS_ITALIC
-                .line_height() * 16.0 + 71.0).round(),
+            y: {
+                let doc_line_count = self.variants.iter()
+                    .map(|variant| doc_lines_of(variant).len())
+                    .sum::<usize>();
+
+                (self.variants.len() as f32 * fonts::SANS_ITALIC.line_height() * 16.0
+                    + doc_line_count as f32 * fonts::SANS.line_height() * DOC_FONT_SIZE
+                    + 71.0).round()
+            },
+        }
+    }
+}
+
+/// Font size of variant documentation.
+const DOC_FONT_SIZE: f32 = 13.0;
+
+/// Maximum number of characters in any line of variant documentation.
+const DOC_LINE_LENGTH_MAX: usize = 48;
+
+/// Formats text following the name of `variant`, which is its value, if any.
+fn value_of(variant: &EnumVariant) -> String {
+    match variant.value {
+        Some(ref value) => format!(" = {}", value),
+        None => String::new(),
+    }
+}
+
+/// Splits the `@Doc` of `variant`, if any, into lines short enough to not
+/// make the figure unreasonably wide.
+fn doc_lines_of(variant: &EnumVariant) -> Vec<String> {
+    let doc = match doc_of(&variant.attributes) {
+        Some(doc) => doc,
+        None => return Vec::new(),
+    };
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in doc.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > DOC_LINE_LENGTH_MAX {
+            lines.push(line);
+            line = String::new();
+        }
+        if !line.is_empty() {
+            line.push(' ');
         }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
     }
+    lines
 }
@@ -60,3 +60,59 @@ mod color {
     pub const META: &'static str = "#666666";
     pub const TEXT: &'static str = "#333333";
 }
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn render_optional_entries() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "record ServiceRequestForm {\n",
+                    "    RequesterCloud?: String,\n",
+                    "    OrchestrationFlags: Map<String, Boolean> = {},\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+
+        let mut record = Vec::new();
+        render(&&spec.records[0], &mut record).unwrap();
+        let record = String::from_utf8(record).unwrap();
+        assert!(record.contains("RequesterCloud</tspan><tspan>?: </tspan>"));
+        assert!(record.contains("OrchestrationFlags</tspan><tspan>: </tspan>"));
+        assert!(record.contains("<tspan class=\"record-field-default\"> = {}</tspan>"));
+    }
+
+    #[test]
+    fn render_variant_values_and_docs() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "a.ahfs".into(),
+                body: concat!(
+                    "enum OrchestratorWarnings {\n",
+                    "    @Doc(\"Provider is located in another Cloud.\")\n",
+                    "    FROM_OTHER_CLOUD = \"from-other-cloud\",\n",
+                    "    TTL_EXPIRED = \"ttl-expired\",\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+
+        let mut enum_ = Vec::new();
+        render(&&spec.enums[0], &mut enum_).unwrap();
+        let enum_ = String::from_utf8(enum_).unwrap();
+        assert!(enum_.contains("class=\"enum-variant-value\"> = &quot;from-other-cloud&quot;</tspan>"));
+        assert!(enum_.contains("class=\"enum-variant-doc\">Provider is located in another Cloud.</text>"));
+        assert_eq!(enum_.matches("class=\"enum-variant-doc\"").count(), 1);
+    }
+}
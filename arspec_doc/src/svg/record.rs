@@ -1,5 +1,6 @@
-use arspec::spec::Record;
+use arspec::spec::{Record, RecordEntry};
 use crate::fonts;
+use crate::html::escape;
 use std::io;
 use super::{color, Encode, Vector};
 
@@ -20,7 +21,7 @@ impl<'a: 'b, 'b> Encode for &'b Record<'a> {
                 "",
                 "<g text-anchor=\"middle\">",
                 "<text x=\"{x_middle}\" y=\"{y_meta}\" fill=\"{color_meta}\"",
-                " font-size=\"15\">«record»</text>",
+                " font-size=\"15\">{meta}</text>",
                 "<text x=\"{x_middle}\" y=\"{y_name}\" fill=\"{color_name}\" font-size=\"18\"",
                 " font-weight=\"bold\" class=\"record-name\">{name}</text>",
                 "</g>",
@@ -33,7 +34,8 @@ impl<'a: 'b, 'b> Encode for &'b Record<'a> {
             color_text = color::TEXT,
             height0 = size.y,
             height1 = size.y - 6.0,
-            name = self.name.as_str(),
+            meta = meta_of(self).replace('<', "&lt;").replace('>', "&gt;"),
+            name = name_of(self).replace('<', "&lt;").replace('>', "&gt;"),
             width0 = size.x,
             width1 = size.x - 6.0,
             x_middle = offset.x + size.x / 2.0,
@@ -52,16 +54,19 @@ impl<'a: 'b, 'b> Encode for &'b Record<'a> {
                 w,
                 concat!(
                     "<text x=\"{}\" y=\"{}\">",
-                    "<tspan class=\"record-field-name\">{}</tspan>",
-                    "<tspan>: </tspan>",
+                    "<tspan fill=\"{}\" class=\"record-field-name\">{}</tspan>",
+                    "<tspan>{}</tspan>",
                     "<tspan fill=\"{}\" font-weight=\"bold\" class=\"type-ref\">{}</tspan>",
+                    "<tspan class=\"record-field-default\">{}</tspan>",
                     "</text>",
                 ),
                 offset.x + 10.0,
                 offset_y as usize,
+                if entry.inherited_from.is_some() { color::META } else { color::TEXT },
                 entry.name.as_str(),
+                separator_of(entry),
                 color::GAMMA,
-                entry.type_ref.as_str()
+                entry.type_ref.to_string()
                     .chars()
                     .fold(String::new(), |mut acc, ch| {
                         match ch {
@@ -71,6 +76,7 @@ impl<'a: 'b, 'b> Encode for &'b Record<'a> {
                         }
                         acc
                     }),
+                escape(&default_of(entry)),
             )?;
             offset_y += entry_height;
         }
@@ -80,27 +86,68 @@ impl<'a: 'b, 'b> Encode for &'b Record<'a> {
     fn measure(&self) -> Vector {
         Vector {
             x: {
-                let colon_space_width = fonts::SANS.line_width_of(": ");
                 let entry_width_max = self.entries.iter()
                     .map(|entry| {
                         let name_width = fonts::SANS
                             .line_width_of(entry.name.as_str());
+                        let separator_width = fonts::SANS
+                            .line_width_of(separator_of(entry));
                         let type_ref_width = fonts::SANS_BOLD
-                            .line_width_of(entry.type_ref.as_str());
+                            .line_width_of(&entry.type_ref.to_string());
+                        let default_width = fonts::SANS
+                            .line_width_of(&default_of(entry));
 
-                        (name_width + colon_space_width + type_ref_width)
+                        (name_width + separator_width + type_ref_width + default_width)
                             * 16.0 * 1000.0
                     } as usize)
                     .max()
                     .unwrap_or(0) as f32 / 1000.0;
 
                 let name_width = fonts::SANS_BOLD
-                    .line_width_of(self.name.as_str()) * 18.0;
+                    .line_width_of(&name_of(self)) * 18.0;
 
-                (entry_width_max.max(name_width) + 20.0).round()
+                let meta_width = fonts::SANS
+                    .line_width_of(&meta_of(self)) * 15.0;
+
+                (entry_width_max.max(name_width).max(meta_width) + 20.0).round()
             },
             y: (self.entries.len() as f32 * fonts::SANS
                 .line_height() * 16.0 + 71.0).round(),
         }
     }
 }
+
+/// Formats name of `record`, followed by its generic parameters, if any.
+fn name_of(record: &Record) -> String {
+    if record.generic_parameters.is_empty() {
+        return record.name.as_str().into();
+    }
+    let parameters = record.generic_parameters.iter()
+        .map(|parameter| parameter.as_str())
+        .collect::<Vec<_>>();
+    format!("{}<{}>", record.name.as_str(), parameters.join(", "))
+}
+
+/// Gets text separating the name of `entry` from its type, which includes a
+/// question mark if the entry is optional.
+fn separator_of(entry: &RecordEntry) -> &'static str {
+    if entry.is_optional { "?: " } else { ": " }
+}
+
+/// Formats text following the type of `entry`, which is its default value,
+/// if any.
+fn default_of(entry: &RecordEntry) -> String {
+    match entry.default {
+        Some(ref default) => format!(" = {}", default),
+        None => String::new(),
+    }
+}
+
+/// Formats text describing the kind of `record`, and what record it extends,
+/// if any.
+fn meta_of(record: &Record) -> String {
+    match record.base {
+        Some(ref base) => format!("«record» extends {}", base),
+        None => "«record»".into(),
+    }
+}
@@ -50,6 +50,14 @@ impl<'a: 'b, 'b> Encode for &'b Service<'a> {
         for method in &self.methods {
             let input = method.input.as_ref().map(|input| input.as_str());
             let output = method.output.as_ref().map(|output| output.as_str());
+            let errors = method.errors.iter()
+                .map(|error| format!(
+                    "<tspan fill=\"{}\" font-weight=\"bold\" class=\"type-ref\">{}</tspan>",
+                    color::GAMMA,
+                    error.as_str(),
+                ))
+                .collect::<Vec<_>>()
+                .join(&format!("<tspan fill=\"{}\">, </tspan>", color::META));
             write!(
                 w,
                 concat!(
@@ -59,9 +67,12 @@ impl<'a: 'b, 'b> Encode for &'b Service<'a> {
                 "<tspan fill=\"{color_type_ref}\" font-weight=\"bold\" class=\"type-ref\">{input}</tspan>",
                 "<tspan fill=\"{color_meta}\">){colon}</tspan>",
                 "<tspan fill=\"{color_type_ref}\" font-weight=\"bold\" class=\"type-ref\">{output}</tspan>",
+                "<tspan fill=\"{color_meta}\">{throws}</tspan>{errors}",
                 "</text>",
                 ),
                 colon = if output.is_some() { ": " } else { "" },
+                errors = errors,
+                throws = if method.errors.is_empty() { "" } else { " throws " },
                 color_meta = color::META,
                 color_type_ref = color::GAMMA,
                 input = input.unwrap_or(""),
@@ -95,6 +106,13 @@ impl<'a: 'b, 'b> Encode for &'b Service<'a> {
                                 .line_width_of(": ") + fonts::SANS_BOLD
                                 .line_width_of(output.as_str()))
                             .unwrap_or(0.0);
+                        if !method.errors.is_empty() {
+                            width += fonts::SANS.line_width_of(" throws ");
+                            width += fonts::SANS.line_width_of(", ") * (method.errors.len() - 1) as f32;
+                            width += method.errors.iter()
+                                .map(|error| fonts::SANS_BOLD.line_width_of(error.as_str()))
+                                .sum::<f32>();
+                        }
 
                         ((width * 16.0 + 15.0) * 1000.0) as usize
                     })
@@ -0,0 +1,443 @@
+//! AsyncAPI 2.6 generation, for implementations using the MQTT protocol.
+//!
+//! Each implementation method is expected to have `Topic` data, naming the
+//! MQTT topic of its channel, and may have `QoS` and `Retain` data, which
+//! default to `0` and `false`. Method inputs become `publish` operations,
+//! while outputs become `subscribe` operations on the same channel, unless
+//! a `ResponseTopic` is given. Method errors become `subscribe` operations
+//! on the channels of the topics they are mapped to by `Errors` data, and
+//! operations shared by several messages accept any one of them. Payloads
+//! are described using JSON Schema, with one schema per distinct reference
+//! to each generic record. Record entries that may be absent are not
+//! required, and any default values are given as `default` keywords.
+//! Aliases become schemas of their own, restricting the schemas of the
+//! aliased types by the constraints of the aliases.
+
+use arspec::payload::{self, Data};
+use arspec::spec::{
+    Alias, Constraint, Implement, ImplementMethod, Record, RecordEntry, ServiceMethod, Specification, TypeRef, Value,
+};
+use crate::{Definition, doc_of, instance_name, instances, reachable};
+use std::mem;
+
+/// Generates AsyncAPI document, in JSON, describing MQTT `implementation`
+/// of given `version`.
+///
+/// Implementation methods without `Topic` data are ignored.
+pub fn generate<'a>(spec: &Specification<'a>, implementation: &Implement<'a>, version: &str) -> String {
+    let service = spec.resolve_service(&implementation.name);
+    let content_type = match implementation.encoding.as_str() {
+        "CBOR" => "application/cbor",
+        "XML" => "application/xml",
+        _ => "application/json",
+    };
+
+    let mut info = vec![
+        entry("title", string(&spec.qualify(&implementation.name))),
+        entry("version", string(version)),
+    ];
+    if let Some(doc) = service.and_then(|service| doc_of(&service.attributes)) {
+        info.push(entry("description", string(&doc)));
+    }
+
+    let mut channels: Vec<(String, Data)> = Vec::new();
+    let mut messages = Vec::new();
+    let mut roots = Vec::new();
+    for method in &implementation.methods {
+        let service_method = service.and_then(|service| service.methods.iter()
+            .find(|service_method| service_method.name == method.name));
+        let service_method = match service_method {
+            Some(service_method) => service_method,
+            None => continue,
+        };
+        let topic = match data_string(method, "Topic") {
+            Some(topic) => topic,
+            None => continue,
+        };
+        let mut operations = Vec::new();
+        if let Some(ref input) = service_method.input {
+            operations.push(("publish", format!("{}Input", method.name.as_str()), topic.clone(), input));
+        }
+        if let Some(ref output) = service_method.output {
+            let response_topic = data_string(method, "ResponseTopic").unwrap_or(topic);
+            operations.push(("subscribe", format!("{}Output", method.name.as_str()), response_topic, output));
+        }
+        for error in &service_method.errors {
+            if let Some(topic) = method.error_code(error.name.as_str()).and_then(Value::as_string) {
+                operations.push(("subscribe", format!("{}{}", method.name.as_str(), error.name.as_str()), topic, error));
+            }
+        }
+        for (operation, message_name, topic, type_ref) in operations {
+            let mut message = vec![
+                entry("name", string(&message_name)),
+                entry("contentType", string(content_type)),
+                entry("payload", schema_of(spec, type_ref)),
+            ];
+            if let Some(doc) = doc_of(&service_method.attributes) {
+                message.insert(1, entry("summary", string(&doc)));
+            }
+            messages.push((message_name.clone(), Data::Map(message)));
+            roots.push(type_ref);
+
+            let index = match channels.iter().position(|(name, _)| *name == topic) {
+                Some(index) => index,
+                None => {
+                    channels.push((topic.clone(), Data::Map(parameters_of(&topic))));
+                    channels.len() - 1
+                }
+            };
+            if let Data::Map(ref mut channel) = channels[index].1 {
+                match channel.iter_mut().find(|(key, _)| key == operation) {
+                    Some((_, existing)) => add_message(existing, &message_name),
+                    None => channel.push(entry(operation, operation_of(method, service_method, &message_name))),
+                }
+            }
+        }
+    }
+
+    let schemas = schemas_of(spec, roots);
+
+    let document = Data::Map(vec![
+        entry("asyncapi", string("2.6.0")),
+        entry("info", Data::Map(info)),
+        entry("defaultContentType", string(content_type)),
+        entry("channels", Data::Map(channels)),
+        entry("components", Data::Map(vec![
+            entry("messages", Data::Map(messages)),
+            entry("schemas", Data::Map(schemas)),
+        ])),
+    ]);
+    let mut output = payload::json::encode(&document);
+    output.push('\n');
+    output
+}
+
+/// Creates operation object describing how `method` is invoked.
+fn operation_of(method: &ImplementMethod, service_method: &ServiceMethod, message_name: &str) -> Data {
+    let qos = data_of(method, "QoS")
+        .and_then(|qos| match qos {
+            Data::Integer(qos @ 0..=2) => Some(qos),
+            _ => None,
+        })
+        .unwrap_or(0);
+    let retain = match data_of(method, "Retain") {
+        Some(Data::Boolean(retain)) => retain,
+        _ => false,
+    };
+    let mut operation = vec![
+        entry("operationId", string(message_name)),
+        entry("bindings", Data::Map(vec![
+            entry("mqtt", Data::Map(vec![
+                entry("qos", Data::Integer(qos)),
+                entry("retain", Data::Boolean(retain)),
+                entry("bindingVersion", string("0.1.0")),
+            ])),
+        ])),
+        entry("message", reference("messages", message_name)),
+    ];
+    if let Some(doc) = doc_of(&service_method.attributes) {
+        operation.insert(1, entry("description", string(&doc)));
+    }
+    Data::Map(operation)
+}
+
+/// Adds message named `message_name` to existing channel `operation`, in
+/// which case the operation accepts either of its messages.
+fn add_message(operation: &mut Data, message_name: &str) {
+    let operation = match operation {
+        Data::Map(operation) => operation,
+        _ => return,
+    };
+    let message = match operation.iter_mut().find(|(key, _)| key == "message") {
+        Some((_, message)) => message,
+        None => return,
+    };
+    let mut alternatives = match mem::replace(message, Data::Null) {
+        Data::Map(mut entries) if entries.iter().any(|(key, _)| key == "oneOf") => match entries.remove(0).1 {
+            Data::List(alternatives) => alternatives,
+            _ => Vec::new(),
+        },
+        other => vec![other],
+    };
+    alternatives.push(reference("messages", message_name));
+    *message = Data::Map(vec![entry("oneOf", Data::List(alternatives))]);
+}
+
+/// Creates channel parameters object for each `{name}` in `topic`, if any.
+fn parameters_of(topic: &str) -> Vec<(String, Data)> {
+    let parameters = topic.split('/')
+        .filter(|segment| segment.starts_with('{') && segment.ends_with('}'))
+        .map(|segment| (segment[1..segment.len() - 1].to_string(), Data::Map(vec![
+            entry("schema", Data::Map(vec![entry("type", string("string"))])),
+        ])))
+        .collect::<Vec<_>>();
+    if parameters.is_empty() {
+        Vec::new()
+    } else {
+        vec![("parameters".into(), Data::Map(parameters))]
+    }
+}
+
+/// Creates JSON Schemas, keyed by component name, describing the aliases,
+/// enums and records reachable from `roots`, as well as every distinct
+/// reference to a generic record.
+pub(crate) fn schemas_of<'a>(spec: &Specification<'a>, roots: Vec<&TypeRef<'a>>) -> Vec<(String, Data)> {
+    let mut schemas = reachable(spec, roots.iter().copied())
+        .into_iter()
+        .filter_map(|definition| match definition {
+            Definition::Alias(alias) => Some((schema_name(spec.qualify(&alias.name)), alias_schema(spec, alias))),
+            Definition::Enum(enum_) => {
+                let mut schema = vec![
+                    entry("type", string(if enum_.is_integer() { "integer" } else { "string" })),
+                    entry("enum", Data::List(enum_.variants.iter()
+                        .map(|variant| variant.wire_value())
+                        .collect())),
+                ];
+                if let Some(doc) = doc_of(&enum_.attributes) {
+                    schema.insert(0, entry("description", string(&doc)));
+                }
+                Some((schema_name(spec.qualify(&enum_.name)), Data::Map(schema)))
+            }
+            Definition::Record(record) if record.generic_parameters.is_empty() => {
+                Some((schema_name(spec.qualify(&record.name)), record_schema(spec, record, &record.entries)))
+            }
+            Definition::Record(_) | Definition::Primitive(_) => None,
+        })
+        .collect::<Vec<_>>();
+    for (name, type_ref) in instances(spec, roots) {
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            schemas.push((schema_name(name), record_schema(spec, record, &record.entries_of(&type_ref))));
+        }
+    }
+    schemas
+}
+
+/// Creates JSON Schema describing `alias`.
+///
+/// The schema of the aliased type is extended with the constraints of
+/// `alias`, unless it is a reference, in which case it is combined with them
+/// using `allOf`.
+fn alias_schema<'a>(spec: &Specification<'a>, alias: &Alias<'a>) -> Data {
+    let mut schema = match schema_of(spec, &alias.definition) {
+        Data::Map(schema) if !schema.iter().any(|(key, _)| key == "$ref") => schema,
+        reference => vec![entry("allOf", Data::List(vec![reference]))],
+    };
+    let mut set = |key: &str, value: Data| match schema.iter_mut().find(|(other, _)| other == key) {
+        Some((_, other)) => *other = value,
+        None => schema.push(entry(key, value)),
+    };
+    for constraint in &alias.constraints {
+        match constraint {
+            Constraint::Range { start, end, is_inclusive, .. } => {
+                if let Some(start) = start {
+                    set("minimum", start.into());
+                }
+                if let Some(end) = end {
+                    set(if *is_inclusive { "maximum" } else { "exclusiveMaximum" }, end.into());
+                }
+            }
+            Constraint::LengthMin { length, .. } => set("minLength", length.into()),
+            Constraint::LengthMax { length, .. } => set("maxLength", length.into()),
+            Constraint::Pattern { pattern, .. } => set("pattern", pattern.into()),
+        }
+    }
+    if schema.iter().any(|(key, _)| key == "exclusiveMaximum") {
+        schema.retain(|(key, _)| key != "maximum");
+    }
+    if let Some(doc) = doc_of(&alias.attributes) {
+        schema.insert(0, entry("description", string(&doc)));
+    }
+    Data::Map(schema)
+}
+
+/// Creates JSON Schema describing `record`, which has given `entries`.
+fn record_schema<'a>(spec: &Specification<'a>, record: &Record<'a>, entries: &[RecordEntry<'a>]) -> Data {
+    let mut schema = vec![entry("type", string("object"))];
+    if let Some(doc) = doc_of(&record.attributes) {
+        schema.insert(0, entry("description", string(&doc)));
+    }
+    schema.push(entry("properties", Data::Map(entries.iter()
+        .map(|field| {
+            let mut property = if field.may_be_null() {
+                Data::Map(vec![entry("oneOf", Data::List(vec![
+                    schema_of(spec, field.value_type()),
+                    Data::Map(vec![entry("type", string("null"))]),
+                ]))])
+            } else {
+                schema_of(spec, &field.type_ref)
+            };
+            if let Data::Map(ref mut property) = property {
+                if let Some(doc) = doc_of(&field.attributes) {
+                    property.insert(0, ("description".into(), string(&doc)));
+                }
+                if let Some(ref default) = field.default {
+                    property.push(("default".into(), default.into()));
+                }
+            }
+            (field.name.as_str().to_string(), property)
+        })
+        .collect())));
+    let required = entries.iter()
+        .filter(|entry| !entry.may_be_absent())
+        .map(|entry| string(entry.name.as_str()))
+        .collect::<Vec<_>>();
+    if !required.is_empty() {
+        schema.push(entry("required", Data::List(required)));
+    }
+    schema.push(entry("additionalProperties", Data::Boolean(false)));
+    Data::Map(schema)
+}
+
+/// Creates JSON Schema describing the type referred to by `type_ref`.
+pub(crate) fn schema_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> Data {
+    if let Some(record) = spec.resolve_record(&type_ref.name) {
+        let name = if record.generic_parameters.is_empty() {
+            spec.qualify(&record.name)
+        } else {
+            instance_name(spec, type_ref)
+        };
+        return reference("schemas", &schema_name(name));
+    }
+    if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+        return reference("schemas", &schema_name(spec.qualify(&enum_.name)));
+    }
+    if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+        return reference("schemas", &schema_name(spec.qualify(&alias.name)));
+    }
+    let integer = |min: i128, max: i128| Data::Map(vec![
+        entry("type", string("integer")),
+        entry("minimum", Data::Integer(min)),
+        entry("maximum", Data::Integer(max)),
+    ]);
+    let params = &type_ref.params;
+    Data::Map(match (type_ref.name.as_str(), params.len()) {
+        ("Boolean", 0) => vec![entry("type", string("boolean"))],
+        ("Float32", 0) => vec![entry("type", string("number")), entry("format", string("float"))],
+        ("Float64", 0) => vec![entry("type", string("number")), entry("format", string("double"))],
+        ("I8", 0) => return integer(i8::MIN.into(), i8::MAX.into()),
+        ("I16", 0) => return integer(i16::MIN.into(), i16::MAX.into()),
+        ("I32", 0) => return integer(i32::MIN.into(), i32::MAX.into()),
+        ("I64", 0) => return integer(i64::MIN.into(), i64::MAX.into()),
+        ("U8", 0) => return integer(0, u8::MAX.into()),
+        ("U16", 0) => return integer(0, u16::MAX.into()),
+        ("U32", 0) => return integer(0, u32::MAX.into()),
+        ("U64", 0) => return integer(0, u64::MAX.into()),
+        ("String", 0) => vec![entry("type", string("string"))],
+        ("List", 1) => vec![
+            entry("type", string("array")),
+            entry("items", schema_of(spec, &params[0])),
+        ],
+        ("Set", 1) => vec![
+            entry("type", string("array")),
+            entry("items", schema_of(spec, &params[0])),
+            entry("uniqueItems", Data::Boolean(true)),
+        ],
+        ("Map", 2) => vec![
+            entry("type", string("object")),
+            entry("additionalProperties", schema_of(spec, &params[1])),
+        ],
+        ("Option", 1) => vec![
+            entry("oneOf", Data::List(vec![
+                schema_of(spec, &params[0]),
+                Data::Map(vec![entry("type", string("null"))]),
+            ])),
+        ],
+        _ => Vec::new(),
+    })
+}
+
+/// Looks up named `key` among the data of `method`.
+fn data_of(method: &ImplementMethod, key: &str) -> Option<Data> {
+    method.data.iter()
+        .find(|(name, _)| name.as_str() == key)
+        .map(|(_, value): &(_, Value)| value.into())
+}
+
+/// Looks up named string `key` among the data of `method`.
+fn data_string(method: &ImplementMethod, key: &str) -> Option<String> {
+    match data_of(method, key) {
+        Some(Data::String(string)) => Some(string),
+        _ => None,
+    }
+}
+
+/// Creates reference to named `component` of given `kind`, such as
+/// `schemas`.
+pub(crate) fn reference(kind: &str, component: &str) -> Data {
+    Data::Map(vec![entry("$ref", string(&format!("#/components/{}/{}", kind, component)))])
+}
+
+/// Turns qualified specification name into valid component name.
+pub(crate) fn schema_name(qualified: String) -> String {
+    qualified.replace("::", ".")
+}
+
+pub(crate) fn entry(key: &str, value: Data) -> (String, Data) {
+    (key.into(), value)
+}
+
+pub(crate) fn string(string: &str) -> Data {
+    Data::String(string.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_implementation() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Reading { Value: Float64, Unit: Unit, Note: Option<String> }\n",
+                    "enum Unit { Celsius, Kelvin }\n",
+                    "service Sensor {\n",
+                    "    @Doc(\"Publishes readings.\")\n",
+                    "    method Publish(): Reading throws Fault;\n",
+                    "    method Reset(U8) throws Fault;\n",
+                    "}\n",
+                    "implement Sensor using MQTT/JSON {\n",
+                    "    method Publish { Topic: \"sensor/{id}/reading\", QoS: 1, Retain: true, Errors: { Fault: \"sensor/{id}/reading\" } }\n",
+                    "    method Reset { Topic: \"sensor/{id}/reset\", Errors: { Fault: \"sensor/{id}/fault\" } }\n",
+                    "}\n",
+                    "record Fault { Reason: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let document = payload::json::decode(&generate(&spec, &spec.implementations[0], "1.2.3")).unwrap();
+
+        assert_eq!(document.get("asyncapi"), Some(&string("2.6.0")));
+        let channels = document.get("channels").unwrap();
+        let reading = channels.get("sensor/{id}/reading").unwrap();
+        assert!(reading.get("parameters").and_then(|parameters| parameters.get("id")).is_some());
+        let subscribe = reading.get("subscribe").unwrap();
+        assert_eq!(subscribe.get("description"), Some(&string("Publishes readings.")));
+        assert_eq!(subscribe.get("bindings").and_then(|bindings| bindings.get("mqtt")), Some(&Data::Map(vec![
+            entry("qos", Data::Integer(1)),
+            entry("retain", Data::Boolean(true)),
+            entry("bindingVersion", string("0.1.0")),
+        ])));
+        assert_eq!(subscribe.get("message"), Some(&Data::Map(vec![entry("oneOf", Data::List(vec![
+            reference("messages", "PublishOutput"),
+            reference("messages", "PublishFault"),
+        ]))])));
+        let reset = channels.get("sensor/{id}/reset").unwrap().get("publish").unwrap();
+        assert_eq!(reset.get("message"), Some(&reference("messages", "ResetInput")));
+        let fault = channels.get("sensor/{id}/fault").unwrap().get("subscribe").unwrap();
+        assert_eq!(fault.get("message"), Some(&reference("messages", "ResetFault")));
+
+        let components = document.get("components").unwrap();
+        let messages = components.get("messages").unwrap();
+        assert_eq!(messages.get("PublishOutput").and_then(|message| message.get("payload")),
+            Some(&reference("schemas", "Reading")));
+        let schemas = components.get("schemas").unwrap();
+        assert_eq!(schemas.get("Reading").and_then(|reading| reading.get("required")),
+            Some(&Data::List(vec![string("Value"), string("Unit")])));
+        assert!(schemas.get("Unit").is_some());
+        assert!(schemas.get("Fault").is_some());
+    }
+}
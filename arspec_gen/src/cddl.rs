@@ -0,0 +1,326 @@
+//! Concise Data Definition Language (CDDL) generation, as described by
+//! RFC 8610.
+//!
+//! Every service is described by a CDDL document, the first rule of which is
+//! named after the service and admits any method input or output payload.
+//! The rules that follow define every alias, enum, record and primitive
+//! reachable from those payloads, in the order they are first referred to.
+//!
+//! `Float32` and `Float64` are mapped to `float16-32` and `float`,
+//! respectively, as CBOR encoders commonly shorten floating-point numbers
+//! that can be represented exactly using fewer bits. Generic records become
+//! generic rules. Record entries that may be absent become optional members,
+//! with any scalar or enum default values given using `.default` controls.
+//! Alias constraints are expressed using ranges and the `.ge`, `.le`, `.lt`,
+//! `.size` and `.regexp` controls, which means that string lengths are
+//! constrained in bytes rather than in characters.
+
+use arspec::payload::{json, Data};
+use arspec::spec::{Alias, Constraint, Record, RecordEntry, Service, Specification, TypeRef};
+use arspec_parser::Span;
+use crate::{Definition, doc_of, reachable};
+use crate::types::{self, TypeNames};
+use crate::xsd::regex_of;
+use std::fmt::Write;
+
+/// Generates CDDL document describing the payloads of `service`.
+pub fn generate<'a>(spec: &Specification<'a>, service: &Service<'a>) -> String {
+    let roots = service.methods.iter()
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()))
+        .collect::<Vec<_>>();
+
+    let mut output = String::new();
+    let _ = writeln!(output, "; Payloads of service `{}`.", spec.qualify(&service.name));
+    let mut choices = Vec::new();
+    for type_ref in &roots {
+        let choice = type_of(spec, type_ref);
+        if !choices.contains(&choice) {
+            choices.push(choice);
+        }
+    }
+    let _ = writeln!(output, "{}-payload = {}", rule_name(spec.qualify(&service.name)), if choices.is_empty() {
+        "nil".into()
+    } else {
+        choices.join(" / ")
+    });
+
+    for definition in reachable(spec, roots) {
+        output.push('\n');
+        match definition {
+            Definition::Alias(alias) => write_alias(spec, alias, &mut output),
+            Definition::Enum(enum_) => {
+                write_doc(doc_of(&enum_.attributes), &mut output);
+                let variants = enum_.variants.iter()
+                    .map(|variant| json::encode(&variant.wire_value()))
+                    .collect::<Vec<_>>();
+                let _ = writeln!(output, "{} = {}", rule_name(spec.qualify(&enum_.name)), if variants.is_empty() {
+                    "nil".into()
+                } else {
+                    variants.join(" / ")
+                });
+            }
+            Definition::Record(record) => write_record(spec, record, &mut output),
+            Definition::Primitive(name) => {
+                if let Some(primitive) = spec.resolve_primitive(name) {
+                    write_doc(doc_of(&primitive.attributes), &mut output);
+                }
+                let _ = writeln!(output, "{} = {}", name.as_str(), match name.as_str() {
+                    "Boolean" => "bool",
+                    "Float32" => "float16-32",
+                    "Float64" => "float",
+                    "I8" => "-128..127",
+                    "I16" => "-32768..32767",
+                    "I32" => "-2147483648..2147483647",
+                    "I64" => "-9223372036854775808..9223372036854775807",
+                    "U8" => "uint .size 1",
+                    "U16" => "uint .size 2",
+                    "U32" => "uint .size 4",
+                    "U64" => "uint .size 8",
+                    "String" => "tstr",
+                    _ => "any",
+                });
+            }
+        }
+    }
+    output
+}
+
+fn write_alias<'a>(spec: &Specification<'a>, alias: &Alias<'a>, output: &mut String) {
+    write_doc(doc_of(&alias.attributes), output);
+    let base = type_of(spec, &alias.definition);
+    let is_float = matches!(spec.unalias(&alias.definition).0.name.as_str(), "Float32" | "Float64");
+    let number = |value| match Data::from(value) {
+        Data::Integer(integer) if is_float => format!("{:?}", integer as f64),
+        Data::Integer(integer) => integer.to_string(),
+        Data::Float(number) => format!("{:?}", number),
+        _ => String::new(),
+    };
+    let mut controls = Vec::new();
+    for constraint in &alias.constraints {
+        match constraint {
+            Constraint::Range { start: Some(start), end: Some(end), is_inclusive, .. } => {
+                let operator = if *is_inclusive { ".." } else { "..." };
+                controls.push(format!(".and ({}{}{})", number(start), operator, number(end)));
+            }
+            Constraint::Range { start: Some(start), end: None, .. } => controls.push(format!(".ge {}", number(start))),
+            Constraint::Range { start: None, end: Some(end), is_inclusive, .. } => {
+                controls.push(format!("{} {}", if *is_inclusive { ".le" } else { ".lt" }, number(end)));
+            }
+            Constraint::Range { .. } | Constraint::LengthMin { .. } | Constraint::LengthMax { .. } => {}
+            Constraint::Pattern { pattern, .. } => {
+                let pattern = regex_of(&pattern.as_string().unwrap_or_default());
+                controls.push(format!(".regexp \"{}\"", pattern.replace('\\', "\\\\").replace('"', "\\\"")));
+            }
+        }
+    }
+    if alias.length_min().is_some() || alias.length_max().is_some() {
+        let min = alias.length_min().unwrap_or(0);
+        let max = alias.length_max().unwrap_or(u64::MAX);
+        controls.insert(0, format!(".size ({}..{})", min, max));
+    }
+    let rule = match controls.as_slice() {
+        [] => base,
+        [control] => format!("{} {}", base, control),
+        // Controls cannot be chained, which is why the size and pattern of
+        // strings, the only controls that may be combined, are applied to
+        // separate copies of the aliased type.
+        controls => controls.iter()
+            .map(|control| format!("({} {})", base, control))
+            .collect::<Vec<_>>()
+            .join(" .and "),
+    };
+    let _ = writeln!(output, "{} = {}", rule_name(spec.qualify(&alias.name)), rule);
+}
+
+fn write_record<'a>(spec: &Specification<'a>, record: &Record<'a>, output: &mut String) {
+    write_doc(doc_of(&record.attributes), output);
+    let mut name = rule_name(spec.qualify(&record.name));
+    if !record.generic_parameters.is_empty() {
+        let parameters = record.generic_parameters.iter()
+            .map(|parameter| parameter.as_str())
+            .collect::<Vec<_>>();
+        let _ = write!(name, "<{}>", parameters.join(", "));
+    }
+    if record.entries.is_empty() {
+        let _ = writeln!(output, "{} = {{}}", name);
+        return;
+    }
+    let _ = writeln!(output, "{} = {{", name);
+    for entry in &record.entries {
+        let mut type_ = generic_type_of(spec, entry.value_type(), &record.generic_parameters);
+        if entry.may_be_null() {
+            type_.push_str(" / nil");
+        }
+        if let Some(default) = default_of(entry) {
+            if entry.may_be_null() {
+                type_ = format!("({})", type_);
+            }
+            let _ = write!(type_, " .default {}", default);
+        }
+        let _ = writeln!(output, "  {}{}: {},",
+            if entry.may_be_absent() { "? " } else { "" },
+            entry.name.as_str(),
+            type_);
+    }
+    output.push_str("}\n");
+}
+
+/// Formats the default value of record `entry` as a CDDL value, if it has
+/// one and it is a number, string or boolean.
+fn default_of(entry: &RecordEntry) -> Option<String> {
+    match Data::from(entry.default.as_ref()?) {
+        Data::Boolean(boolean) => Some(boolean.to_string()),
+        Data::Integer(integer) => match entry.value_type().name.as_str() {
+            "Float32" | "Float64" => Some(format!("{:?}", integer as f64)),
+            _ => Some(integer.to_string()),
+        },
+        Data::Float(number) if number.is_finite() => Some(format!("{:?}", number)),
+        Data::String(string) => Some(format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))),
+        _ => None,
+    }
+}
+
+fn write_doc(doc: Option<String>, output: &mut String) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            output.push(';');
+            if !line.is_empty() {
+                output.push(' ');
+                output.push_str(line);
+            }
+            output.push('\n');
+        }
+    }
+}
+
+/// Formats CDDL type expression matching the type referred to by `type_ref`.
+fn type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> String {
+    generic_type_of(spec, type_ref, &[])
+}
+
+/// Formats CDDL type expression matching the type referred to by `type_ref`,
+/// which may refer to any of `generic_parameters`.
+fn generic_type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, generic_parameters: &[Span<'a>]) -> String {
+    types::generic_type_of(&mut CddlNames, spec, type_ref, generic_parameters)
+}
+
+/// Names CDDL type expressions.
+struct CddlNames;
+
+impl<'a> TypeNames<'a> for CddlNames {
+    fn definition(&mut self, spec: &Specification<'a>, name: &Span<'a>) -> String {
+        rule_name(spec.qualify(name))
+    }
+
+    fn list(&mut self, item: String) -> String {
+        format!("[* {}]", item)
+    }
+
+    fn map(&mut self, key: String, value: String) -> String {
+        format!("{{ * {} => {} }}", key, value)
+    }
+
+    fn option(&mut self, value: String) -> String {
+        format!("{} / nil", value)
+    }
+
+    fn primitive(&mut self, _spec: &Specification<'a>, type_ref: &TypeRef<'a>, _is_nested: bool) -> String {
+        type_ref.name.as_str().into()
+    }
+}
+
+/// Turns qualified specification name into valid CDDL rule name.
+fn rule_name(qualified: String) -> String {
+    qualified.replace("::", ".")
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_service() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "@Doc(\"\n    A stored entry.\n\")\n",
+                    "record Entry { Name: String, Level: Level, Counts: Map<String, List<U16>>, Note: Option<I8> }\n",
+                    "enum Level { Low, High }\n",
+                    "service S { method Get(U64): Entry; method Put(Entry); }\n",
+                    "implement S using COAP/CBOR {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(generate(&spec, &spec.services[0]), concat!(
+            "; Payloads of service `S`.\n",
+            "S-payload = U64 / Entry\n",
+            "\n",
+            "U64 = uint .size 8\n",
+            "\n",
+            "; A stored entry.\n",
+            "Entry = {\n",
+            "  Name: String,\n",
+            "  Level: Level,\n",
+            "  Counts: { * String => [* U16] },\n",
+            "  ? Note: I8 / nil,\n",
+            "}\n",
+            "\n",
+            "String = tstr\n",
+            "\n",
+            "Level = \"Low\" / \"High\"\n",
+            "\n",
+            "U16 = uint .size 2\n",
+            "\n",
+            "I8 = -128..127\n",
+        ));
+    }
+
+    #[test]
+    fn generate_aliases() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "type Port = U16 where 1..=65535;\n",
+                    "type Low = Port where ..1024;\n",
+                    "type Ratio = Float32 where 0..1;\n",
+                    "@Doc(\"A name.\")\n",
+                    "type Name = String where len >= 1, pattern \"^[a-z]+\";\n",
+                    "record Entry { Name: Name, Port: Low, Ratio: Ratio }\n",
+                    "service S { method Get(): Entry; }\n",
+                    "implement S using COAP/CBOR {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(generate(&spec, &spec.services[0]), concat!(
+            "; Payloads of service `S`.\n",
+            "S-payload = Entry\n",
+            "\n",
+            "Entry = {\n",
+            "  Name: Name,\n",
+            "  Port: Low,\n",
+            "  Ratio: Ratio,\n",
+            "}\n",
+            "\n",
+            "; A name.\n",
+            "Name = (String .size (1..18446744073709551615)) .and (String .regexp \"[a-z]+.*\")\n",
+            "\n",
+            "String = tstr\n",
+            "\n",
+            "Low = Port .lt 1024\n",
+            "\n",
+            "Port = U16 .and (1..65535)\n",
+            "\n",
+            "U16 = uint .size 2\n",
+            "\n",
+            "Ratio = Float32 .and (0.0...1.0)\n",
+            "\n",
+            "Float32 = float16-32\n",
+        ));
+    }
+}
@@ -0,0 +1,694 @@
+//! Java source code generation.
+//!
+//! Every record becomes a class with a private field, a getter and a setter
+//! per record entry, every enum becomes a Java `enum`, and every service
+//! becomes an interface with one method per service method. Classes of
+//! records extending other records extend the classes of those records,
+//! rather than repeating their entries. Records and enums used by services
+//! with JSON implementations are annotated for serialization using Jackson.
+//! Fields of record entries with scalar or enum default values are
+//! initialized to those values. Enums with integer variant values hold those
+//! values, which are what such enums are serialized as. Aliases are replaced
+//! by the types they alias.
+//!
+//! Java has no unsigned integer types, which is why `U8`, `U16` and `U32`
+//! are mapped to signed types wide enough to hold their values. Setters
+//! check that integers are within the ranges of their primitives whenever
+//! the Java types of those integers can hold values outside those ranges,
+//! or are constrained to narrower ranges by aliases.
+//! `U64` is mapped to `long` by default, which means that values larger
+//! than `Long.MAX_VALUE` cannot be represented. Configure it as
+//! `java.math.BigInteger` if such values must be supported.
+
+use arspec::payload::Data;
+use arspec::spec::{Attribute, Constraint, Enum, Record, Service, Specification, TypeRef};
+use arspec_parser::Span;
+use crate::{Definition, doc_of, reachable};
+use crate::types::{self, TypeNames};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+/// Words that cannot be used as Java identifiers.
+const KEYWORDS: &[&str] = &[
+    "_", "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class",
+    "const", "continue", "default", "do", "double", "else", "enum", "extends", "false", "final",
+    "finally", "float", "for", "goto", "if", "implements", "import", "instanceof", "int",
+    "interface", "long", "native", "new", "null", "package", "private", "protected", "public",
+    "return", "short", "static", "strictfp", "super", "switch", "synchronized", "this", "throw",
+    "throws", "transient", "true", "try", "void", "volatile", "while",
+];
+
+/// Java generation options.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Name of the package of all generated types, such as
+    /// `eu.arrowhead.common`.
+    pub package: String,
+
+    /// Java types of primitives, such as `U64` and `java.math.BigInteger`,
+    /// overriding the default mappings.
+    pub primitives: Vec<(String, String)>,
+}
+
+/// A Java source code generator.
+pub struct Java<'a: 'b, 'b> {
+    spec: &'b Specification<'a>,
+    options: &'b Options,
+    json: Vec<&'b str>,
+}
+
+impl<'a: 'b, 'b> Java<'a, 'b> {
+    /// Creates Java source code generator for the definitions of `spec`.
+    pub fn new(spec: &'b Specification<'a>, options: &'b Options) -> Self {
+        let roots = spec.implementations.iter()
+            .filter(|implementation| implementation.encoding.as_str() == "JSON")
+            .filter_map(|implementation| spec.resolve_service(&implementation.name))
+            .flat_map(|service| service.methods.iter())
+            .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()));
+        let json = reachable(spec, roots).into_iter()
+            .filter(|definition| !matches!(definition, Definition::Alias(_) | Definition::Primitive(_)))
+            .map(|definition| definition.name().as_str())
+            .collect();
+        Java { spec, options, json }
+    }
+
+    /// Determines whether the named type is used by any JSON-encoded service.
+    fn is_json(&self, name: &str) -> bool {
+        self.json.contains(&name)
+    }
+
+    /// Generates Java `enum` source code for `enum_`.
+    pub fn enum_(&self, enum_: &Enum<'a>) -> String {
+        let name = enum_.name.as_str();
+        let mut imports = BTreeSet::new();
+        let mut body = String::new();
+        for (index, variant) in enum_.variants.iter().enumerate() {
+            if index > 0 {
+                body.push_str(",\n");
+            }
+            write_doc(&variant.attributes, 1, &mut body);
+            let identifier = identifier(variant.name.as_str());
+            match variant.wire_value() {
+                Data::Integer(value) => {
+                    let _ = write!(body, "    {}({})", identifier, value);
+                }
+                Data::String(value) => {
+                    if identifier != value && self.is_json(name) {
+                        imports.insert("com.fasterxml.jackson.annotation.JsonProperty".to_string());
+                        let _ = writeln!(body, "    @JsonProperty({})", string_literal(&value));
+                    }
+                    let _ = write!(body, "    {}", identifier);
+                }
+                _ => {}
+            }
+        }
+        if enum_.is_integer() {
+            let type_ = if enum_.variants.iter().all(|variant| match variant.wire_value() {
+                Data::Integer(value) => i32::try_from(value).is_ok(),
+                _ => true,
+            }) { "int" } else { "long" };
+            if self.is_json(name) {
+                imports.insert("com.fasterxml.jackson.annotation.JsonCreator".to_string());
+                imports.insert("com.fasterxml.jackson.annotation.JsonValue".to_string());
+            }
+            let _ = write!(body, concat!(
+                ";\n",
+                "\n",
+                "    private final {type_} value;\n",
+                "\n",
+                "    {name}({type_} value) {{\n",
+                "        this.value = value;\n",
+                "    }}\n",
+                "\n",
+                "{json_value}",
+                "    public {type_} getValue() {{\n",
+                "        return value;\n",
+                "    }}\n",
+                "\n",
+                "{json_creator}",
+                "    public static {name} of({type_} value) {{\n",
+                "        for ({name} variant : values()) {{\n",
+                "            if (variant.value == value) {{\n",
+                "                return variant;\n",
+                "            }}\n",
+                "        }}\n",
+                "        throw new IllegalArgumentException(value + \" is not a variant value of enum {name}\");\n",
+                "    }}",
+            ),
+                name = name,
+                type_ = type_,
+                json_value = if self.is_json(name) { "    @JsonValue\n" } else { "" },
+                json_creator = if self.is_json(name) { "    @JsonCreator\n" } else { "" },
+            );
+        }
+        if !body.is_empty() {
+            body.push('\n');
+        }
+
+        let mut output = self.header(&imports);
+        write_doc(&enum_.attributes, 0, &mut output);
+        let _ = write!(output, "public enum {} {{\n{}}}\n", name, body);
+        output
+    }
+
+    /// Generates Java class source code for `record`.
+    pub fn record(&self, record: &Record<'a>) -> String {
+        let name = record.name.as_str();
+        let is_json = self.is_json(name);
+        let generic_parameters = &record.generic_parameters;
+        let base = record.base.as_ref()
+            .filter(|base| self.spec.resolve_record(&base.name).is_some());
+        let entries = record.entries.iter()
+            .filter(|entry| base.is_none() || entry.inherited_from.is_none())
+            .collect::<Vec<_>>();
+
+        let mut imports = BTreeSet::new();
+        if is_json && !entries.is_empty() {
+            imports.insert("com.fasterxml.jackson.annotation.JsonProperty".to_string());
+        }
+
+        let mut fields = String::new();
+        let mut accessors = String::new();
+        for entry in entries {
+            let field = field_name(entry.name.as_str());
+            let is_optional = entry.may_be_null();
+            let type_ = self.type_of(entry.value_type(), is_optional, generic_parameters, &mut imports);
+
+            write_doc(&entry.attributes, 1, &mut fields);
+            let default = entry.default.as_ref()
+                .and_then(|default| self.literal_of(&entry.type_ref, &default.into()))
+                .map(|literal| format!(" = {}", literal))
+                .unwrap_or_default();
+            let _ = writeln!(fields, "    private {} {}{};", type_, field, default);
+
+            let accessor = accessor_name(&field);
+            accessors.push('\n');
+            if is_json {
+                if is_optional {
+                    imports.insert("com.fasterxml.jackson.annotation.JsonInclude".to_string());
+                    accessors.push_str("    @JsonInclude(JsonInclude.Include.NON_NULL)\n");
+                }
+                let _ = writeln!(accessors, "    @JsonProperty(\"{}\")", entry.name.as_str());
+            }
+            let _ = write!(accessors, concat!(
+                "    public {type_} get{accessor}() {{\n",
+                "        return {field};\n",
+                "    }}\n",
+                "\n",
+            ), type_ = type_, accessor = accessor, field = field);
+            if is_json {
+                let _ = writeln!(accessors, "    @JsonProperty(\"{}\")", entry.name.as_str());
+            }
+            let _ = writeln!(accessors, "    public void set{}({} {}) {{", accessor, type_, field);
+            if let Some(check) = self.range_check(entry.value_type(), &field, is_optional) {
+                accessors.push_str(&check);
+            }
+            let _ = write!(accessors, concat!(
+                "        this.{field} = {field};\n",
+                "    }}\n",
+            ), field = field);
+        }
+
+        let parameters = if generic_parameters.is_empty() {
+            String::new()
+        } else {
+            let parameters = generic_parameters.iter()
+                .map(|parameter| parameter.as_str())
+                .collect::<Vec<_>>();
+            format!("<{}>", parameters.join(", "))
+        };
+        let extends = base
+            .map(|base| format!(" extends {}", self.type_of(base, true, generic_parameters, &mut imports)))
+            .unwrap_or_default();
+
+        let mut output = self.header(&imports);
+        write_doc(&record.attributes, 0, &mut output);
+        let _ = write!(output, "public class {}{}{} {{\n{}{}}}\n", name, parameters, extends, fields, accessors);
+        output
+    }
+
+    /// Generates Java interface source code for `service`.
+    pub fn service(&self, service: &Service<'a>) -> String {
+        let mut imports = BTreeSet::new();
+        let mut body = String::new();
+        for (index, method) in service.methods.iter().enumerate() {
+            if index > 0 {
+                body.push('\n');
+            }
+            write_doc(&method.attributes, 1, &mut body);
+            let output = method.output.as_ref()
+                .map_or("void".into(), |output| self.type_of(output, false, &[], &mut imports));
+            let input = method.input.as_ref()
+                .map(|input| {
+                    let is_defined = self.spec.resolve_record(&input.name).is_some()
+                        || self.spec.resolve_enum(&input.name).is_some()
+                        || self.spec.resolve_alias(&input.name).is_some();
+                    let parameter = if is_defined {
+                        field_name(input.name.as_str())
+                    } else {
+                        "input".into()
+                    };
+                    format!("{} {}", self.type_of(input, false, &[], &mut imports), parameter)
+                })
+                .unwrap_or_default();
+            let _ = writeln!(body, "    {} {}({});", output, field_name(method.name.as_str()), input);
+        }
+
+        let mut output = self.header(&imports);
+        write_doc(&service.attributes, 0, &mut output);
+        let _ = write!(output, "public interface {} {{\n{}}}\n", service.name.as_str(), body);
+        output
+    }
+
+    /// Formats package declaration and `imports`.
+    fn header(&self, imports: &BTreeSet<String>) -> String {
+        let mut output = String::new();
+        if !self.options.package.is_empty() {
+            let _ = writeln!(output, "package {};\n", self.options.package);
+        }
+        for import in imports {
+            let _ = writeln!(output, "import {};", import);
+        }
+        if !imports.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Determines Java type of the type referred to by `type_ref`, which may
+    /// refer to any of `generic_parameters`, adding any required imports to
+    /// `imports`.
+    fn type_of(
+        &self,
+        type_ref: &TypeRef<'a>,
+        is_boxed: bool,
+        generic_parameters: &[Span<'a>],
+        imports: &mut BTreeSet<String>,
+    ) -> String {
+        let mut names = JavaNames { java: self, imports, is_boxed };
+        types::generic_type_of(&mut names, self.spec, type_ref, generic_parameters)
+    }
+
+    /// Formats Java literal of `data`, which must be a valid value of the
+    /// type referred to by `type_ref`, unless that type is not a scalar or
+    /// enum type.
+    fn literal_of(&self, type_ref: &TypeRef<'a>, data: &Data) -> Option<String> {
+        if let Some(enum_) = self.spec.resolve_enum(&type_ref.name) {
+            return enum_.variant_of(data)
+                .map(|variant| format!("{}.{}", enum_.name.as_str(), identifier(variant.name.as_str())));
+        }
+        if let Some(alias) = self.spec.resolve_alias(&type_ref.name) {
+            return self.literal_of(&alias.definition, data);
+        }
+        let name = type_ref.name.as_str();
+        Some(match (name, type_ref.params.len(), data) {
+            ("Option", 1, Data::Null) => "null".into(),
+            ("Option", 1, data) => return self.literal_of(&type_ref.params[0], data),
+            (_, 0, data) => match (self.primitive(name), data) {
+                ("boolean", Data::Boolean(boolean)) => boolean.to_string(),
+                ("float", Data::Integer(integer)) => format!("{:?}f", *integer as f64),
+                ("float", Data::Float(number)) if number.is_finite() => format!("{:?}f", number),
+                ("double", Data::Integer(integer)) => format!("{:?}", *integer as f64),
+                ("double", Data::Float(number)) if number.is_finite() => format!("{:?}", number),
+                ("byte", Data::Integer(integer)) |
+                ("short", Data::Integer(integer)) |
+                ("int", Data::Integer(integer)) => integer.to_string(),
+                ("long", Data::Integer(integer)) => format!("{}L", integer),
+                ("String", Data::String(string)) => string_literal(string),
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Determines Java type of primitive `name`.
+    fn primitive<'c>(&'c self, name: &str) -> &'c str {
+        if let Some((_, java)) = self.options.primitives.iter().find(|(primitive, _)| primitive == name) {
+            return java;
+        }
+        match name {
+            "Boolean" => "boolean",
+            "Float32" => "float",
+            "Float64" => "double",
+            "I8" => "byte",
+            "I16" | "U8" => "short",
+            "I32" | "U16" => "int",
+            "I64" | "U32" | "U64" => "long",
+            "String" => "String",
+            _ => "Object",
+        }
+    }
+
+    /// Formats statement rejecting values of `field` outside the range of
+    /// the integer primitive referred to by `type_ref`, narrowed by the range
+    /// constraints of any aliases referred to, unless its Java type cannot
+    /// hold such values.
+    fn range_check(&self, type_ref: &TypeRef<'a>, field: &str, is_optional: bool) -> Option<String> {
+        let (type_ref, aliases) = self.spec.unalias(type_ref);
+        if !type_ref.params.is_empty() {
+            return None;
+        }
+        let name = type_ref.name.as_str();
+        let (mut min, mut max) = integer_range(name)?;
+        let constraints = aliases.iter().flat_map(|alias| alias.constraints.iter());
+        for constraint in constraints {
+            if let Constraint::Range { start, end, is_inclusive, .. } = constraint {
+                if let Some(Data::Integer(start)) = start.as_ref().map(Data::from) {
+                    min = min.max(start);
+                }
+                if let Some(Data::Integer(end)) = end.as_ref().map(Data::from) {
+                    max = max.min(if *is_inclusive { end } else { end - 1 });
+                }
+            }
+        }
+        let java = self.primitive(name);
+        let (java_min, java_max) = match java {
+            "byte" | "Byte" => (i8::MIN as i128, i8::MAX as i128),
+            "short" | "Short" => (i16::MIN as i128, i16::MAX as i128),
+            "int" | "Integer" => (i32::MIN as i128, i32::MAX as i128),
+            "long" | "Long" => (i64::MIN as i128, i64::MAX as i128),
+            _ => return None,
+        };
+        let suffix = if java == "long" || java == "Long" { "L" } else { "" };
+        let mut conditions = Vec::new();
+        if min > java_min {
+            conditions.push(format!("{} < {}{}", field, min, suffix));
+        }
+        if max < java_max {
+            conditions.push(format!("{} > {}{}", field, max, suffix));
+        }
+        if conditions.is_empty() {
+            return None;
+        }
+        let condition = conditions.join(" || ");
+        let condition = if is_optional {
+            format!("{} != null && ({})", field, condition)
+        } else {
+            condition
+        };
+        let range = if max > java_max {
+            format!("{}..", min)
+        } else {
+            format!("{}..={}", min, max)
+        };
+        Some(format!(
+            concat!(
+                "        if ({}) {{\n",
+                "            throw new IllegalArgumentException(\"{} must be in range {}\");\n",
+                "        }}\n",
+            ),
+            condition, field, range,
+        ))
+    }
+}
+
+/// Names Java types, adding any required imports to `imports`.
+struct JavaNames<'a: 'b, 'b, 'c> {
+    java: &'c Java<'a, 'b>,
+    imports: &'c mut BTreeSet<String>,
+    is_boxed: bool,
+}
+
+impl<'a: 'b, 'b, 'c> JavaNames<'a, 'b, 'c> {
+    /// Names generic collection `name`, importing it from `java.util`.
+    fn collection(&mut self, name: &str, params: &[String]) -> String {
+        self.imports.insert(format!("java.util.{}", name));
+        format!("{}<{}>", name, params.join(", "))
+    }
+}
+
+impl<'a: 'b, 'b, 'c> TypeNames<'a> for JavaNames<'a, 'b, 'c> {
+    const REPLACES_ALIASES: bool = true;
+
+    fn definition(&mut self, _spec: &Specification<'a>, name: &Span<'a>) -> String {
+        name.as_str().into()
+    }
+
+    fn list(&mut self, item: String) -> String {
+        self.collection("List", &[item])
+    }
+
+    fn set(&mut self, _spec: &Specification<'a>, _item_ref: &TypeRef<'a>, item: String) -> String {
+        self.collection("Set", &[item])
+    }
+
+    fn map(&mut self, key: String, value: String) -> String {
+        self.collection("Map", &[key, value])
+    }
+
+    fn option(&mut self, value: String) -> String {
+        value
+    }
+
+    fn primitive(&mut self, _spec: &Specification<'a>, type_ref: &TypeRef<'a>, is_nested: bool) -> String {
+        let java = self.java.primitive(type_ref.name.as_str());
+        let java = if self.is_boxed || is_nested { boxed(java) } else { java };
+        match java.rfind('.') {
+            Some(index) => {
+                self.imports.insert(java.into());
+                java[index + 1..].into()
+            }
+            None => java.into(),
+        }
+    }
+}
+
+/// Range of values of named integer primitive, if it is one.
+fn integer_range(name: &str) -> Option<(i128, i128)> {
+    Some(match name {
+        "I8" => (i8::MIN as i128, i8::MAX as i128),
+        "I16" => (i16::MIN as i128, i16::MAX as i128),
+        "I32" => (i32::MIN as i128, i32::MAX as i128),
+        "I64" => (i64::MIN as i128, i64::MAX as i128),
+        "U8" => (0, u8::MAX as i128),
+        "U16" => (0, u16::MAX as i128),
+        "U32" => (0, u32::MAX as i128),
+        "U64" => (0, u64::MAX as i128),
+        _ => return None,
+    })
+}
+
+/// Gets class name of primitive Java type `java`, or `java` itself if not a
+/// primitive type.
+fn boxed(java: &str) -> &str {
+    match java {
+        "boolean" => "Boolean",
+        "byte" => "Byte",
+        "char" => "Character",
+        "double" => "Double",
+        "float" => "Float",
+        "int" => "Integer",
+        "long" => "Long",
+        "short" => "Short",
+        _ => java,
+    }
+}
+
+/// Turns `name` into Java field or method name, such as `serviceURI` for
+/// `ServiceURI` or `ttl` for `TTL`.
+fn field_name(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(name.len());
+    let mut index = 0;
+    while index < chars.len() && chars[index].is_uppercase() {
+        let is_word_start = chars.get(index + 1).is_some_and(|ch| ch.is_lowercase());
+        if index > 0 && is_word_start {
+            break;
+        }
+        output.extend(chars[index].to_lowercase());
+        index += 1;
+    }
+    output.extend(&chars[index..]);
+    identifier(&output)
+}
+
+/// Turns Java field name into the name used by its getter and setter, such
+/// as `ServiceURI` for `serviceURI`.
+fn accessor_name(field: &str) -> String {
+    let field = field.trim_end_matches('_');
+    let mut chars = field.chars();
+    chars.next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Appends `_` to `name` if it is a Java keyword.
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.into()
+    }
+}
+
+/// Formats Java string literal containing `string`.
+fn string_literal(string: &str) -> String {
+    let mut literal = String::from("\"");
+    for ch in string.chars() {
+        match ch {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            ch => literal.push(ch),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Writes Javadoc comment containing the `@Doc` of `attributes`, if any, at
+/// given `level` of indentation.
+fn write_doc(attributes: &[Attribute], level: usize, output: &mut String) {
+    let doc = match doc_of(attributes) {
+        Some(doc) => doc,
+        None => return,
+    };
+    let indent = "    ".repeat(level);
+    let _ = writeln!(output, "{}/**", indent);
+    for line in doc.lines() {
+        let line = line
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace("*/", "*&#47;");
+        if line.is_empty() {
+            let _ = writeln!(output, "{} *", indent);
+        } else {
+            let _ = writeln!(output, "{} * {}", indent, line);
+        }
+    }
+    let _ = writeln!(output, "{} */", indent);
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_definitions() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "@Doc(\"\n    A system <providing> services.\n\")\n",
+                    "record System { Name: String, Port: U16, TTL: Option<U32>, Tags: Set<String>, Id: U64 }\n",
+                    "enum Kind { Sensor, default }\n",
+                    "service Registry { method Register(System): Kind; method List(): List<System>; }\n",
+                    "implement Registry using HTTP/JSON {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let options = Options {
+            package: "eu.arrowhead".into(),
+            primitives: vec![("U64".into(), "java.math.BigInteger".into())],
+        };
+        let java = Java::new(&spec, &options);
+
+        assert_eq!(java.record(&spec.records[0]), concat!(
+            "package eu.arrowhead;\n",
+            "\n",
+            "import com.fasterxml.jackson.annotation.JsonInclude;\n",
+            "import com.fasterxml.jackson.annotation.JsonProperty;\n",
+            "import java.math.BigInteger;\n",
+            "import java.util.Set;\n",
+            "\n",
+            "/**\n",
+            " * A system &lt;providing&gt; services.\n",
+            " */\n",
+            "public class System {\n",
+            "    private String name;\n",
+            "    private int port;\n",
+            "    private Long ttl;\n",
+            "    private Set<String> tags;\n",
+            "    private BigInteger id;\n",
+            "\n",
+            "    @JsonProperty(\"Name\")\n",
+            "    public String getName() {\n",
+            "        return name;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Name\")\n",
+            "    public void setName(String name) {\n",
+            "        this.name = name;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Port\")\n",
+            "    public int getPort() {\n",
+            "        return port;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Port\")\n",
+            "    public void setPort(int port) {\n",
+            "        if (port < 0 || port > 65535) {\n",
+            "            throw new IllegalArgumentException(\"port must be in range 0..=65535\");\n",
+            "        }\n",
+            "        this.port = port;\n",
+            "    }\n",
+            "\n",
+            "    @JsonInclude(JsonInclude.Include.NON_NULL)\n",
+            "    @JsonProperty(\"TTL\")\n",
+            "    public Long getTtl() {\n",
+            "        return ttl;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"TTL\")\n",
+            "    public void setTtl(Long ttl) {\n",
+            "        if (ttl != null && (ttl < 0L || ttl > 4294967295L)) {\n",
+            "            throw new IllegalArgumentException(\"ttl must be in range 0..=4294967295\");\n",
+            "        }\n",
+            "        this.ttl = ttl;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Tags\")\n",
+            "    public Set<String> getTags() {\n",
+            "        return tags;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Tags\")\n",
+            "    public void setTags(Set<String> tags) {\n",
+            "        this.tags = tags;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Id\")\n",
+            "    public BigInteger getId() {\n",
+            "        return id;\n",
+            "    }\n",
+            "\n",
+            "    @JsonProperty(\"Id\")\n",
+            "    public void setId(BigInteger id) {\n",
+            "        this.id = id;\n",
+            "    }\n",
+            "}\n",
+        ));
+
+        assert_eq!(java.enum_(&spec.enums[0]), concat!(
+            "package eu.arrowhead;\n",
+            "\n",
+            "import com.fasterxml.jackson.annotation.JsonProperty;\n",
+            "\n",
+            "public enum Kind {\n",
+            "    Sensor,\n",
+            "    @JsonProperty(\"default\")\n",
+            "    default_\n",
+            "}\n",
+        ));
+
+        assert_eq!(java.service(&spec.services[0]), concat!(
+            "package eu.arrowhead;\n",
+            "\n",
+            "import java.util.List;\n",
+            "\n",
+            "public interface Registry {\n",
+            "    Kind register(System system);\n",
+            "\n",
+            "    List<System> list();\n",
+            "}\n",
+        ));
+    }
+}
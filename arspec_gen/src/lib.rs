@@ -0,0 +1,15 @@
+//! Generators of schemas and source code from Arrowhead specifications.
+
+pub mod asyncapi;
+pub mod cddl;
+pub mod java;
+pub mod openapi;
+pub mod rust;
+pub mod typescript;
+pub mod xsd;
+
+mod reach;
+mod types;
+
+pub use arspec::spec::doc_of;
+pub use self::reach::{Definition, extend_reachable, instance_name, instances, reachable};
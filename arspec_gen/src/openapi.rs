@@ -0,0 +1,196 @@
+//! OpenAPI 3.1 generation, for implementations using the HTTP protocol.
+//!
+//! Each implementation method is expected to have `Method` and `Path` data,
+//! such as `POST` and `/register`, which are prefixed by any `BasePath`
+//! property of the implementation. Method inputs become request bodies,
+//! while outputs become `200` responses, or `204` responses if methods have
+//! no outputs. Method errors become responses with the status codes they
+//! are mapped to by `Errors` data. Path segments such as `{id}` become
+//! string path parameters. Payloads are described using the same JSON
+//! Schemas as in AsyncAPI documents.
+
+use arspec::payload::{self, Data};
+use arspec::spec::{Implement, Route, RouteSegment, Specification};
+use crate::asyncapi::{entry, schema_name, schema_of, schemas_of, string};
+use crate::doc_of;
+
+/// Generates OpenAPI document, in JSON, describing HTTP `implementation` of
+/// given `version`.
+///
+/// Implementation methods without `Method` or `Path` data are ignored.
+pub fn generate<'a>(spec: &Specification<'a>, implementation: &Implement<'a>, version: &str) -> String {
+    let service = spec.resolve_service(&implementation.name);
+    let routes = service.map(|service| implementation.routes(service)).unwrap_or_default();
+    let content_type = match implementation.encoding.as_str() {
+        "CBOR" => "application/cbor",
+        "XML" => "application/xml",
+        _ => "application/json",
+    };
+
+    let mut info = vec![
+        entry("title", string(&spec.qualify(&implementation.name))),
+        entry("version", string(version)),
+    ];
+    if let Some(doc) = service.and_then(|service| doc_of(&service.attributes)) {
+        info.push(entry("description", string(&doc)));
+    }
+
+    let mut paths: Vec<(String, Data)> = Vec::new();
+    let mut roots = Vec::new();
+    for route in &routes {
+        let service_method = route.service_method;
+        roots.extend(service_method.input.iter()
+            .chain(service_method.output.iter())
+            .chain(service_method.errors.iter()));
+
+        let index = match paths.iter().position(|(other, _)| *other == route.path) {
+            Some(index) => index,
+            None => {
+                paths.push((route.path.clone(), Data::Map(Vec::new())));
+                paths.len() - 1
+            }
+        };
+        if let Data::Map(ref mut path_item) = paths[index].1 {
+            let operation = operation_of(spec, route, content_type);
+            path_item.push(entry(&route.method.to_ascii_lowercase(), operation));
+        }
+    }
+
+    let document = Data::Map(vec![
+        entry("openapi", string("3.1.0")),
+        entry("info", Data::Map(info)),
+        entry("paths", Data::Map(paths)),
+        entry("components", Data::Map(vec![
+            entry("schemas", Data::Map(schemas_of(spec, roots))),
+        ])),
+    ]);
+    let mut output = payload::json::encode(&document);
+    output.push('\n');
+    output
+}
+
+/// Creates operation object describing how the service method of `route`
+/// is invoked.
+fn operation_of<'a>(spec: &Specification<'a>, route: &Route<'a, '_>, content_type: &str) -> Data {
+    let service_method = route.service_method;
+    let content = |schema: Data| Data::Map(vec![
+        entry(content_type, Data::Map(vec![entry("schema", schema)])),
+    ]);
+
+    let mut operation = vec![entry("operationId", string(service_method.name.as_str()))];
+    if let Some(doc) = doc_of(&service_method.attributes) {
+        operation.push(entry("description", string(&doc)));
+    }
+    let parameters = route.segments()
+        .filter_map(|segment| match segment {
+            RouteSegment::Parameter(name) => Some(name),
+            RouteSegment::Literal(_) => None,
+        })
+        .map(|name| Data::Map(vec![
+            entry("name", string(name)),
+            entry("in", string("path")),
+            entry("required", Data::Boolean(true)),
+            entry("schema", Data::Map(vec![entry("type", string("string"))])),
+        ]))
+        .collect::<Vec<_>>();
+    if !parameters.is_empty() {
+        operation.push(entry("parameters", Data::List(parameters)));
+    }
+    if let Some(ref input) = service_method.input {
+        operation.push(entry("requestBody", Data::Map(vec![
+            entry("required", Data::Boolean(true)),
+            entry("content", content(schema_of(spec, input))),
+        ])));
+    }
+
+    let mut responses = vec![match service_method.output {
+        Some(ref output) => entry("200", Data::Map(vec![
+            entry("description", string(&format!("Output of {}.", service_method.name.as_str()))),
+            entry("content", content(schema_of(spec, output))),
+        ])),
+        None => entry("204", Data::Map(vec![
+            entry("description", string(&format!("{} succeeded without output.", service_method.name.as_str()))),
+        ])),
+    }];
+    for (error, status) in &route.errors {
+        let description = spec.resolve_record(&error.name).and_then(|record| doc_of(&record.attributes))
+            .unwrap_or_else(|| format!("Error {}.", schema_name(spec.qualify(&error.name))));
+        responses.push(entry(&status.to_string(), Data::Map(vec![
+            entry("description", string(&description)),
+            entry("content", content(schema_of(spec, error))),
+        ])));
+    }
+    operation.push(entry("responses", Data::Map(responses)));
+    Data::Map(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use crate::asyncapi::reference;
+    use super::*;
+
+    #[test]
+    fn generate_implementation() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Query { Name: String }\n",
+                    "record Entry { Name: String, Port: U16 }\n",
+                    "@Doc(\"No matching entry exists.\")\n",
+                    "record NotFound { Name: String }\n",
+                    "record Unauthorized { Reason: String }\n",
+                    "service Registry {\n",
+                    "    @Doc(\"Finds entry.\")\n",
+                    "    method Find(Query): Entry throws NotFound, Unauthorized;\n",
+                    "    method Remove() throws Unauthorized;\n",
+                    "}\n",
+                    "implement Registry using HTTP/JSON {\n",
+                    "    property BasePath: \"/registry/\";\n",
+                    "    method Find { Method: \"POST\", Path: \"/find\", Errors: { NotFound: 404, Unauthorized: 401 } }\n",
+                    "    method Remove { Method: \"DELETE\", Path: \"/entries/{id}\", Errors: { Unauthorized: 401 } }\n",
+                    "}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        let document = payload::json::decode(&generate(&spec, &spec.implementations[0], "1.2.3")).unwrap();
+
+        assert_eq!(document.get("openapi"), Some(&string("3.1.0")));
+        let paths = document.get("paths").unwrap();
+        let find = paths.get("/registry/find").and_then(|path| path.get("post")).unwrap();
+        assert_eq!(find.get("description"), Some(&string("Finds entry.")));
+        let schema = |data: Option<&Data>| data
+            .and_then(|data| data.get("content"))
+            .and_then(|content| content.get("application/json"))
+            .and_then(|media_type| media_type.get("schema"))
+            .cloned();
+        assert_eq!(schema(find.get("requestBody")), Some(reference("schemas", "Query")));
+        let responses = find.get("responses").unwrap();
+        assert_eq!(schema(responses.get("200")), Some(reference("schemas", "Entry")));
+        assert_eq!(schema(responses.get("404")), Some(reference("schemas", "NotFound")));
+        assert_eq!(schema(responses.get("401")), Some(reference("schemas", "Unauthorized")));
+        assert_eq!(responses.get("404").and_then(|response| response.get("description")),
+            Some(&string("No matching entry exists.")));
+
+        let remove = paths.get("/registry/entries/{id}").and_then(|path| path.get("delete")).unwrap();
+        let parameters = remove.get("parameters").unwrap();
+        assert_eq!(parameters, &Data::List(vec![Data::Map(vec![
+            entry("name", string("id")),
+            entry("in", string("path")),
+            entry("required", Data::Boolean(true)),
+            entry("schema", Data::Map(vec![entry("type", string("string"))])),
+        ])]));
+        let responses = remove.get("responses").unwrap();
+        assert!(responses.get("204").is_some());
+        assert!(responses.get("401").is_some());
+
+        let schemas = document.get("components").and_then(|components| components.get("schemas")).unwrap();
+        for name in &["Query", "Entry", "NotFound", "Unauthorized"] {
+            assert!(schemas.get(name).is_some(), "{} has no schema", name);
+        }
+    }
+}
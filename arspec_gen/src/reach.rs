@@ -0,0 +1,226 @@
+use arspec::spec::{Alias, Enum, Record, Specification, TypeRef};
+use arspec_parser::Span;
+use std::ptr;
+
+/// A type definition reachable from some set of type references.
+#[derive(Debug)]
+pub enum Definition<'a: 'b, 'b> {
+    Alias(&'b Alias<'a>),
+    Enum(&'b Enum<'a>),
+    Record(&'b Record<'a>),
+
+    /// A primitive type without type parameters, which may either be
+    /// built-in or declared using `primitive`.
+    Primitive(&'b Span<'a>),
+}
+
+impl<'a: 'b, 'b> Definition<'a, 'b> {
+    /// Name of defined type.
+    pub fn name(&self) -> &'b Span<'a> {
+        match *self {
+            Definition::Alias(alias) => &alias.name,
+            Definition::Enum(enum_) => &enum_.name,
+            Definition::Record(record) => &record.name,
+            Definition::Primitive(name) => name,
+        }
+    }
+}
+
+/// Collects `enums` and `records`, followed by the type definitions reachable
+/// from their entries or from `roots` that are not among them.
+pub fn extend_reachable<'a: 'b, 'b, I>(
+    spec: &'b Specification<'a>,
+    enums: &[&'b Enum<'a>],
+    records: &[&'b Record<'a>],
+    roots: I,
+) -> Vec<Definition<'a, 'b>>
+    where I: IntoIterator<Item = &'b TypeRef<'a>>,
+{
+    let mut reached = Vec::new();
+    for record in records {
+        for type_ref in record.base.iter().chain(record.entries.iter().map(|entry| &entry.type_ref)) {
+            visit(spec, type_ref, &record.generic_parameters, &mut reached);
+        }
+    }
+    for type_ref in roots {
+        visit(spec, type_ref, &[], &mut reached);
+    }
+
+    let mut definitions = enums.iter()
+        .map(|enum_| Definition::Enum(enum_))
+        .chain(records.iter().map(|record| Definition::Record(record)))
+        .collect::<Vec<_>>();
+    for definition in reached {
+        let is_new = match definition {
+            Definition::Alias(_) => true,
+            Definition::Enum(enum_) => !enums.iter().any(|other| ptr::eq(*other, enum_)),
+            Definition::Record(record) => !records.iter().any(|other| ptr::eq(*other, record)),
+            Definition::Primitive(_) => true,
+        };
+        if is_new {
+            definitions.push(definition);
+        }
+    }
+    definitions
+}
+
+/// Collects the type definitions reachable from `roots`, in the order they
+/// are first encountered.
+///
+/// The generic `List`, `Set`, `Map` and `Option` types are never collected,
+/// but their type parameters are. Generic records are collected once, while
+/// the type parameters of every reference to them are.
+pub fn reachable<'a: 'b, 'b, I>(spec: &'b Specification<'a>, roots: I) -> Vec<Definition<'a, 'b>>
+    where I: IntoIterator<Item = &'b TypeRef<'a>>,
+{
+    let mut definitions = Vec::new();
+    for type_ref in roots {
+        visit(spec, type_ref, &[], &mut definitions);
+    }
+    definitions
+}
+
+/// Collects the references to generic records reachable from `roots`, in the
+/// order they are first encountered, each paired with its
+/// [`instance_name`][ins].
+///
+/// The generic parameters of any generic records referring to other generic
+/// records are replaced in the collected references. References with equal
+/// instance names are only collected once.
+///
+/// [ins]: fn.instance_name.html
+pub fn instances<'a: 'b, 'b, I>(spec: &'b Specification<'a>, roots: I) -> Vec<(String, TypeRef<'a>)>
+    where I: IntoIterator<Item = &'b TypeRef<'a>>,
+{
+    let mut records = Vec::new();
+    let mut instances = Vec::new();
+    for type_ref in roots {
+        visit_instances(spec, type_ref, &mut records, &mut instances);
+    }
+    return instances;
+
+    fn visit_instances<'a: 'b, 'b>(
+        spec: &'b Specification<'a>,
+        type_ref: &TypeRef<'a>,
+        records: &mut Vec<&'b Record<'a>>,
+        instances: &mut Vec<(String, TypeRef<'a>)>,
+    ) {
+        for param in &type_ref.params {
+            visit_instances(spec, param, records, instances);
+        }
+        let (target, aliases) = spec.unalias(type_ref);
+        if !aliases.is_empty() {
+            if spec.resolve_alias(&target.name).is_none() {
+                visit_instances(spec, target, records, instances);
+            }
+            return;
+        }
+        let record = match spec.resolve_record(&type_ref.name) {
+            Some(record) => record,
+            None => return,
+        };
+        if record.generic_parameters.is_empty() {
+            if records.iter().any(|other| ptr::eq(*other, record)) {
+                return;
+            }
+            records.push(record);
+            for entry in &record.entries {
+                visit_instances(spec, &entry.type_ref, records, instances);
+            }
+            return;
+        }
+        let name = instance_name(spec, type_ref);
+        if instances.iter().any(|(other, _)| *other == name) {
+            return;
+        }
+        instances.push((name, type_ref.clone()));
+        for entry in record.entries_of(type_ref).iter() {
+            visit_instances(spec, &entry.type_ref, records, instances);
+        }
+    }
+}
+
+/// Names the type referred to by `type_ref` after the qualified name of the
+/// referred type and its type parameters, such as `Page_List_String` for
+/// `Page<List<String>>`.
+///
+/// Meant for naming references to generic records when generating formats
+/// without generic types.
+pub fn instance_name<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> String {
+    let mut name = if let Some(record) = spec.resolve_record(&type_ref.name) {
+        spec.qualify(&record.name)
+    } else if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+        spec.qualify(&enum_.name)
+    } else if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+        spec.qualify(&alias.name)
+    } else {
+        type_ref.name.as_str().into()
+    };
+    for param in &type_ref.params {
+        name.push('_');
+        name.push_str(&instance_name(spec, param));
+    }
+    name
+}
+
+/// Collects the type definitions reachable from `type_ref` that are not
+/// already among `definitions`, ignoring any names among
+/// `generic_parameters`.
+fn visit<'a: 'b, 'b>(
+    spec: &'b Specification<'a>,
+    type_ref: &'b TypeRef<'a>,
+    generic_parameters: &[Span<'a>],
+    definitions: &mut Vec<Definition<'a, 'b>>,
+) {
+    let name = type_ref.name.as_str();
+    if type_ref.params.is_empty() && generic_parameters.iter().any(|parameter| parameter.as_str() == name) {
+        return;
+    }
+    if let Some(record) = spec.resolve_record(&type_ref.name) {
+        let is_new = !definitions.iter().any(|definition| match *definition {
+            Definition::Record(other) => ptr::eq(other, record),
+            _ => false,
+        });
+        if is_new {
+            definitions.push(Definition::Record(record));
+            for type_ref in record.base.iter().chain(record.entries.iter().map(|entry| &entry.type_ref)) {
+                visit(spec, type_ref, &record.generic_parameters, definitions);
+            }
+        }
+        for param in &type_ref.params {
+            visit(spec, param, generic_parameters, definitions);
+        }
+        return;
+    }
+    if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+        if !definitions.iter().any(|definition| match *definition {
+            Definition::Alias(other) => ptr::eq(other, alias),
+            _ => false,
+        }) {
+            definitions.push(Definition::Alias(alias));
+            visit(spec, &alias.definition, &[], definitions);
+        }
+        return;
+    }
+    if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+        if !definitions.iter().any(|definition| match *definition {
+            Definition::Enum(other) => ptr::eq(other, enum_),
+            _ => false,
+        }) {
+            definitions.push(Definition::Enum(enum_));
+        }
+        return;
+    }
+    if !type_ref.params.is_empty() {
+        for param in &type_ref.params {
+            visit(spec, param, generic_parameters, definitions);
+        }
+        return;
+    }
+    if !definitions.iter().any(|definition| match *definition {
+        Definition::Primitive(other) => other.as_str() == name,
+        _ => false,
+    }) {
+        definitions.push(Definition::Primitive(&type_ref.name));
+    }
+}
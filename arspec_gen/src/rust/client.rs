@@ -0,0 +1,435 @@
+//! Rust HTTP/JSON client generation.
+//!
+//! Every HTTP/JSON implementation becomes a client struct, named after its
+//! service, with one method per implemented service method. Clients send
+//! their requests using a `Transport`, which is a trait declared by the
+//! generated module itself, making it possible to use any HTTP stack. Input
+//! and output payloads are encoded and decoded using the `serde_json` crate.
+//!
+//! Service methods with errors mapped to HTTP status codes get an error enum
+//! each, named after their service and method, with one variant per error.
+//! Error responses are decoded into such enums whenever their transports
+//! expose the status codes and bodies of those responses.
+
+use arspec::spec::{Implement, Route, RouteSegment, Specification};
+use crate::extend_reachable;
+use std::fmt::Write;
+use super::{identifier, input_name, snake_case, type_name, type_of, upper_camel_case, write_definitions, write_doc};
+
+/// Generates Rust module declaring a client struct per implementation in
+/// `implementations`, as well as every record and enum used by those
+/// implementations.
+pub fn generate<'a>(spec: &Specification<'a>, implementations: &[&Implement<'a>]) -> String {
+    let services = implementations.iter()
+        .filter_map(|implementation| Some((*implementation, spec.resolve_service(&implementation.name)?)))
+        .collect::<Vec<_>>();
+    let roots = services.iter()
+        .flat_map(|(_, service)| service.methods.iter())
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()));
+
+    let mut output = String::from(concat!(
+        "// Generated by `arspec gen rust-client`.\n",
+        "\n",
+        "#![allow(unused_imports)]\n",
+        "\n",
+        "use serde::{Deserialize, Serialize};\n",
+        "use std::collections::{BTreeMap, BTreeSet};\n",
+        "use std::fmt;\n",
+        "\n",
+        "/// An HTTP transport used by clients to send requests.\n",
+        "pub trait Transport {\n",
+        "    /// Error produced if a request cannot be sent or does not succeed.\n",
+        "    type Error;\n",
+        "\n",
+        "    /// Sends request with given `method`, such as `POST`, to `path`, which is\n",
+        "    /// relative to the root of the targeted host, with JSON `body`, if any,\n",
+        "    /// and returns the body of its response.\n",
+        "    ///\n",
+        "    /// Responses without 2xx status codes must be reported as errors.\n",
+        "    fn send(&self, method: &str, path: &str, body: Option<&[u8]>) -> Result<Vec<u8>, Self::Error>;\n",
+        "\n",
+        "    /// Returns the status code and body of the response reported by\n",
+        "    /// `error`, if any, making it possible to decode service errors.\n",
+        "    fn response_of<'e>(&self, _error: &'e Self::Error) -> Option<(u16, &'e [u8])> {\n",
+        "        None\n",
+        "    }\n",
+        "}\n",
+        "\n",
+        "/// An error produced by a client.\n",
+        "#[derive(Debug)]\n",
+        "pub enum ClientError<E, S = std::convert::Infallible> {\n",
+        "    /// Request could not be sent or did not succeed.\n",
+        "    Transport(E),\n",
+        "\n",
+        "    /// Request or response body could not be encoded or decoded.\n",
+        "    Json(serde_json::Error),\n",
+        "\n",
+        "    /// Service responded with one of the errors of the invoked method.\n",
+        "    Service(S),\n",
+        "}\n",
+        "\n",
+        "impl<E: fmt::Display, S: fmt::Display> fmt::Display for ClientError<E, S> {\n",
+        "    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n",
+        "        match self {\n",
+        "            ClientError::Transport(error) => error.fmt(f),\n",
+        "            ClientError::Json(error) => error.fmt(f),\n",
+        "            ClientError::Service(error) => error.fmt(f),\n",
+        "        }\n",
+        "    }\n",
+        "}\n",
+        "\n",
+        "impl<E: fmt::Debug + fmt::Display, S: fmt::Debug + fmt::Display> std::error::Error for ClientError<E, S> {}\n",
+    ));
+
+    let mut has_parameters = false;
+    let mut has_errors = false;
+    for (implementation, service) in &services {
+        let name = type_name(spec, &service.name);
+        output.push('\n');
+        write_doc(&service.attributes, 0, &mut output);
+        let _ = write!(output, concat!(
+            "pub struct {name}Client<T> {{\n",
+            "    transport: T,\n",
+            "}}\n",
+            "\n",
+            "impl<T: Transport> {name}Client<T> {{\n",
+            "    /// Creates client sending requests using `transport`.\n",
+            "    pub fn new(transport: T) -> Self {{\n",
+            "        {name}Client {{ transport }}\n",
+            "    }}\n",
+        ), name = name);
+
+        let mut error_enums = String::new();
+        for route in implementation.routes(service) {
+            let service_method = route.service_method;
+            let mut parameters = Vec::new();
+            let mut path = String::new();
+            for segment in route.segments() {
+                path.push('/');
+                match segment {
+                    RouteSegment::Literal(literal) => path.push_str(&literal
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('{', "{{")
+                        .replace('}', "}}")),
+                    RouteSegment::Parameter(name) => {
+                        parameters.push(unique(identifier(&snake_case(name)), &parameters));
+                        path.push_str("{}");
+                    }
+                }
+            }
+            has_parameters |= !parameters.is_empty();
+
+            let mut signature = parameters.iter()
+                .map(|parameter| format!(", {}: &str", parameter))
+                .collect::<String>();
+            let body = service_method.input.as_ref().map(|input| {
+                let parameter = unique(input_name(spec, service_method).unwrap(), &parameters);
+                let type_ = match type_of(spec, input) {
+                    ref type_ if type_ == "String" => "str".into(),
+                    type_ => type_,
+                };
+                let _ = write!(signature, ", {}: &{}", parameter, type_);
+                parameter
+            });
+            let result = service_method.output.as_ref().map_or("()".into(), |output| type_of(spec, output));
+            let error = if route.errors.is_empty() {
+                None
+            } else {
+                let error = format!("{}{}Error", name, upper_camel_case(service_method.name.as_str()));
+                write_error_enum(spec, &error, service.name.as_str(), &route, &mut error_enums);
+                Some(error)
+            };
+            has_errors |= error.is_some();
+
+            output.push('\n');
+            write_doc(&service_method.attributes, 1, &mut output);
+            let _ = writeln!(
+                output,
+                "    pub fn {}(&self{}) -> Result<{}, ClientError<T::Error{}>> {{",
+                identifier(&snake_case(service_method.name.as_str())),
+                signature,
+                result,
+                error.as_ref().map(|error| format!(", {}", error)).unwrap_or_default(),
+            );
+            let path = if parameters.is_empty() {
+                format!("\"{}\"", path)
+            } else {
+                let arguments = parameters.iter()
+                    .map(|parameter| format!(", encode_segment({})", parameter))
+                    .collect::<String>();
+                let _ = writeln!(output, "        let path = format!(\"{}\"{});", path, arguments);
+                "&path".into()
+            };
+            let body = match body {
+                Some(body) => {
+                    let _ = writeln!(output, "        let body = serde_json::to_vec({}).map_err(ClientError::Json)?;", body);
+                    "Some(&body)"
+                }
+                None => "None",
+            };
+            let send = format!("self.transport.send(\"{}\", {}, {})", route.method, path, body);
+            let map_err = match error {
+                Some(error) => format!("|error| service_error(&self.transport, error, {}::decode)", error),
+                None => "ClientError::Transport".into(),
+            };
+            if service_method.output.is_some() {
+                let _ = write!(output, concat!(
+                    "        let response = {}\n",
+                    "            .map_err({})?;\n",
+                    "        serde_json::from_slice(&response).map_err(ClientError::Json)\n",
+                ), send, map_err);
+            } else {
+                let _ = write!(output, concat!(
+                    "        {}\n",
+                    "            .map_err({})?;\n",
+                    "        Ok(())\n",
+                ), send, map_err);
+            }
+            output.push_str("    }\n");
+        }
+        output.push_str("}\n");
+        output.push_str(&error_enums);
+    }
+
+    if has_errors {
+        output.push_str(concat!(
+            "\n",
+            "/// Turns transport `error` into a service error using `decode`, if it\n",
+            "/// reports a response with a status code known to `decode`.\n",
+            "fn service_error<T: Transport, S>(\n",
+            "    transport: &T,\n",
+            "    error: T::Error,\n",
+            "    decode: fn(u16, &[u8]) -> Option<serde_json::Result<S>>,\n",
+            ") -> ClientError<T::Error, S> {\n",
+            "    let decoded = transport.response_of(&error)\n",
+            "        .and_then(|(status, body)| decode(status, body));\n",
+            "    match decoded {\n",
+            "        Some(Ok(error)) => ClientError::Service(error),\n",
+            "        Some(Err(error)) => ClientError::Json(error),\n",
+            "        None => ClientError::Transport(error),\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+
+    if has_parameters {
+        output.push_str(concat!(
+            "\n",
+            "/// Percent-encodes `segment` for use as an URL path segment.\n",
+            "fn encode_segment(segment: &str) -> String {\n",
+            "    let mut output = String::with_capacity(segment.len());\n",
+            "    for byte in segment.bytes() {\n",
+            "        match byte {\n",
+            "            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(byte as char),\n",
+            "            _ => output.push_str(&format!(\"%{:02X}\", byte)),\n",
+            "        }\n",
+            "    }\n",
+            "    output\n",
+            "}\n",
+        ));
+    }
+
+    let definitions = extend_reachable(spec, &[], &[], roots);
+    write_definitions(spec, &definitions, &mut output);
+    output
+}
+
+/// Writes enum named `name` with one variant per error of `route`, which
+/// belongs to the service named `service`.
+fn write_error_enum(spec: &Specification, name: &str, service: &str, route: &Route, output: &mut String) {
+    let method = route.service_method.name.as_str();
+    let variants = route.errors.iter()
+        .map(|(error, status)| (type_name(spec, &error.name), type_of(spec, error), status, error.name.as_str()))
+        .collect::<Vec<_>>();
+
+    let _ = write!(output, concat!(
+        "\n",
+        "/// An error of the `{method}` method of the `{service}` service.\n",
+        "#[derive(Clone, Debug, PartialEq)]\n",
+        "pub enum {name} {{\n",
+    ), method = method, service = service, name = name);
+    for (variant, type_, _, _) in &variants {
+        let _ = writeln!(output, "    {}({}),", variant, type_);
+    }
+    let _ = write!(output, concat!(
+        "}}\n",
+        "\n",
+        "impl {name} {{\n",
+        "    fn decode(status: u16, body: &[u8]) -> Option<serde_json::Result<Self>> {{\n",
+        "        match status {{\n",
+    ), name = name);
+    for (variant, _, status, _) in &variants {
+        let _ = writeln!(output, "            {} => Some(serde_json::from_slice(body).map({}::{})),", status, name, variant);
+    }
+    let _ = write!(output, concat!(
+        "            _ => None,\n",
+        "        }}\n",
+        "    }}\n",
+        "}}\n",
+        "\n",
+        "impl fmt::Display for {name} {{\n",
+        "    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n",
+        "        match self {{\n",
+    ), name = name);
+    for (variant, _, _, error) in &variants {
+        let _ = writeln!(output, "            {}::{}(_) => f.write_str(\"{}\"),", name, variant, error);
+    }
+    output.push_str(concat!(
+        "        }\n",
+        "    }\n",
+        "}\n",
+    ));
+}
+
+/// Appends a numeric suffix to `name` if it is among `taken`.
+fn unique(name: String, taken: &[String]) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_client() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "service ServiceDiscovery {\n",
+                    "    @Doc(\"\n        Registers service.\n    \")\n",
+                    "    method Register(ServiceRegistryEntry): ServiceRegistryEntry;\n",
+                    "    method Unregister(String) throws NotFound;\n",
+                    "}\n",
+                    "implement ServiceDiscovery using HTTP/JSON {\n",
+                    "    property BasePath: \"/serviceregistry\";\n",
+                    "    method Register { Method: \"post\", Path: \"/register\" }\n",
+                    "    method Unregister { Method: \"DELETE\", Path: \"/unregister/{ID}\", Errors: { NotFound: 404 } }\n",
+                    "}\n",
+                    "record ServiceRegistryEntry { ServiceURI: String, TTL: Option<U32>, Kind: Kind, Tags: Set<String> }\n",
+                    "enum Kind { Sensor, default }\n",
+                    "record NotFound { Message: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let implementations = spec.implementations.iter().collect::<Vec<_>>();
+
+        let output = generate(&spec, &implementations);
+        let start = output.find("\npub struct").unwrap();
+        assert_eq!(&output[start..], concat!(
+            "\n",
+            "pub struct ServiceDiscoveryClient<T> {\n",
+            "    transport: T,\n",
+            "}\n",
+            "\n",
+            "impl<T: Transport> ServiceDiscoveryClient<T> {\n",
+            "    /// Creates client sending requests using `transport`.\n",
+            "    pub fn new(transport: T) -> Self {\n",
+            "        ServiceDiscoveryClient { transport }\n",
+            "    }\n",
+            "\n",
+            "    /// Registers service.\n",
+            "    pub fn register(&self, service_registry_entry: &ServiceRegistryEntry) -> Result<ServiceRegistryEntry, ClientError<T::Error>> {\n",
+            "        let body = serde_json::to_vec(service_registry_entry).map_err(ClientError::Json)?;\n",
+            "        let response = self.transport.send(\"POST\", \"/serviceregistry/register\", Some(&body))\n",
+            "            .map_err(ClientError::Transport)?;\n",
+            "        serde_json::from_slice(&response).map_err(ClientError::Json)\n",
+            "    }\n",
+            "\n",
+            "    pub fn unregister(&self, id: &str, input: &str) -> Result<(), ClientError<T::Error, ServiceDiscoveryUnregisterError>> {\n",
+            "        let path = format!(\"/serviceregistry/unregister/{}\", encode_segment(id));\n",
+            "        let body = serde_json::to_vec(input).map_err(ClientError::Json)?;\n",
+            "        self.transport.send(\"DELETE\", &path, Some(&body))\n",
+            "            .map_err(|error| service_error(&self.transport, error, ServiceDiscoveryUnregisterError::decode))?;\n",
+            "        Ok(())\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "/// An error of the `Unregister` method of the `ServiceDiscovery` service.\n",
+            "#[derive(Clone, Debug, PartialEq)]\n",
+            "pub enum ServiceDiscoveryUnregisterError {\n",
+            "    NotFound(NotFound),\n",
+            "}\n",
+            "\n",
+            "impl ServiceDiscoveryUnregisterError {\n",
+            "    fn decode(status: u16, body: &[u8]) -> Option<serde_json::Result<Self>> {\n",
+            "        match status {\n",
+            "            404 => Some(serde_json::from_slice(body).map(ServiceDiscoveryUnregisterError::NotFound)),\n",
+            "            _ => None,\n",
+            "        }\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "impl fmt::Display for ServiceDiscoveryUnregisterError {\n",
+            "    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n",
+            "        match self {\n",
+            "            ServiceDiscoveryUnregisterError::NotFound(_) => f.write_str(\"NotFound\"),\n",
+            "        }\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "/// Turns transport `error` into a service error using `decode`, if it\n",
+            "/// reports a response with a status code known to `decode`.\n",
+            "fn service_error<T: Transport, S>(\n",
+            "    transport: &T,\n",
+            "    error: T::Error,\n",
+            "    decode: fn(u16, &[u8]) -> Option<serde_json::Result<S>>,\n",
+            ") -> ClientError<T::Error, S> {\n",
+            "    let decoded = transport.response_of(&error)\n",
+            "        .and_then(|(status, body)| decode(status, body));\n",
+            "    match decoded {\n",
+            "        Some(Ok(error)) => ClientError::Service(error),\n",
+            "        Some(Err(error)) => ClientError::Json(error),\n",
+            "        None => ClientError::Transport(error),\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "/// Percent-encodes `segment` for use as an URL path segment.\n",
+            "fn encode_segment(segment: &str) -> String {\n",
+            "    let mut output = String::with_capacity(segment.len());\n",
+            "    for byte in segment.bytes() {\n",
+            "        match byte {\n",
+            "            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(byte as char),\n",
+            "            _ => output.push_str(&format!(\"%{:02X}\", byte)),\n",
+            "        }\n",
+            "    }\n",
+            "    output\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct ServiceRegistryEntry {\n",
+            "    #[serde(rename = \"ServiceURI\")]\n",
+            "    pub service_uri: String,\n",
+            "    #[serde(rename = \"TTL\", default, skip_serializing_if = \"Option::is_none\")]\n",
+            "    pub ttl: Option<u32>,\n",
+            "    #[serde(rename = \"Kind\")]\n",
+            "    pub kind: Kind,\n",
+            "    #[serde(rename = \"Tags\")]\n",
+            "    pub tags: BTreeSet<String>,\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]\n",
+            "pub enum Kind {\n",
+            "    Sensor,\n",
+            "    #[serde(rename = \"default\")]\n",
+            "    Default,\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct NotFound {\n",
+            "    #[serde(rename = \"Message\")]\n",
+            "    pub message: String,\n",
+            "}\n",
+        ));
+    }
+}
@@ -0,0 +1,345 @@
+//! Rust item generation.
+//!
+//! Every record and enum of a specification becomes a Rust type, while
+//! every service becomes a trait with one method per service method. Each
+//! trait declares an associated `Error` type, returned by all its methods
+//! if they fail, leaving it to implementors to decide how errors are
+//! represented.
+
+use arspec::spec::Specification;
+use arspec_parser::Span;
+use crate::extend_reachable;
+use std::fmt::Write;
+use super::{identifier, input_name, snake_case, type_name, type_of, write_definitions, write_doc};
+
+/// Generates Rust items declaring every record, enum and service of `spec`.
+///
+/// The items may be included into any module, as they refer to no other
+/// items than those of the `serde` and `serde_json` crates by name.
+pub fn generate(spec: &Specification) -> String {
+    generate_from(spec, |_| true)
+}
+
+/// Generates Rust items declaring the records, enums and services of `spec`
+/// with names satisfying `is_root`, as well as any types they refer to.
+pub fn generate_from<F>(spec: &Specification, is_root: F) -> String
+    where F: Fn(&Span) -> bool,
+{
+    let mut output = String::from(concat!(
+        "#[allow(unused_imports)]\n",
+        "use serde::{Deserialize, Serialize};\n",
+        "#[allow(unused_imports)]\n",
+        "use std::collections::{BTreeMap, BTreeSet};\n",
+    ));
+
+    let enums = spec.enums.iter()
+        .filter(|enum_| is_root(&enum_.name))
+        .collect::<Vec<_>>();
+    let records = spec.records.iter()
+        .filter(|record| is_root(&record.name))
+        .collect::<Vec<_>>();
+    let services = spec.services.iter()
+        .filter(|service| is_root(&service.name))
+        .collect::<Vec<_>>();
+    let definitions = extend_reachable(spec, &enums, &records, services.iter()
+        .flat_map(|service| service.methods.iter())
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter())));
+    write_definitions(spec, &definitions, &mut output);
+
+    for service in services {
+        output.push('\n');
+        write_doc(&service.attributes, 0, &mut output);
+        let _ = write!(output, concat!(
+            "pub trait {} {{\n",
+            "    /// Error produced if a method fails.\n",
+            "    type Error;\n",
+        ), type_name(spec, &service.name));
+        for method in &service.methods {
+            output.push('\n');
+            write_doc(&method.attributes, 1, &mut output);
+            let input = method.input.as_ref()
+                .map(|input| format!(", {}: {}", input_name(spec, method).unwrap(), type_of(spec, input)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                output,
+                "    fn {}(&self{}) -> Result<{}, Self::Error>;",
+                identifier(&snake_case(method.name.as_str())),
+                input,
+                method.output.as_ref().map_or("()".into(), |output| type_of(spec, output)),
+            );
+        }
+        output.push_str("}\n");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use std::{env, fs};
+    use std::process::Command;
+    use super::*;
+
+    /// Type checks `items` as a library crate, panicking with the compiler
+    /// output if they do not compile.
+    ///
+    /// The `serde` derive macros are replaced by macros expanding to
+    /// nothing, as no `serde` crate is available to tests.
+    fn compile(name: &str, items: &str) {
+        let folder = env::temp_dir().join(format!("arspec-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("serde.rs"), concat!(
+            "extern crate proc_macro;\n",
+            "use proc_macro::TokenStream;\n",
+            "#[proc_macro_derive(Deserialize, attributes(serde))]\n",
+            "pub fn deserialize(_: TokenStream) -> TokenStream { TokenStream::new() }\n",
+            "#[proc_macro_derive(Serialize, attributes(serde))]\n",
+            "pub fn serialize(_: TokenStream) -> TokenStream { TokenStream::new() }\n",
+        )).unwrap();
+        fs::write(folder.join("items.rs"), items).unwrap();
+
+        let rustc = |args: &[&str]| {
+            let output = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".into()))
+                .args(["--edition", "2018", "--out-dir"])
+                .arg(&folder)
+                .args(args)
+                .current_dir(&folder)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        };
+        rustc(&["--crate-type", "proc-macro", "--crate-name", "serde", "serde.rs"]);
+        rustc(&["--crate-type", "lib", "--emit", "metadata", "--extern", "serde", "-L", folder.to_str().unwrap(), "items.rs"]);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn generate_items() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "@Doc(\"\n    Orchestrates services.\n\")\n",
+                    "service Orchestrator {\n",
+                    "    method Orchestrate(OrchestrationForm): List<Match>;\n",
+                    "    method Echo();\n",
+                    "}\n",
+                    "record OrchestrationForm { RequestedService: String, Flags: Map<String, Boolean> }\n",
+                    "record Match { Provider: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        assert_eq!(generate(&spec), concat!(
+            "#[allow(unused_imports)]\n",
+            "use serde::{Deserialize, Serialize};\n",
+            "#[allow(unused_imports)]\n",
+            "use std::collections::{BTreeMap, BTreeSet};\n",
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct OrchestrationForm {\n",
+            "    #[serde(rename = \"RequestedService\")]\n",
+            "    pub requested_service: String,\n",
+            "    #[serde(rename = \"Flags\")]\n",
+            "    pub flags: BTreeMap<String, bool>,\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct Match {\n",
+            "    #[serde(rename = \"Provider\")]\n",
+            "    pub provider: String,\n",
+            "}\n",
+            "\n",
+            "/// Orchestrates services.\n",
+            "pub trait Orchestrator {\n",
+            "    /// Error produced if a method fails.\n",
+            "    type Error;\n",
+            "\n",
+            "    fn orchestrate(&self, orchestration_form: OrchestrationForm) -> Result<Vec<Match>, Self::Error>;\n",
+            "\n",
+            "    fn echo(&self) -> Result<(), Self::Error>;\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn generate_recursive_items() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "service S { method Get(): Entry; }\n",
+                    "record Entry { Sub: Option<Entry>, Children: List<Entry>, Link?: Link }\n",
+                    "record Link { Target: Entry, Pair: Pair<Link> }\n",
+                    "record Pair<T> { First: Option<T> }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        let output = generate(&spec);
+        for field in &[
+            "pub sub: Option<Box<Entry>>,",
+            "pub children: Vec<Entry>,",
+            "pub link: Option<Box<Link>>,",
+            "pub target: Box<Entry>,",
+            "pub pair: Box<Pair<Link>>,",
+            "pub first: Option<T>,",
+        ] {
+            assert!(output.contains(field), "`{}` not in:\n{}", field, output);
+        }
+        compile("recursive-items", &output);
+    }
+
+    #[test]
+    fn generate_generic_items() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "service S { method List(): Page<Pair<String, U16>>; }\n",
+                    "record Page<Item> { Items: List<Item> }\n",
+                    "record Pair<A, B> { First: A, Second: Option<B> }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        let output = generate(&spec);
+        let start = output.find("\n#[derive").unwrap();
+        assert_eq!(&output[start..], concat!(
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct Page<Item> {\n",
+            "    #[serde(rename = \"Items\")]\n",
+            "    pub items: Vec<Item>,\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct Pair<A, B> {\n",
+            "    #[serde(rename = \"First\")]\n",
+            "    pub first: A,\n",
+            "    #[serde(rename = \"Second\", default, skip_serializing_if = \"Option::is_none\")]\n",
+            "    pub second: Option<B>,\n",
+            "}\n",
+            "\n",
+            "pub trait S {\n",
+            "    /// Error produced if a method fails.\n",
+            "    type Error;\n",
+            "\n",
+            "    fn list(&self) -> Result<Page<Pair<String, u16>>, Self::Error>;\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn generate_entry_defaults() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Entry {\n",
+                    "    Name?: String,\n",
+                    "    Port: U16 = 8080,\n",
+                    "    Ratio?: Float32 = 1,\n",
+                    "    Tags: Set<Tag> = [\"Secure\"],\n",
+                    "    Meta: Map<String, Entry> = {},\n",
+                    "}\n",
+                    "enum Tag { Secure }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        let output = generate(&spec);
+        let start = output.find("\n#[derive(Clone, Debug, PartialEq").unwrap();
+        assert_eq!(&output[start..], concat!(
+            "\n",
+            "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+            "pub struct Entry {\n",
+            "    #[serde(rename = \"Name\", default, skip_serializing_if = \"Option::is_none\")]\n",
+            "    pub name: Option<String>,\n",
+            "    #[serde(rename = \"Port\", default = \"Entry::default_port\")]\n",
+            "    pub port: u16,\n",
+            "    #[serde(rename = \"Ratio\", default = \"Entry::default_ratio\")]\n",
+            "    pub ratio: Option<f32>,\n",
+            "    #[serde(rename = \"Tags\", default = \"Entry::default_tags\")]\n",
+            "    pub tags: BTreeSet<Tag>,\n",
+            "    #[serde(rename = \"Meta\", default = \"Entry::default_meta\")]\n",
+            "    pub meta: BTreeMap<String, Entry>,\n",
+            "}\n",
+            "\n",
+            "impl Entry {\n",
+            "    fn default_port() -> u16 {\n",
+            "        8080\n",
+            "    }\n",
+            "\n",
+            "    fn default_ratio() -> Option<f32> {\n",
+            "        Some(1.0)\n",
+            "    }\n",
+            "\n",
+            "    fn default_tags() -> BTreeSet<Tag> {\n",
+            "        vec![Tag::Secure].into_iter().collect()\n",
+            "    }\n",
+            "\n",
+            "    fn default_meta() -> BTreeMap<String, Entry> {\n",
+            "        vec![].into_iter().collect()\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn generate_enum_values() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "enum Origin { LOCAL, FROM_OTHER_CLOUD = \"from-other-cloud\" }\n",
+                    "enum Reason { TTL_EXPIRED = 2 }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        let output = generate(&spec);
+        let start = output.find("\n#[derive(Clone, Copy").unwrap();
+        assert_eq!(&output[start..], concat!(
+            "\n",
+            "#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]\n",
+            "pub enum Origin {\n",
+            "    #[serde(rename = \"LOCAL\")]\n",
+            "    Local,\n",
+            "    #[serde(rename = \"from-other-cloud\")]\n",
+            "    FromOtherCloud,\n",
+            "}\n",
+            "\n",
+            "#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]\n",
+            "#[serde(into = \"i64\", try_from = \"i64\")]\n",
+            "pub enum Reason {\n",
+            "    TtlExpired,\n",
+            "}\n",
+            "\n",
+            "impl From<Reason> for i64 {\n",
+            "    fn from(value: Reason) -> Self {\n",
+            "        match value {\n",
+            "            Reason::TtlExpired => 2,\n",
+            "        }\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "impl std::convert::TryFrom<i64> for Reason {\n",
+            "    type Error = String;\n",
+            "\n",
+            "    fn try_from(value: i64) -> Result<Self, Self::Error> {\n",
+            "        match value {\n",
+            "            2 => Ok(Reason::TtlExpired),\n",
+            "            _ => Err(format!(\"{} is not a variant value of enum `Reason`\", value)),\n",
+            "        }\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+}
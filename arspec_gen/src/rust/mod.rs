@@ -0,0 +1,532 @@
+//! Rust source code generation.
+//!
+//! Generated modules declare one `struct` per record and one `enum` per enum
+//! reachable from the services they concern, deriving the `Serialize` and
+//! `Deserialize` traits of the `serde` crate, which must be available to the
+//! crate the modules are included into. Record entries and enum variants are
+//! renamed to match Rust naming conventions, while their serialized names
+//! remain those of the specification. Enums with integer variant values are
+//! serialized as integers via conversions to and from `i64`. Aliases become
+//! `type` aliases, which do not enforce any constraints of the aliases.
+//!
+//! `Set<T>` is mapped to `BTreeSet<T>` if `T` is an enum, string or integer,
+//! and to `Vec<T>` otherwise, as records and floating-point numbers are not
+//! totally ordered. Primitives declared using `primitive` are mapped to
+//! `serde_json::Value`.
+//!
+//! Optional record entries become `Option<T>` fields, while entries with
+//! default values get private associated functions producing those values
+//! when deserializing payloads lacking them. Entries of types containing
+//! their own records, other than via lists, sets or maps, are boxed, as in
+//! `Option<Box<Entry>>`, to give the records a finite size.
+
+pub mod client;
+pub mod items;
+pub mod server;
+
+use arspec::payload::{json, Data};
+use arspec::spec::{Attribute, Enum, Record, ServiceMethod, Specification, TypeRef};
+use arspec_parser::Span;
+use crate::{Definition, doc_of};
+use crate::types::{self, TypeNames};
+use std::fmt::Write;
+use std::ptr;
+
+/// Words that cannot be used as Rust identifiers without the `r#` prefix.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "try",
+    "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+    "macro", "override", "priv", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Determines name of the parameter or variable holding the input of
+/// `service_method`, which is named after its type if a record or enum.
+fn input_name<'a>(spec: &Specification<'a>, service_method: &ServiceMethod<'a>) -> Option<String> {
+    let input = service_method.input.as_ref()?;
+    let is_defined = spec.resolve_record(&input.name).is_some()
+        || spec.resolve_enum(&input.name).is_some()
+        || spec.resolve_alias(&input.name).is_some();
+    Some(if is_defined {
+        identifier(&snake_case(input.name.as_str()))
+    } else {
+        "input".into()
+    })
+}
+
+/// Writes Rust declarations of all `definitions`.
+fn write_definitions<'a>(spec: &Specification<'a>, definitions: &[Definition<'a, '_>], output: &mut String) {
+    for definition in definitions {
+        match *definition {
+            Definition::Alias(alias) => {
+                output.push('\n');
+                write_doc(&alias.attributes, 0, output);
+                let _ = writeln!(output, "pub type {} = {};", type_name(spec, &alias.name), type_of(spec, &alias.definition));
+            }
+            Definition::Enum(enum_) => write_enum(spec, enum_, output),
+            Definition::Record(record) => write_record(spec, record, output),
+            Definition::Primitive(name) => {
+                let primitive = match spec.resolve_primitive(name) {
+                    Some(primitive) if builtin(name.as_str()).is_none() => primitive,
+                    _ => continue,
+                };
+                output.push('\n');
+                write_doc(&primitive.attributes, 0, output);
+                let _ = writeln!(output, "pub type {} = serde_json::Value;", type_name(spec, name));
+            }
+        }
+    }
+}
+
+fn write_enum<'a>(spec: &Specification<'a>, enum_: &Enum<'a>, output: &mut String) {
+    let name = type_name(spec, &enum_.name);
+    output.push('\n');
+    write_doc(&enum_.attributes, 0, output);
+    output.push_str("#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]\n");
+    if enum_.is_integer() {
+        output.push_str("#[serde(into = \"i64\", try_from = \"i64\")]\n");
+    }
+    let _ = writeln!(output, "pub enum {} {{", name);
+    for variant in &enum_.variants {
+        write_doc(&variant.attributes, 1, output);
+        let identifier = identifier(&upper_camel_case(variant.name.as_str()));
+        if let Data::String(value) = variant.wire_value() {
+            if identifier != value {
+                let _ = writeln!(output, "    #[serde(rename = \"{}\")]", value.replace('\\', "\\\\").replace('"', "\\\""));
+            }
+        }
+        let _ = writeln!(output, "    {},", identifier);
+    }
+    output.push_str("}\n");
+
+    if !enum_.is_integer() {
+        return;
+    }
+    let variants = enum_.variants.iter()
+        .filter_map(|variant| match variant.wire_value() {
+            Data::Integer(value) => Some((identifier(&upper_camel_case(variant.name.as_str())), value)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let _ = write!(output, concat!(
+        "\n",
+        "impl From<{name}> for i64 {{\n",
+        "    fn from(value: {name}) -> Self {{\n",
+        "        match value {{\n",
+    ), name = name);
+    for (identifier, value) in &variants {
+        let _ = writeln!(output, "            {}::{} => {},", name, identifier, value);
+    }
+    let _ = write!(output, concat!(
+        "        }}\n",
+        "    }}\n",
+        "}}\n",
+        "\n",
+        "impl std::convert::TryFrom<i64> for {name} {{\n",
+        "    type Error = String;\n",
+        "\n",
+        "    fn try_from(value: i64) -> Result<Self, Self::Error> {{\n",
+        "        match value {{\n",
+    ), name = name);
+    for (identifier, value) in &variants {
+        let _ = writeln!(output, "            {} => Ok({}::{}),", value, name, identifier);
+    }
+    let _ = write!(output, concat!(
+        "            _ => Err(format!(\"{{}} is not a variant value of enum `{spec_name}`\", value)),\n",
+        "        }}\n",
+        "    }}\n",
+        "}}\n",
+    ), spec_name = enum_.name.as_str());
+}
+
+fn write_record<'a>(spec: &Specification<'a>, record: &Record<'a>, output: &mut String) {
+    let name = type_name(spec, &record.name);
+    let generic_parameters = generic_parameters_of(record);
+    output.push('\n');
+    write_doc(&record.attributes, 0, output);
+    let _ = writeln!(output, concat!(
+        "#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]\n",
+        "pub struct {}{} {{",
+    ), name, generic_parameters);
+    let mut defaults = Vec::new();
+    for entry in &record.entries {
+        write_doc(&entry.attributes, 1, output);
+        let field = entry.name.as_str();
+        let identifier = identifier(&snake_case(field));
+        let mut type_ = generic_type_of(spec, entry.value_type(), &record.generic_parameters);
+        if contains_record(spec, entry.value_type(), record, &mut Vec::new()) {
+            type_ = format!("Box<{}>", type_);
+        }
+        let mut options = Vec::new();
+        if identifier != field {
+            options.push(format!("rename = \"{}\"", field));
+        }
+        if let Some(ref default) = entry.default {
+            let function = format!("default_{}", identifier.trim_start_matches("r#"));
+            let path = if generic_parameters.is_empty() {
+                name.clone()
+            } else {
+                format!("{}::{}", name, generic_parameters)
+            };
+            options.push(format!("default = \"{}::{}\"", path, function));
+            let data = Data::from(default);
+            let value = match (entry.may_be_null(), data) {
+                (true, Data::Null) => "None".into(),
+                (true, data) => format!("Some({})", value_of(spec, entry.value_type(), &data)),
+                (false, data) => value_of(spec, entry.value_type(), &data),
+            };
+            if entry.may_be_null() {
+                type_ = format!("Option<{}>", type_);
+            }
+            defaults.push((function, type_.clone(), value));
+        } else if entry.may_be_null() {
+            type_ = format!("Option<{}>", type_);
+            options.push("default, skip_serializing_if = \"Option::is_none\"".into());
+        }
+        if !options.is_empty() {
+            let _ = writeln!(output, "    #[serde({})]", options.join(", "));
+        }
+        let _ = writeln!(output, "    pub {}: {},", identifier, type_);
+    }
+    output.push_str("}\n");
+
+    if defaults.is_empty() {
+        return;
+    }
+    let _ = writeln!(output, "\nimpl{} {}{} {{", generic_parameters, name, generic_parameters);
+    for (index, (function, type_, value)) in defaults.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        let _ = writeln!(output, "    fn {}() -> {} {{\n        {}\n    }}", function, type_, value);
+    }
+    output.push_str("}\n");
+}
+
+/// Formats the generic parameters of `record`, if any, as a Rust generic
+/// parameter list.
+fn generic_parameters_of(record: &Record) -> String {
+    if record.generic_parameters.is_empty() {
+        return String::new();
+    }
+    let parameters = record.generic_parameters.iter()
+        .map(|parameter| upper_camel_case(parameter.as_str()))
+        .collect::<Vec<_>>();
+    format!("<{}>", parameters.join(", "))
+}
+
+/// Whether or not values of the type referred to by `type_ref` contain
+/// values of `record` directly, rather than via the heap allocations of
+/// lists, sets or maps, making them recursive if contained by `record`.
+///
+/// Records already in `visited` are assumed not to contain `record`.
+fn contains_record<'a: 'b, 'b>(
+    spec: &'b Specification<'a>,
+    type_ref: &'b TypeRef<'a>,
+    record: &Record<'a>,
+    visited: &mut Vec<&'b Record<'a>>,
+) -> bool {
+    if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+        return contains_record(spec, &alias.definition, record, visited);
+    }
+    if let Some(other) = spec.resolve_record(&type_ref.name) {
+        if ptr::eq(other, record) {
+            return true;
+        }
+        if visited.iter().any(|visited| ptr::eq(*visited, other)) {
+            return false;
+        }
+        visited.push(other);
+        if other.entries.iter().any(|entry| contains_record(spec, &entry.type_ref, record, visited)) {
+            return true;
+        }
+    }
+    match type_ref.name.as_str() {
+        "List" | "Set" | "Map" => false,
+        _ => type_ref.params.iter().any(|param| contains_record(spec, param, record, visited)),
+    }
+}
+
+/// Determines Rust type of the type referred to by `type_ref`.
+fn type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> String {
+    generic_type_of(spec, type_ref, &[])
+}
+
+/// Determines Rust type of the type referred to by `type_ref`, which may
+/// refer to any of `generic_parameters`.
+fn generic_type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, generic_parameters: &[Span<'a>]) -> String {
+    types::generic_type_of(&mut RustNames, spec, type_ref, generic_parameters)
+}
+
+/// Names Rust types.
+struct RustNames;
+
+impl<'a> TypeNames<'a> for RustNames {
+    fn definition(&mut self, spec: &Specification<'a>, name: &Span<'a>) -> String {
+        type_name(spec, name)
+    }
+
+    fn parameter(&mut self, name: &str) -> String {
+        upper_camel_case(name)
+    }
+
+    fn list(&mut self, item: String) -> String {
+        format!("Vec<{}>", item)
+    }
+
+    fn set(&mut self, spec: &Specification<'a>, item_ref: &TypeRef<'a>, item: String) -> String {
+        let (target, _) = spec.unalias(item_ref);
+        let is_ordered = spec.resolve_enum(&target.name).is_some() || match builtin(target.name.as_str()) {
+            Some(builtin) => !builtin.starts_with('f') && builtin != "bool",
+            None => false,
+        };
+        if is_ordered {
+            format!("BTreeSet<{}>", item)
+        } else {
+            format!("Vec<{}>", item)
+        }
+    }
+
+    fn map(&mut self, key: String, value: String) -> String {
+        format!("BTreeMap<{}, {}>", key, value)
+    }
+
+    fn option(&mut self, value: String) -> String {
+        format!("Option<{}>", value)
+    }
+
+    fn primitive(&mut self, spec: &Specification<'a>, type_ref: &TypeRef<'a>, _is_nested: bool) -> String {
+        match (builtin(type_ref.name.as_str()), spec.resolve_primitive(&type_ref.name)) {
+            (Some(builtin), _) => builtin.into(),
+            (None, Some(primitive)) => type_name(spec, &primitive.definition.name),
+            (None, None) => "serde_json::Value".into(),
+        }
+    }
+}
+
+/// Formats Rust expression evaluating to `data`, which must be a valid value
+/// of the type referred to by `type_ref`.
+///
+/// Values that cannot be expressed as literals, such as those of records,
+/// are decoded from JSON when evaluated.
+fn value_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, data: &Data) -> String {
+    return literal_of(spec, type_ref, data)
+        .unwrap_or_else(|| format!("serde_json::from_str({:?}).unwrap()", json::encode(data)));
+
+    fn literal_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, data: &Data) -> Option<String> {
+        if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+            return enum_.variant_of(data).map(|variant| format!(
+                "{}::{}",
+                type_name(spec, &enum_.name),
+                identifier(&upper_camel_case(variant.name.as_str())),
+            ));
+        }
+        if spec.resolve_record(&type_ref.name).is_some() {
+            return None;
+        }
+        let (target, aliases) = spec.unalias(type_ref);
+        if !aliases.is_empty() {
+            return literal_of(spec, target, data);
+        }
+        let items_of = |item: &TypeRef<'a>, items: &[Data]| items.iter()
+            .map(|data| literal_of(spec, item, data))
+            .collect::<Option<Vec<_>>>()
+            .map(|items| items.join(", "));
+        let params = &type_ref.params;
+        Some(match (type_ref.name.as_str(), params.len(), data) {
+            ("List", 1, Data::List(items)) => format!("vec![{}]", items_of(&params[0], items)?),
+            ("Set", 1, Data::List(items)) => format!("vec![{}].into_iter().collect()", items_of(&params[0], items)?),
+            ("Map", 2, Data::Map(entries)) => {
+                let entries = entries.iter()
+                    .map(|(key, value)| Some(format!(
+                        "({}, {})",
+                        literal_of(spec, &params[0], &Data::String(key.clone()))?,
+                        literal_of(spec, &params[1], value)?,
+                    )))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("vec![{}].into_iter().collect()", entries.join(", "))
+            }
+            ("Option", 1, Data::Null) => "None".into(),
+            ("Option", 1, data) => format!("Some({})", literal_of(spec, &params[0], data)?),
+            (name, 0, data) => match (builtin(name)?, data) {
+                ("bool", Data::Boolean(boolean)) => boolean.to_string(),
+                ("String", Data::String(string)) => format!("{:?}.into()", string),
+                ("f32", Data::Integer(integer)) | ("f64", Data::Integer(integer)) => format!("{:?}", *integer as f64),
+                (float @ ("f32" | "f64"), Data::Float(number)) => match *number {
+                    number if number.is_finite() => format!("{:?}", number),
+                    number if number.is_nan() => format!("{}::NAN", float),
+                    number if number > 0.0 => format!("{}::INFINITY", float),
+                    _ => format!("{}::NEG_INFINITY", float),
+                },
+                (integer, Data::Integer(value)) if integer.starts_with(['i', 'u']) => value.to_string(),
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Determines Rust type of named built-in primitive, if it is one.
+fn builtin(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Boolean" => "bool",
+        "Float32" => "f32",
+        "Float64" => "f64",
+        "I8" => "i8",
+        "I16" => "i16",
+        "I32" => "i32",
+        "I64" => "i64",
+        "U8" => "u8",
+        "U16" => "u16",
+        "U32" => "u32",
+        "U64" => "u64",
+        "String" => "String",
+        _ => return None,
+    })
+}
+
+/// Determines Rust name of the type defined with `name`, which is prefixed
+/// by its package name unless it belongs to the root package.
+fn type_name(spec: &Specification, name: &Span) -> String {
+    spec.qualify(name)
+        .split("::")
+        .map(upper_camel_case)
+        .collect()
+}
+
+/// Turns `name` into upper camel case, such as `ServiceDiscovery` for
+/// `service_discovery`, `FromOtherCloud` for `FROM_OTHER_CLOUD` or
+/// `ServiceUri` for `ServiceURI`.
+fn upper_camel_case(name: &str) -> String {
+    words(name)
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars.next().into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars.flat_map(char::to_lowercase))
+        })
+        .collect()
+}
+
+/// Turns `name` into snake case, such as `service_uri` for `ServiceURI`,
+/// `ttl` for `TTL` or `requested_qos` for `RequestedQoS`.
+fn snake_case(name: &str) -> String {
+    words(name)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Splits `name` into words, such as `Service` and `URI` for `ServiceURI`.
+///
+/// Words are separated by non-alphanumeric characters and by capital
+/// letters following lowercase letters or digits. Runs of capitals are
+/// words of their own, apart from any last capital followed by a lowercase
+/// letter. Short mixed-case acronyms, such as `QoS` or `IoT`, are kept as
+/// single words.
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let mut words: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+    for (index, &(offset, ch)) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                words.push((start, offset));
+            }
+            continue;
+        }
+        let start = match start {
+            Some(ref mut start) => start,
+            None => {
+                start = Some(offset);
+                continue;
+            }
+        };
+        let previous = chars[index - 1].1;
+        let is_next_lowercase = chars.get(index + 1).is_some_and(|(_, ch)| ch.is_lowercase());
+        let is_word_start = ch.is_uppercase() && (previous.is_lowercase() || previous.is_numeric()
+            || (previous.is_uppercase() && is_next_lowercase));
+        if is_word_start {
+            words.push((*start, offset));
+            *start = offset;
+        }
+    }
+    if let Some(start) = start {
+        words.push((start, name.len()));
+    }
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(words.len());
+    for (start, end) in words {
+        if let Some(last) = merged.last_mut() {
+            let previous = &name[last.0..last.1];
+            let word = &name[start..end];
+            let is_acronym = last.1 == start
+                && previous.chars().count() <= 2
+                && previous.ends_with(char::is_lowercase)
+                && word.chars().count() == 1
+                && !name[end..].starts_with(char::is_lowercase);
+            if is_acronym {
+                last.1 = end;
+                continue;
+            }
+        }
+        merged.push((start, end));
+    }
+    merged.into_iter().map(move |(start, end)| &name[start..end])
+}
+
+/// Prefixes `name` with `r#` if it is a Rust keyword, or with `_` if it
+/// begins with a digit.
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else if name.is_empty() || name.starts_with(|ch: char| ch.is_numeric()) {
+        format!("_{}", name)
+    } else {
+        name.into()
+    }
+}
+
+/// Writes documentation comment containing the `@Doc` of `attributes`, if
+/// any, at given `level` of indentation.
+fn write_doc(attributes: &[Attribute], level: usize, output: &mut String) {
+    let doc = match doc_of(attributes) {
+        Some(doc) => doc,
+        None => return,
+    };
+    let indent = "    ".repeat(level);
+    for line in doc.lines() {
+        if line.is_empty() {
+            let _ = writeln!(output, "{}///", indent);
+        } else {
+            let _ = writeln!(output, "{}/// {}", indent, line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        for (name, upper_camel, snake) in &[
+            ("service_discovery", "ServiceDiscovery", "service_discovery"),
+            ("FROM_OTHER_CLOUD", "FromOtherCloud", "from_other_cloud"),
+            ("FIRST", "First", "first"),
+            ("ServiceURI", "ServiceUri", "service_uri"),
+            ("URIScheme", "UriScheme", "uri_scheme"),
+            ("TTL", "Ttl", "ttl"),
+            ("RequestedQoS", "RequestedQos", "requested_qos"),
+            ("IoTDevice", "IotDevice", "iot_device"),
+            ("PositionX", "PositionX", "position_x"),
+            ("ipv4Address", "Ipv4Address", "ipv4_address"),
+            ("celsius", "Celsius", "celsius"),
+            ("serviceId", "ServiceId", "service_id"),
+        ] {
+            assert_eq!(upper_camel_case(name), *upper_camel, "{}", name);
+            assert_eq!(snake_case(name), *snake, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Rust server skeleton generation.
+//!
+//! Every system becomes a server struct with one handler per service it
+//! produces. Each handler implements a trait, named after its service, with
+//! one method per service method. The server routes requests to those
+//! methods as described by the first HTTP implementation of each service
+//! using either the `JSON` or `CBOR` encoding, which are decoded and encoded
+//! using the `serde_json` and `ciborium` crates, respectively. Produced
+//! services without such implementations are left out.
+
+use arspec::spec::{Implement, RouteSegment, Service, Specification, System};
+use crate::extend_reachable;
+use std::fmt::Write;
+use std::ptr;
+use super::{identifier, input_name, snake_case, type_name, type_of, write_definitions, write_doc};
+
+/// Finds the HTTP implementation of `service` used by generated servers, if
+/// any.
+pub fn implementation_of<'a: 'b, 'b>(spec: &'b Specification<'a>, service: &Service<'a>) -> Option<&'b Implement<'a>> {
+    spec.implementations.iter()
+        .filter(|implementation| implementation.protocol.as_str() == "HTTP")
+        .filter(|implementation| matches!(implementation.encoding.as_str(), "CBOR" | "JSON"))
+        .find(|implementation| spec.resolve_service(&implementation.name)
+            .is_some_and(|implemented| ptr::eq(implemented, service)))
+}
+
+/// Determines name of the file of the module generated for `system`, such
+/// as `service_registry.rs` for `ServiceRegistry`.
+pub fn file_name(spec: &Specification, system: &System) -> String {
+    format!("{}.rs", snake_case(&type_name(spec, &system.name)))
+}
+
+/// Generates Rust module declaring a server skeleton for `system`, as well
+/// as every record and enum used by the services it produces.
+pub fn generate<'a>(spec: &Specification<'a>, system: &System<'a>) -> String {
+    let services = system.produces.iter()
+        .filter_map(|service_ref| spec.resolve_service(&service_ref.name))
+        .filter_map(|service| Some((implementation_of(spec, service)?, service)))
+        .collect::<Vec<_>>();
+    let roots = services.iter()
+        .flat_map(|(_, service)| service.methods.iter())
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()));
+
+    let mut output = String::from(concat!(
+        "// Generated by `arspec gen rust-server`.\n",
+        "\n",
+        "#![allow(unused_imports)]\n",
+        "\n",
+        "use serde::{Deserialize, Serialize};\n",
+        "use std::collections::{BTreeMap, BTreeSet};\n",
+        "\n",
+        "/// An error produced by a handler, reported as an HTTP response.\n",
+        "#[derive(Debug)]\n",
+        "pub struct Error {\n",
+        "    /// HTTP status code, such as `404`.\n",
+        "    pub status: u16,\n",
+        "\n",
+        "    /// Description of error.\n",
+        "    pub message: String,\n",
+        "}\n",
+        "\n",
+        "impl Error {\n",
+        "    /// Creates error with given HTTP `status` and `message`.\n",
+        "    pub fn new(status: u16, message: impl Into<String>) -> Self {\n",
+        "        Error { status, message: message.into() }\n",
+        "    }\n",
+        "}\n",
+        "\n",
+        "/// An HTTP response produced by a server.\n",
+        "#[derive(Debug)]\n",
+        "pub struct Response {\n",
+        "    /// HTTP status code, such as `200`.\n",
+        "    pub status: u16,\n",
+        "\n",
+        "    /// Media type of `body`, unless empty.\n",
+        "    pub content_type: Option<&'static str>,\n",
+        "\n",
+        "    /// Response body.\n",
+        "    pub body: Vec<u8>,\n",
+        "}\n",
+        "\n",
+        "impl Response {\n",
+        "    fn error(status: u16, message: &str) -> Self {\n",
+        "        Response { status, content_type: Some(\"text/plain\"), body: message.into() }\n",
+        "    }\n",
+        "}\n",
+    ));
+
+    let mut traits = String::new();
+    let mut routing = String::new();
+    let mut helpers = Helpers::default();
+    for (implementation, service) in &services {
+        let encoding = implementation.encoding.as_str();
+        let field = identifier(&snake_case(service.name.as_str()));
+        traits.push('\n');
+        write_doc(&service.attributes, 0, &mut traits);
+        let _ = writeln!(traits, "pub trait {} {{", type_name(spec, &service.name));
+
+        for (route_index, route) in implementation.routes(service).iter().enumerate() {
+            let service_method = route.service_method;
+            let mut parameters = Vec::new();
+            let mut pattern = Vec::new();
+            for segment in route.segments() {
+                match segment {
+                    RouteSegment::Literal(literal) => {
+                        pattern.push(format!("\"{}\"", literal.replace('\\', "\\\\").replace('"', "\\\"")));
+                    }
+                    RouteSegment::Parameter(name) => {
+                        let parameter = unique(identifier(&snake_case(name)), &parameters);
+                        pattern.push(parameter.clone());
+                        parameters.push(parameter);
+                    }
+                }
+            }
+            let mut signature = parameters.iter()
+                .map(|parameter| format!(", {}: &str", parameter))
+                .collect::<String>();
+            let mut arguments = parameters.iter()
+                .map(|parameter| format!("&decode_segment({})", parameter))
+                .collect::<Vec<_>>();
+            helpers.decode_segment |= !parameters.is_empty();
+            let input = service_method.input.as_ref().map(|input| {
+                let parameter = unique(input_name(spec, service_method).unwrap(), &parameters);
+                let _ = write!(signature, ", {}: {}", parameter, type_of(spec, input));
+                arguments.push(parameter.clone());
+                parameter
+            });
+            let result = service_method.output.as_ref().map_or("()".into(), |output| type_of(spec, output));
+            let name = identifier(&snake_case(service_method.name.as_str()));
+
+            if route_index > 0 {
+                traits.push('\n');
+            }
+            write_doc(&service_method.attributes, 1, &mut traits);
+            let _ = writeln!(traits, "    fn {}(&self{}) -> Result<{}, Error>;", name, signature, result);
+
+            let _ = writeln!(routing, "            (\"{}\", [{}]) => {{", route.method, pattern.join(", "));
+            if let Some(input) = input {
+                let decode = match encoding {
+                    "CBOR" => "ciborium::from_reader(body).map_err(|error| error.to_string())",
+                    _ => "serde_json::from_slice(body).map_err(|error| error.to_string())",
+                };
+                let _ = write!(routing, concat!(
+                    "                let {} = match {} {{\n",
+                    "                    Ok(input) => input,\n",
+                    "                    Err(error) => return Response::error(400, &error),\n",
+                    "                }};\n",
+                ), input, decode);
+            }
+            let respond = if service_method.output.is_none() {
+                helpers.respond_empty = true;
+                "respond_empty"
+            } else if encoding == "CBOR" {
+                helpers.respond_cbor = true;
+                "respond_cbor"
+            } else {
+                helpers.respond_json = true;
+                "respond_json"
+            };
+            let _ = write!(routing, concat!(
+                "                {}(self.{}.{}({}))\n",
+                "            }}\n",
+            ), respond, field, name, arguments.join(", "));
+        }
+        traits.push_str("}\n");
+    }
+    output.push_str(&traits);
+
+    let generics = (0..services.len())
+        .map(|index| format!("H{}", index))
+        .collect::<Vec<_>>();
+    let bounds = services.iter().enumerate()
+        .map(|(index, (_, service))| format!("H{}: {}", index, type_name(spec, &service.name)))
+        .collect::<Vec<_>>();
+    let fields = services.iter()
+        .map(|(_, service)| identifier(&snake_case(service.name.as_str())))
+        .collect::<Vec<_>>();
+    let name = format!("{}Server", type_name(spec, &system.name));
+    let generics = if generics.is_empty() { String::new() } else { format!("<{}>", generics.join(", ")) };
+    let bounds = if bounds.is_empty() { String::new() } else { format!("<{}>", bounds.join(", ")) };
+
+    output.push('\n');
+    write_doc(&system.attributes, 0, &mut output);
+    let _ = writeln!(output, "pub struct {}{} {{", name, generics);
+    for (index, field) in fields.iter().enumerate() {
+        let _ = writeln!(output, "    pub {}: H{},", field, index);
+    }
+    let _ = write!(output, concat!(
+        "}}\n",
+        "\n",
+        "impl{bounds} {name}{generics} {{\n",
+        "    /// Creates server dispatching requests to given handlers.\n",
+        "    pub fn new({parameters}) -> Self {{\n",
+        "        {name} {{ {fields} }}\n",
+        "    }}\n",
+        "\n",
+        "    /// Handles request with given `method`, such as `POST`, `path` and\n",
+        "    /// `body`, producing a response.\n",
+        "    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {{\n",
+        "        let path = path.split('?').next().unwrap_or_default();\n",
+        "        let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();\n",
+        "        match (method, segments.as_slice()) {{\n",
+        "{routing}",
+        "            _ => Response::error(404, \"no such route\"),\n",
+        "        }}\n",
+        "    }}\n",
+        "}}\n",
+    ),
+        bounds = bounds,
+        name = name,
+        generics = generics,
+        parameters = fields.iter().enumerate()
+            .map(|(index, field)| format!("{}: H{}", field, index))
+            .collect::<Vec<_>>()
+            .join(", "),
+        fields = fields.join(", "),
+        routing = routing,
+    );
+
+    if helpers.respond_cbor {
+        output.push_str(concat!(
+            "\n",
+            "fn respond_cbor<T: Serialize>(result: Result<T, Error>) -> Response {\n",
+            "    let mut body = Vec::new();\n",
+            "    match result.and_then(|output| ciborium::into_writer(&output, &mut body)\n",
+            "        .map_err(|error| Error::new(500, error.to_string())))\n",
+            "    {\n",
+            "        Ok(()) => Response { status: 200, content_type: Some(\"application/cbor\"), body },\n",
+            "        Err(error) => Response::error(error.status, &error.message),\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+    if helpers.respond_empty {
+        output.push_str(concat!(
+            "\n",
+            "fn respond_empty(result: Result<(), Error>) -> Response {\n",
+            "    match result {\n",
+            "        Ok(()) => Response { status: 204, content_type: None, body: Vec::new() },\n",
+            "        Err(error) => Response::error(error.status, &error.message),\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+    if helpers.respond_json {
+        output.push_str(concat!(
+            "\n",
+            "fn respond_json<T: Serialize>(result: Result<T, Error>) -> Response {\n",
+            "    match result.and_then(|output| serde_json::to_vec(&output)\n",
+            "        .map_err(|error| Error::new(500, error.to_string())))\n",
+            "    {\n",
+            "        Ok(body) => Response { status: 200, content_type: Some(\"application/json\"), body },\n",
+            "        Err(error) => Response::error(error.status, &error.message),\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+    if helpers.decode_segment {
+        output.push_str(concat!(
+            "\n",
+            "/// Decodes percent-encoded URL path `segment`.\n",
+            "fn decode_segment(segment: &str) -> String {\n",
+            "    let bytes = segment.as_bytes();\n",
+            "    let mut output = Vec::with_capacity(bytes.len());\n",
+            "    let mut index = 0;\n",
+            "    while index < bytes.len() {\n",
+            "        let hex = bytes.get(index + 1..index + 3)\n",
+            "            .and_then(|hex| std::str::from_utf8(hex).ok())\n",
+            "            .and_then(|hex| u8::from_str_radix(hex, 16).ok());\n",
+            "        match (bytes[index], hex) {\n",
+            "            (b'%', Some(byte)) => {\n",
+            "                output.push(byte);\n",
+            "                index += 3;\n",
+            "            }\n",
+            "            (byte, _) => {\n",
+            "                output.push(byte);\n",
+            "                index += 1;\n",
+            "            }\n",
+            "        }\n",
+            "    }\n",
+            "    String::from_utf8_lossy(&output).into_owned()\n",
+            "}\n",
+        ));
+    }
+
+    let definitions = extend_reachable(spec, &[], &[], roots);
+    write_definitions(spec, &definitions, &mut output);
+    output
+}
+
+/// Helper functions required by the routes of a generated server.
+#[derive(Default)]
+struct Helpers {
+    decode_segment: bool,
+    respond_cbor: bool,
+    respond_empty: bool,
+    respond_json: bool,
+}
+
+/// Appends a numeric suffix to `name` if it is among `taken`.
+fn unique(name: String, taken: &[String]) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_server() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "system Registry { produces ServiceDiscovery; produces Echo; }\n",
+                    "service ServiceDiscovery {\n",
+                    "    @Doc(\"\n        Registers service.\n    \")\n",
+                    "    method Register(Entry): Entry;\n",
+                    "    method Unregister();\n",
+                    "}\n",
+                    "implement ServiceDiscovery using HTTP/JSON {\n",
+                    "    property BasePath: \"/serviceregistry\";\n",
+                    "    method Register { Method: \"POST\", Path: \"/register\" }\n",
+                    "    method Unregister { Method: \"DELETE\", Path: \"/unregister/{ID}\" }\n",
+                    "}\n",
+                    "service Echo { method Echo(String): String; }\n",
+                    "implement Echo using MQTT/JSON { method Echo { Topic: \"echo\" } }\n",
+                    "record Entry { Name: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+
+        let output = generate(&spec, &spec.systems[0]);
+        let start = output.find("\npub trait").unwrap();
+        let end = output.find("\nfn respond_empty").unwrap();
+        assert_eq!(&output[start..end], concat!(
+            "\n",
+            "pub trait ServiceDiscovery {\n",
+            "    /// Registers service.\n",
+            "    fn register(&self, entry: Entry) -> Result<Entry, Error>;\n",
+            "\n",
+            "    fn unregister(&self, id: &str) -> Result<(), Error>;\n",
+            "}\n",
+            "\n",
+            "pub struct RegistryServer<H0> {\n",
+            "    pub service_discovery: H0,\n",
+            "}\n",
+            "\n",
+            "impl<H0: ServiceDiscovery> RegistryServer<H0> {\n",
+            "    /// Creates server dispatching requests to given handlers.\n",
+            "    pub fn new(service_discovery: H0) -> Self {\n",
+            "        RegistryServer { service_discovery }\n",
+            "    }\n",
+            "\n",
+            "    /// Handles request with given `method`, such as `POST`, `path` and\n",
+            "    /// `body`, producing a response.\n",
+            "    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {\n",
+            "        let path = path.split('?').next().unwrap_or_default();\n",
+            "        let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();\n",
+            "        match (method, segments.as_slice()) {\n",
+            "            (\"POST\", [\"serviceregistry\", \"register\"]) => {\n",
+            "                let entry = match serde_json::from_slice(body).map_err(|error| error.to_string()) {\n",
+            "                    Ok(input) => input,\n",
+            "                    Err(error) => return Response::error(400, &error),\n",
+            "                };\n",
+            "                respond_json(self.service_discovery.register(entry))\n",
+            "            }\n",
+            "            (\"DELETE\", [\"serviceregistry\", \"unregister\", id]) => {\n",
+            "                respond_empty(self.service_discovery.unregister(&decode_segment(id)))\n",
+            "            }\n",
+            "            _ => Response::error(404, \"no such route\"),\n",
+            "        }\n",
+            "    }\n",
+            "}\n",
+        ));
+        assert!(output.contains("\npub struct Entry {\n"));
+    }
+}
@@ -0,0 +1,115 @@
+use arspec::spec::{Specification, TypeRef};
+use arspec_parser::Span;
+
+/// Names the types of some target language.
+///
+/// Used by [`generic_type_of`] to determine what target language types
+/// specification types correspond to.
+pub trait TypeNames<'a> {
+    /// Whether or not aliases are replaced by the types they are aliases of,
+    /// rather than being named by [`definition`][TypeNames::definition].
+    const REPLACES_ALIASES: bool = false;
+
+    /// Names record, enum or alias defined with `name`.
+    fn definition(&mut self, spec: &Specification<'a>, name: &Span<'a>) -> String;
+
+    /// Names instance of generic record named `record`, given the names of
+    /// its type `params`.
+    fn instance(&mut self, record: String, params: Vec<String>) -> String {
+        format!("{}<{}>", record, params.join(", "))
+    }
+
+    /// Names generic parameter `name` of the record being generated.
+    fn parameter(&mut self, name: &str) -> String {
+        name.into()
+    }
+
+    /// Names list of `item`s.
+    fn list(&mut self, item: String) -> String;
+
+    /// Names set of `item`s, which are of the type referred to by
+    /// `item_ref`.
+    fn set(&mut self, _spec: &Specification<'a>, _item_ref: &TypeRef<'a>, item: String) -> String {
+        self.list(item)
+    }
+
+    /// Names map of `key`s to `value`s.
+    fn map(&mut self, key: String, value: String) -> String;
+
+    /// Names optional `value`.
+    fn option(&mut self, value: String) -> String;
+
+    /// Names primitive, or other unknown type, referred to by `type_ref`,
+    /// which `is_nested` if it is a type parameter of some other type.
+    fn primitive(&mut self, spec: &Specification<'a>, type_ref: &TypeRef<'a>, is_nested: bool) -> String;
+}
+
+/// Determines target language type of the type referred to by `type_ref`,
+/// which may refer to any of `generic_parameters`, using `names`.
+pub fn generic_type_of<'a, N>(
+    names: &mut N,
+    spec: &Specification<'a>,
+    type_ref: &TypeRef<'a>,
+    generic_parameters: &[Span<'a>],
+) -> String
+    where N: TypeNames<'a>,
+{
+    return type_of(names, spec, type_ref, generic_parameters, false);
+
+    fn type_of<'a, N>(
+        names: &mut N,
+        spec: &Specification<'a>,
+        type_ref: &TypeRef<'a>,
+        generic_parameters: &[Span<'a>],
+        is_nested: bool,
+    ) -> String
+        where N: TypeNames<'a>,
+    {
+        let param = |names: &mut N, index: usize| {
+            type_of(names, spec, &type_ref.params[index], generic_parameters, true)
+        };
+        let name = type_ref.name.as_str();
+        if type_ref.params.is_empty() && generic_parameters.iter().any(|parameter| parameter.as_str() == name) {
+            return names.parameter(name);
+        }
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            let record_name = names.definition(spec, &record.name);
+            if type_ref.params.is_empty() {
+                return record_name;
+            }
+            let params = (0..type_ref.params.len())
+                .map(|index| param(names, index))
+                .collect();
+            return names.instance(record_name, params);
+        }
+        if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+            return names.definition(spec, &enum_.name);
+        }
+        if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+            if N::REPLACES_ALIASES {
+                return type_of(names, spec, &alias.definition, generic_parameters, is_nested);
+            }
+            return names.definition(spec, &alias.name);
+        }
+        match (name, type_ref.params.len()) {
+            ("List", 1) => {
+                let item = param(names, 0);
+                names.list(item)
+            }
+            ("Set", 1) => {
+                let item = param(names, 0);
+                names.set(spec, &type_ref.params[0], item)
+            }
+            ("Map", 2) => {
+                let key = param(names, 0);
+                let value = param(names, 1);
+                names.map(key, value)
+            }
+            ("Option", 1) => {
+                let value = param(names, 0);
+                names.option(value)
+            }
+            _ => names.primitive(spec, type_ref, is_nested),
+        }
+    }
+}
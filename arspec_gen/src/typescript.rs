@@ -0,0 +1,534 @@
+//! TypeScript generation.
+//!
+//! Every record becomes an `interface` with one property per record entry,
+//! named exactly as the entry, and every enum becomes a union of string
+//! literal types. Aliases become type aliases, without any of their
+//! constraints. Records extending other records become interfaces
+//! extending the interfaces of those records. Record entries that may be
+//! absent become optional properties, with any default values documented
+//! using `@default` tags. Every `HTTP/JSON`
+//! implementation also becomes a client class, named after its service,
+//! that sends requests using `fetch`. Error responses with status codes
+//! mapped to method errors are thrown as `ServiceError`s, carrying the names
+//! and decoded payloads of those errors.
+//!
+//! All numeric primitives are mapped to `number`, which means that `I64` and
+//! `U64` values outside the range `±(2^53 - 1)` cannot be represented
+//! exactly.
+
+use arspec::payload::json;
+use arspec::spec::{Attribute, Enum, Implement, Record, RecordEntry, RouteSegment, Specification, TypeRef};
+use arspec_parser::Span;
+use crate::{Definition, doc_of, extend_reachable};
+use crate::types::{self, TypeNames};
+use std::fmt::Write;
+
+/// Words that cannot be used as TypeScript parameter names.
+const KEYWORDS: &[&str] = &[
+    "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default",
+    "delete", "do", "else", "enum", "export", "extends", "false", "finally", "for", "function",
+    "if", "implements", "import", "in", "instanceof", "interface", "let", "new", "null",
+    "package", "private", "protected", "public", "return", "static", "super", "switch", "this",
+    "throw", "true", "try", "typeof", "var", "void", "while", "with", "yield",
+];
+
+/// Generates TypeScript module declaring `records`, `enums` and a client
+/// class per implementation in `implementations`.
+///
+/// Any records, enums and primitives used by, but not among, the given
+/// definitions are declared after them.
+pub fn generate<'a>(
+    spec: &Specification<'a>,
+    records: &[&Record<'a>],
+    enums: &[&Enum<'a>],
+    implementations: &[&Implement<'a>],
+) -> String {
+    let services = implementations.iter()
+        .filter_map(|implementation| spec.resolve_service(&implementation.name))
+        .collect::<Vec<_>>();
+    let roots = services.iter()
+        .flat_map(|service| service.methods.iter())
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()));
+    let definitions = extend_reachable(spec, enums, records, roots).into_iter()
+        .filter(|definition| match *definition {
+            Definition::Primitive(name) => builtin(name.as_str()).is_none() && spec.resolve_primitive(name).is_some(),
+            _ => true,
+        });
+
+    let mut output = String::new();
+    for definition in definitions {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        match definition {
+            Definition::Alias(alias) => {
+                write_doc(&alias.attributes, 0, &mut output);
+                let _ = writeln!(output, "export type {} = {};", type_name(spec, &alias.name), type_of(spec, &alias.definition));
+            }
+            Definition::Enum(enum_) => write_enum(spec, enum_, &mut output),
+            Definition::Record(record) => write_record(spec, record, &mut output),
+            Definition::Primitive(name) => {
+                write_doc(&spec.resolve_primitive(name).unwrap().attributes, 0, &mut output);
+                let _ = writeln!(output, "export type {} = unknown;", type_name(spec, name));
+            }
+        }
+    }
+    let has_errors = implementations.iter()
+        .flat_map(|implementation| implementation.methods.iter())
+        .any(|method| method.data.iter().any(|(name, _)| name.as_str() == "Errors"));
+    if has_errors {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(concat!(
+            "/**\n",
+            " * An error response of a service method, named after the method error it\n",
+            " * represents.\n",
+            " */\n",
+            "export class ServiceError extends Error {\n",
+            "    constructor(readonly error: string, readonly status: number, readonly payload: unknown) {\n",
+            "        super(`service responded with ${error} (status ${status})`);\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+    for implementation in implementations {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        write_client(spec, implementation, &mut output);
+    }
+    output
+}
+
+fn write_enum<'a>(spec: &Specification<'a>, enum_: &Enum<'a>, output: &mut String) {
+    write_doc(&enum_.attributes, 0, output);
+    let variants = enum_.variants.iter()
+        .map(|variant| json::encode(&variant.wire_value()))
+        .collect::<Vec<_>>();
+    let _ = writeln!(output, "export type {} = {};", type_name(spec, &enum_.name), if variants.is_empty() {
+        "never".into()
+    } else {
+        variants.join(" | ")
+    });
+}
+
+fn write_record<'a>(spec: &Specification<'a>, record: &Record<'a>, output: &mut String) {
+    write_doc(&record.attributes, 0, output);
+    let generic_parameters = &record.generic_parameters;
+    let name = if generic_parameters.is_empty() {
+        type_name(spec, &record.name)
+    } else {
+        let parameters = generic_parameters.iter()
+            .map(|parameter| parameter.as_str())
+            .collect::<Vec<_>>();
+        format!("{}<{}>", type_name(spec, &record.name), parameters.join(", "))
+    };
+    let base = record.base.as_ref()
+        .filter(|base| spec.resolve_record(&base.name).is_some());
+    match base {
+        Some(base) => {
+            let _ = writeln!(
+                output,
+                "export interface {} extends {} {{",
+                name,
+                generic_type_of(spec, base, generic_parameters),
+            );
+        }
+        None => {
+            let _ = writeln!(output, "export interface {} {{", name);
+        }
+    }
+    let entries = record.entries.iter()
+        .filter(|entry| base.is_none() || entry.inherited_from.is_none());
+    for entry in entries {
+        write_entry_doc(entry, 1, output);
+        if entry.may_be_absent() {
+            let type_ = generic_type_of(spec, entry.value_type(), generic_parameters);
+            let _ = writeln!(output, "    {}?: {};", entry.name.as_str(), type_);
+        } else {
+            let type_ = generic_type_of(spec, &entry.type_ref, generic_parameters);
+            let _ = writeln!(output, "    {}: {};", entry.name.as_str(), type_);
+        }
+    }
+    output.push_str("}\n");
+}
+
+/// Writes client class sending requests to the HTTP/JSON service
+/// `implementation`.
+///
+/// Methods lacking `Method` or `Path` data are skipped. Every path segment
+/// enclosed in braces, such as `{id}`, becomes a method parameter preceding
+/// the method input, if any.
+fn write_client<'a>(spec: &Specification<'a>, implementation: &Implement<'a>, output: &mut String) {
+    let service = match spec.resolve_service(&implementation.name) {
+        Some(service) => service,
+        None => return,
+    };
+    write_doc(&service.attributes, 0, output);
+    let _ = write!(output, concat!(
+        "export class {}Client {{\n",
+        "    constructor(private readonly baseUrl: string, private readonly init: RequestInit = {{}}) {{}}\n",
+    ), type_name(spec, &service.name));
+
+    let mut has_errors = false;
+    for route in implementation.routes(service) {
+        let service_method = route.service_method;
+        let mut parameters = Vec::new();
+        let mut url = String::new();
+        for segment in route.segments() {
+            url.push('/');
+            match segment {
+                RouteSegment::Parameter(name) => {
+                    let parameter = parameter_name(name, &parameters);
+                    let _ = write!(url, "${{encodeURIComponent({})}}", parameter);
+                    parameters.push(parameter);
+                }
+                RouteSegment::Literal(literal) => url.push_str(&literal.replace('\\', "\\\\").replace('`', "\\`").replace('$', "\\$")),
+            }
+        }
+        let mut signature = parameters.iter()
+            .map(|parameter| format!("{}: string", parameter))
+            .collect::<Vec<_>>();
+        let body = service_method.input.as_ref().map(|input| {
+            let is_defined = spec.resolve_record(&input.name).is_some()
+                || spec.resolve_enum(&input.name).is_some()
+                || spec.resolve_alias(&input.name).is_some();
+            let name = if is_defined { lower_camel_case(input.name.as_str()) } else { "input".into() };
+            let parameter = parameter_name(&name, &parameters);
+            signature.push(format!("{}: {}", parameter, type_of(spec, input)));
+            parameter
+        });
+
+        output.push('\n');
+        write_doc(&service_method.attributes, 1, output);
+        let _ = writeln!(
+            output,
+            "    async {}({}): Promise<{}> {{",
+            lower_camel_case(service_method.name.as_str()),
+            signature.join(", "),
+            service_method.output.as_ref().map_or("void".into(), |output| type_of(spec, output)),
+        );
+        let errors = route.errors.iter()
+            .map(|(error, status)| format!("{}: \"{}\"", status, error.name.as_str()))
+            .collect::<Vec<_>>();
+        has_errors |= !errors.is_empty();
+        let arguments = if errors.is_empty() {
+            body.map(|body| format!(", {}", body)).unwrap_or_default()
+        } else {
+            format!(", {}, {{ {} }}", body.as_deref().unwrap_or("undefined"), errors.join(", "))
+        };
+        let send = format!(
+            "this.send(\"{}\", `{}`{})",
+            route.method,
+            url,
+            arguments,
+        );
+        match service_method.output {
+            Some(ref type_ref) => {
+                let _ = writeln!(output, "        const response = await {};", send);
+                let _ = writeln!(output, "        return await response.json() as {};", type_of(spec, type_ref));
+            }
+            None => {
+                let _ = writeln!(output, "        await {};", send);
+            }
+        }
+        output.push_str("    }\n");
+    }
+
+    output.push_str(if has_errors {
+        concat!(
+            "\n",
+            "    private async send(\n",
+            "        method: string,\n",
+            "        path: string,\n",
+            "        body?: unknown,\n",
+            "        errors: { [status: number]: string } = {},\n",
+            "    ): Promise<Response> {\n",
+        )
+    } else {
+        concat!(
+            "\n",
+            "    private async send(method: string, path: string, body?: unknown): Promise<Response> {\n",
+        )
+    });
+    output.push_str(concat!(
+        "        const headers = new Headers(this.init.headers);\n",
+        "        if (body !== undefined) {\n",
+        "            headers.set(\"Content-Type\", \"application/json\");\n",
+        "        }\n",
+        "        const response = await fetch(this.baseUrl + path, {\n",
+        "            ...this.init,\n",
+        "            method,\n",
+        "            headers,\n",
+        "            body: body === undefined ? undefined : JSON.stringify(body),\n",
+        "        });\n",
+        "        if (!response.ok) {\n",
+    ));
+    if has_errors {
+        output.push_str(concat!(
+            "            const error = errors[response.status];\n",
+            "            if (error !== undefined) {\n",
+            "                throw new ServiceError(error, response.status, await response.json());\n",
+            "            }\n",
+        ));
+    }
+    output.push_str(concat!(
+        "            throw new Error(`${method} ${path} failed with status ${response.status}`);\n",
+        "        }\n",
+        "        return response;\n",
+        "    }\n",
+        "}\n",
+    ));
+}
+
+/// Determines TypeScript type of the type referred to by `type_ref`.
+fn type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>) -> String {
+    generic_type_of(spec, type_ref, &[])
+}
+
+/// Determines TypeScript type of the type referred to by `type_ref`, which
+/// may refer to any of `generic_parameters`.
+fn generic_type_of<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, generic_parameters: &[Span<'a>]) -> String {
+    types::generic_type_of(&mut TypeScriptNames, spec, type_ref, generic_parameters)
+}
+
+/// Names TypeScript types.
+struct TypeScriptNames;
+
+impl<'a> TypeNames<'a> for TypeScriptNames {
+    fn definition(&mut self, spec: &Specification<'a>, name: &Span<'a>) -> String {
+        type_name(spec, name)
+    }
+
+    fn list(&mut self, item: String) -> String {
+        if item.contains(' ') {
+            format!("({})[]", item)
+        } else {
+            format!("{}[]", item)
+        }
+    }
+
+    fn map(&mut self, _key: String, value: String) -> String {
+        format!("{{ [key: string]: {} }}", value)
+    }
+
+    fn option(&mut self, value: String) -> String {
+        format!("{} | null", value)
+    }
+
+    fn primitive(&mut self, spec: &Specification<'a>, type_ref: &TypeRef<'a>, _is_nested: bool) -> String {
+        match (builtin(type_ref.name.as_str()), spec.resolve_primitive(&type_ref.name)) {
+            (Some(builtin), _) => builtin.into(),
+            (None, Some(primitive)) => type_name(spec, &primitive.definition.name),
+            (None, None) => "unknown".into(),
+        }
+    }
+}
+
+/// Determines TypeScript type of named built-in primitive, if it is one.
+fn builtin(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Boolean" => "boolean",
+        "Float32" | "Float64" | "I8" | "I16" | "I32" | "I64" | "U8" | "U16" | "U32" | "U64" => "number",
+        "String" => "string",
+        _ => return None,
+    })
+}
+
+/// Determines TypeScript name of the type defined with `name`, which is
+/// prefixed by its package name unless it belongs to the root package.
+fn type_name(spec: &Specification, name: &Span) -> String {
+    spec.qualify(name)
+        .replace("::", "_")
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() || ch == '_' { ch } else { '_' })
+        .collect()
+}
+
+/// Turns `name` into a parameter name not among `taken`, such as
+/// `serviceURI` for `ServiceURI` or `id2` for `id` if `id` is taken.
+fn parameter_name(name: &str, taken: &[String]) -> String {
+    let mut name = name.chars()
+        .map(|ch| if ch.is_alphanumeric() || ch == '_' || ch == '$' { ch } else { '_' })
+        .collect::<String>();
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) || KEYWORDS.contains(&name.as_str()) {
+        name.insert(0, '_');
+    }
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Turns `name` into lower camel case, such as `serviceURI` for `ServiceURI`
+/// or `ttl` for `TTL`.
+fn lower_camel_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(name.len());
+    let mut index = 0;
+    while index < chars.len() && chars[index].is_uppercase() {
+        let is_word_start = chars.get(index + 1).is_some_and(|ch| ch.is_lowercase());
+        if index > 0 && is_word_start {
+            break;
+        }
+        output.extend(chars[index].to_lowercase());
+        index += 1;
+    }
+    output.extend(&chars[index..]);
+    output
+}
+
+/// Writes TSDoc comment containing the `@Doc` of `attributes`, if any, at
+/// given `level` of indentation.
+fn write_doc(attributes: &[Attribute], level: usize, output: &mut String) {
+    if let Some(doc) = doc_of(attributes) {
+        write_comment(&doc, level, output);
+    }
+}
+
+/// Writes TSDoc comment containing the `@Doc` of record `entry`, if
+/// any, followed by a `@default` tag if it has a default value.
+fn write_entry_doc(entry: &RecordEntry, level: usize, output: &mut String) {
+    let default = entry.default.as_ref()
+        .map(|default| {
+            let json = json::encode(&default.into());
+            let lines = json.lines().map(str::trim).collect::<Vec<_>>();
+            format!("@default {}", lines.join(" "))
+        });
+    let doc = match (doc_of(&entry.attributes), default) {
+        (Some(doc), Some(default)) => format!("{}\n\n{}", doc, default),
+        (Some(doc), None) | (None, Some(doc)) => doc,
+        (None, None) => return,
+    };
+    write_comment(&doc, level, output);
+}
+
+/// Writes `doc` as TSDoc comment at given `level` of indentation.
+fn write_comment(doc: &str, level: usize, output: &mut String) {
+    let indent = "    ".repeat(level);
+    let _ = writeln!(output, "{}/**", indent);
+    for line in doc.lines() {
+        let line = line.replace("*/", "*\\/");
+        if line.is_empty() {
+            let _ = writeln!(output, "{} *", indent);
+        } else {
+            let _ = writeln!(output, "{} * {}", indent, line);
+        }
+    }
+    let _ = writeln!(output, "{} */", indent);
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_module() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "service Registry {\n",
+                    "    @Doc(\"\n        Registers system.\n    \")\n",
+                    "    method Register(System): Kind;\n",
+                    "    method Query(): Map<String, List<Option<System>>>;\n",
+                    "    method Remove() throws Missing;\n",
+                    "}\n",
+                    "implement Registry using HTTP/JSON {\n",
+                    "    property BasePath: \"/registry\";\n",
+                    "    method Register { Method: \"POST\", Path: \"/systems\" }\n",
+                    "    method Query { Method: \"GET\", Path: \"/systems\" }\n",
+                    "    method Remove { Method: \"DELETE\", Path: \"/systems/{id}\", Errors: { Missing: 404 } }\n",
+                    "}\n",
+                    "@Doc(\"\n    A system.\n\")\n",
+                    "record System { Name: String, Port: U16, TTL: Option<U32>, Tags: Set<String> }\n",
+                    "enum Kind { Sensor, Actuator }\n",
+                    "record Missing { Name: String }\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        let implementations = spec.implementations.iter().collect::<Vec<_>>();
+
+        assert_eq!(generate(&spec, &[], &[], &implementations), concat!(
+            "/**\n",
+            " * A system.\n",
+            " */\n",
+            "export interface System {\n",
+            "    Name: string;\n",
+            "    Port: number;\n",
+            "    TTL?: number;\n",
+            "    Tags: string[];\n",
+            "}\n",
+            "\n",
+            "export type Kind = \"Sensor\" | \"Actuator\";\n",
+            "\n",
+            "export interface Missing {\n",
+            "    Name: string;\n",
+            "}\n",
+            "\n",
+            "/**\n",
+            " * An error response of a service method, named after the method error it\n",
+            " * represents.\n",
+            " */\n",
+            "export class ServiceError extends Error {\n",
+            "    constructor(readonly error: string, readonly status: number, readonly payload: unknown) {\n",
+            "        super(`service responded with ${error} (status ${status})`);\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "export class RegistryClient {\n",
+            "    constructor(private readonly baseUrl: string, private readonly init: RequestInit = {}) {}\n",
+            "\n",
+            "    /**\n",
+            "     * Registers system.\n",
+            "     */\n",
+            "    async register(system: System): Promise<Kind> {\n",
+            "        const response = await this.send(\"POST\", `/registry/systems`, system);\n",
+            "        return await response.json() as Kind;\n",
+            "    }\n",
+            "\n",
+            "    async query(): Promise<{ [key: string]: (System | null)[] }> {\n",
+            "        const response = await this.send(\"GET\", `/registry/systems`);\n",
+            "        return await response.json() as { [key: string]: (System | null)[] };\n",
+            "    }\n",
+            "\n",
+            "    async remove(id: string): Promise<void> {\n",
+            "        await this.send(\"DELETE\", `/registry/systems/${encodeURIComponent(id)}`, undefined, { 404: \"Missing\" });\n",
+            "    }\n",
+            "\n",
+            "    private async send(\n",
+            "        method: string,\n",
+            "        path: string,\n",
+            "        body?: unknown,\n",
+            "        errors: { [status: number]: string } = {},\n",
+            "    ): Promise<Response> {\n",
+            "        const headers = new Headers(this.init.headers);\n",
+            "        if (body !== undefined) {\n",
+            "            headers.set(\"Content-Type\", \"application/json\");\n",
+            "        }\n",
+            "        const response = await fetch(this.baseUrl + path, {\n",
+            "            ...this.init,\n",
+            "            method,\n",
+            "            headers,\n",
+            "            body: body === undefined ? undefined : JSON.stringify(body),\n",
+            "        });\n",
+            "        if (!response.ok) {\n",
+            "            const error = errors[response.status];\n",
+            "            if (error !== undefined) {\n",
+            "                throw new ServiceError(error, response.status, await response.json());\n",
+            "            }\n",
+            "            throw new Error(`${method} ${path} failed with status ${response.status}`);\n",
+            "        }\n",
+            "        return response;\n",
+            "    }\n",
+            "}\n",
+        ));
+    }
+}
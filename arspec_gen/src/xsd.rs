@@ -0,0 +1,533 @@
+//! XML Schema (XSD) generation.
+//!
+//! Every service is described by a schema declaring one root element per
+//! distinct method input or output type, as well as one named type per enum
+//! and record reachable from those. Generic records are declared once per
+//! distinct set of type parameters they are referred to with, as named by
+//! [`instance_name`][ins]. The schema follows the XML mapping
+//! described in [`arspec::payload::xml`][xml], including its `@XmlAttribute`
+//! and `@XmlName` overrides. Record entries that may be absent become
+//! elements with `minOccurs="0"` or optional attributes, with any scalar or
+//! enum default values given as `default` attributes. Aliases of simple
+//! types become simple types restricted by the constraints of the aliases,
+//! while aliases of other types are replaced by the types they alias.
+//!
+//! [ins]: ../fn.instance_name.html
+//! [xml]: ../../arspec/payload/xml/index.html
+
+use arspec::payload::Data;
+use arspec::payload::xml::{field_name, is_attribute, record_name};
+use arspec::spec::{Alias, Constraint, Enum, Record, RecordEntry, Service, Specification, TypeRef};
+use crate::{Definition, doc_of, instance_name, instances, reachable};
+use std::fmt::Write;
+
+/// Generates XSD document describing the payloads of `service`.
+pub fn generate<'a>(spec: &Specification<'a>, service: &Service<'a>) -> String {
+    let roots = service.methods.iter()
+        .flat_map(|method| method.input.iter().chain(method.output.iter()).chain(method.errors.iter()))
+        .collect::<Vec<_>>();
+
+    let mut output = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" elementFormDefault=\"qualified\">\n",
+    ));
+    let _ = writeln!(output, "  <!-- Payloads of service `{}`. -->", escape(&spec.qualify(&service.name)));
+
+    let mut names = Vec::new();
+    for type_ref in &roots {
+        let name = spec.resolve_record(&type_ref.name)
+            .map_or_else(|| type_ref.name.as_str().into(), record_name);
+        if names.contains(&name) {
+            continue;
+        }
+        write_element(spec, &name, type_ref, "", 1, &mut output);
+        names.push(name);
+    }
+
+    for definition in reachable(spec, roots.iter().copied()) {
+        match definition {
+            Definition::Alias(alias) => write_alias(spec, alias, &mut output),
+            Definition::Enum(enum_) => write_enum(spec, enum_, &mut output),
+            Definition::Record(record) if record.generic_parameters.is_empty() => {
+                write_record(spec, &type_name(spec.qualify(&record.name)), record, &record.entries, &mut output);
+            }
+            Definition::Record(_) | Definition::Primitive(_) => {}
+        }
+    }
+    for (name, type_ref) in instances(spec, roots) {
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            write_record(spec, &type_name(name), record, &record.entries_of(&type_ref), &mut output);
+        }
+    }
+    output.push_str("</xs:schema>\n");
+    output
+}
+
+/// The structure of some type, as far as XSD is concerned.
+enum Shape<'a: 'b, 'b> {
+    /// A named type, which is simple if it has no child elements or
+    /// attributes.
+    Named { name: String, is_simple: bool },
+    List(&'b TypeRef<'a>),
+    Map(&'b TypeRef<'a>),
+}
+
+impl<'a: 'b, 'b> Shape<'a, 'b> {
+    /// Determines shape of the type referred to by `type_ref`, which is the
+    /// same as that of `T` if it is an `Option<T>`, or that of the aliased
+    /// type if an alias of a type that is not simple.
+    fn of(spec: &'b Specification<'a>, type_ref: &'b TypeRef<'a>) -> Self {
+        if let Some(record) = spec.resolve_record(&type_ref.name) {
+            let name = if record.generic_parameters.is_empty() {
+                spec.qualify(&record.name)
+            } else {
+                instance_name(spec, type_ref)
+            };
+            return Shape::Named { name: type_name(name), is_simple: false };
+        }
+        if let Some(enum_) = spec.resolve_enum(&type_ref.name) {
+            return Shape::Named { name: type_name(spec.qualify(&enum_.name)), is_simple: true };
+        }
+        if let Some(alias) = spec.resolve_alias(&type_ref.name) {
+            return match Shape::of(spec, &alias.definition) {
+                Shape::Named { is_simple: true, .. } => {
+                    Shape::Named { name: type_name(spec.qualify(&alias.name)), is_simple: true }
+                }
+                shape => shape,
+            };
+        }
+        let params = &type_ref.params;
+        let name = match (type_ref.name.as_str(), params.len()) {
+            ("List", 1) | ("Set", 1) => return Shape::List(&params[0]),
+            ("Map", 2) => return Shape::Map(&params[1]),
+            ("Option", 1) => return Shape::of(spec, &params[0]),
+            ("Boolean", 0) => "xs:boolean",
+            ("Float32", 0) => "xs:float",
+            ("Float64", 0) => "xs:double",
+            ("I8", 0) => "xs:byte",
+            ("I16", 0) => "xs:short",
+            ("I32", 0) => "xs:int",
+            ("I64", 0) => "xs:long",
+            ("U8", 0) => "xs:unsignedByte",
+            ("U16", 0) => "xs:unsignedShort",
+            ("U32", 0) => "xs:unsignedInt",
+            ("U64", 0) => "xs:unsignedLong",
+            ("String", 0) => "xs:string",
+            _ => return Shape::Named { name: "xs:anyType".into(), is_simple: false },
+        };
+        Shape::Named { name: name.into(), is_simple: true }
+    }
+}
+
+/// Writes declaration of element `name` of the type referred to by
+/// `type_ref`, with given `occurs` constraint and default value attributes.
+fn write_element<'a>(
+    spec: &Specification<'a>,
+    name: &str,
+    type_ref: &TypeRef<'a>,
+    occurs: &str,
+    indent: usize,
+    output: &mut String,
+) {
+    push_indent(indent, output);
+    let _ = write!(output, "<xs:element name=\"{}\"", escape(name));
+    match Shape::of(spec, type_ref) {
+        Shape::Named { name, .. } => {
+            let _ = writeln!(output, " type=\"{}\"{}/>", name, occurs);
+            return;
+        }
+        Shape::List(item) => {
+            let _ = writeln!(output, "{}>", occurs);
+            push_line(indent + 1, "<xs:complexType>", output);
+            push_line(indent + 2, "<xs:sequence>", output);
+            write_element(spec, "item", item, " minOccurs=\"0\" maxOccurs=\"unbounded\"", indent + 3, output);
+            push_line(indent + 2, "</xs:sequence>", output);
+            push_line(indent + 1, "</xs:complexType>", output);
+        }
+        Shape::Map(value) => {
+            let _ = writeln!(output, "{}>", occurs);
+            push_line(indent + 1, "<xs:complexType>", output);
+            push_line(indent + 2, "<xs:sequence>", output);
+            push_line(indent + 3, "<xs:element name=\"entry\" minOccurs=\"0\" maxOccurs=\"unbounded\">", output);
+            write_keyed_type(spec, value, indent + 4, output);
+            push_line(indent + 3, "</xs:element>", output);
+            push_line(indent + 2, "</xs:sequence>", output);
+            push_line(indent + 1, "</xs:complexType>", output);
+        }
+    }
+    push_line(indent, "</xs:element>", output);
+}
+
+/// Writes anonymous type of map entries holding values of the type referred
+/// to by `type_ref`, as well as a `key` attribute.
+fn write_keyed_type<'a>(spec: &Specification<'a>, type_ref: &TypeRef<'a>, indent: usize, output: &mut String) {
+    const KEY: &str = "<xs:attribute name=\"key\" type=\"xs:string\" use=\"required\"/>";
+    match Shape::of(spec, type_ref) {
+        Shape::Named { ref name, is_simple } if name != "xs:anyType" => {
+            let content = if is_simple { "xs:simpleContent" } else { "xs:complexContent" };
+            push_line(indent, "<xs:complexType>", output);
+            push_line(indent + 1, &format!("<{}>", content), output);
+            push_line(indent + 2, &format!("<xs:extension base=\"{}\">", name), output);
+            push_line(indent + 3, KEY, output);
+            push_line(indent + 2, "</xs:extension>", output);
+            push_line(indent + 1, &format!("</{}>", content), output);
+            push_line(indent, "</xs:complexType>", output);
+        }
+        _ => {
+            push_line(indent, "<xs:complexType mixed=\"true\">", output);
+            push_line(indent + 1, "<xs:sequence>", output);
+            push_line(indent + 2, "<xs:any processContents=\"lax\" minOccurs=\"0\" maxOccurs=\"unbounded\"/>", output);
+            push_line(indent + 1, "</xs:sequence>", output);
+            push_line(indent + 1, KEY, output);
+            push_line(indent, "</xs:complexType>", output);
+        }
+    }
+}
+
+/// Writes simple type of `alias`, unless the aliased type is not simple.
+fn write_alias<'a>(spec: &Specification<'a>, alias: &Alias<'a>, output: &mut String) {
+    let base = match Shape::of(spec, &alias.definition) {
+        Shape::Named { name, is_simple: true } => name,
+        _ => return,
+    };
+    push_line(1, &format!("<xs:simpleType name=\"{}\">", type_name(spec.qualify(&alias.name))), output);
+    write_doc(doc_of(&alias.attributes), 2, output);
+    let mut facets = Vec::new();
+    for constraint in &alias.constraints {
+        match constraint {
+            Constraint::Range { start, end, is_inclusive, .. } => {
+                if let Some(start) = start {
+                    facets.push(("minInclusive", Data::from(start)));
+                }
+                if let Some(end) = end {
+                    facets.push((if *is_inclusive { "maxInclusive" } else { "maxExclusive" }, Data::from(end)));
+                }
+            }
+            Constraint::LengthMin { length, .. } => facets.push(("minLength", Data::from(length))),
+            Constraint::LengthMax { length, .. } => facets.push(("maxLength", Data::from(length))),
+            Constraint::Pattern { pattern, .. } => {
+                let pattern = pattern.as_string().unwrap_or_default();
+                facets.push(("pattern", Data::String(regex_of(&pattern))));
+            }
+        }
+    }
+    if facets.is_empty() {
+        push_line(2, &format!("<xs:restriction base=\"{}\"/>", base), output);
+    } else {
+        push_line(2, &format!("<xs:restriction base=\"{}\">", base), output);
+        for (facet, value) in facets {
+            let value = match value {
+                Data::Integer(integer) => integer.to_string(),
+                Data::Float(number) => number.to_string(),
+                Data::String(string) => escape(&string),
+                _ => continue,
+            };
+            push_line(3, &format!("<xs:{} value=\"{}\"/>", facet, value), output);
+        }
+        push_line(2, "</xs:restriction>", output);
+    }
+    push_line(1, "</xs:simpleType>", output);
+}
+
+fn write_enum<'a>(spec: &Specification<'a>, enum_: &Enum<'a>, output: &mut String) {
+    push_line(1, &format!("<xs:simpleType name=\"{}\">", type_name(spec.qualify(&enum_.name))), output);
+    write_doc(doc_of(&enum_.attributes), 2, output);
+    let base = if enum_.is_integer() { "xs:long" } else { "xs:string" };
+    push_line(2, &format!("<xs:restriction base=\"{}\">", base), output);
+    for variant in &enum_.variants {
+        let value = match variant.wire_value() {
+            Data::Integer(integer) => integer.to_string(),
+            Data::String(string) => escape(&string),
+            _ => continue,
+        };
+        push_line(3, &format!("<xs:enumeration value=\"{}\"/>", value), output);
+    }
+    push_line(2, "</xs:restriction>", output);
+    push_line(1, "</xs:simpleType>", output);
+}
+
+/// Writes complex type `name` of `record`, which has given `entries`.
+fn write_record<'a>(
+    spec: &Specification<'a>,
+    name: &str,
+    record: &Record<'a>,
+    entries: &[RecordEntry<'a>],
+    output: &mut String,
+) {
+    push_line(1, &format!("<xs:complexType name=\"{}\">", name), output);
+    write_doc(doc_of(&record.attributes), 2, output);
+
+    let (attributes, elements): (Vec<_>, Vec<_>) = entries.iter()
+        .partition(|entry| is_attribute(spec, entry));
+    if !elements.is_empty() {
+        push_line(2, "<xs:sequence>", output);
+        for entry in elements {
+            match Shape::of(spec, &entry.type_ref) {
+                Shape::List(item) => {
+                    let occurs = " minOccurs=\"0\" maxOccurs=\"unbounded\"";
+                    write_element(spec, &field_name(entry), item, occurs, 3, output);
+                }
+                _ => {
+                    let mut occurs = if entry.may_be_absent() { " minOccurs=\"0\"" } else { "" }.to_string();
+                    if let Some(default) = default_of(spec, entry) {
+                        let _ = write!(occurs, " default=\"{}\"", escape(&default));
+                    }
+                    write_element(spec, &field_name(entry), &entry.type_ref, &occurs, 3, output);
+                }
+            }
+        }
+        push_line(2, "</xs:sequence>", output);
+    }
+    for entry in attributes {
+        let usage = if entry.may_be_absent() { "optional" } else { "required" };
+        let default = default_of(spec, entry)
+            .map(|default| format!(" default=\"{}\"", escape(&default)))
+            .unwrap_or_default();
+        if let Shape::Named { name, .. } = Shape::of(spec, &entry.type_ref) {
+            push_line(2, &format!("<xs:attribute name=\"{}\" type=\"{}\" use=\"{}\"{}/>",
+                escape(&field_name(entry)), name, usage, default), output);
+        }
+    }
+    push_line(1, "</xs:complexType>", output);
+}
+
+fn write_doc(doc: Option<String>, indent: usize, output: &mut String) {
+    if let Some(doc) = doc {
+        push_line(indent, "<xs:annotation>", output);
+        push_line(indent + 1, &format!("<xs:documentation>{}</xs:documentation>", escape(&doc)), output);
+        push_line(indent, "</xs:annotation>", output);
+    }
+}
+
+/// Formats the default value of record `entry` as XML text, if it has one
+/// and its type is simple.
+fn default_of<'a>(spec: &Specification<'a>, entry: &RecordEntry<'a>) -> Option<String> {
+    match Shape::of(spec, &entry.type_ref) {
+        Shape::Named { is_simple: true, .. } => {}
+        _ => return None,
+    }
+    match Data::from(entry.default.as_ref()?) {
+        Data::Boolean(boolean) => Some(boolean.to_string()),
+        Data::Integer(integer) => Some(integer.to_string()),
+        Data::Float(number) => Some(number.to_string()),
+        Data::String(string) => Some(string),
+        _ => None,
+    }
+}
+
+/// Turns alias `pattern` into an XSD regular expression matching the same
+/// strings.
+///
+/// XSD regular expressions are implicitly anchored and lack non-capturing
+/// groups, which is why unanchored alternatives are padded with `.*` and the
+/// anchors `^` and `$` are removed. The `.regexp` controls of CDDL use the
+/// same kind of regular expressions.
+pub(crate) fn regex_of(pattern: &str) -> String {
+    let pattern = pattern.replace("(?:", "(");
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut is_in_class = false;
+    let mut is_escaped = false;
+    let mut start = 0;
+    for (index, ch) in pattern.char_indices() {
+        match ch {
+            _ if is_escaped => is_escaped = false,
+            '\\' => is_escaped = true,
+            '[' => is_in_class = true,
+            ']' => is_in_class = false,
+            '(' if !is_in_class => depth += 1,
+            ')' if !is_in_class => depth -= 1,
+            '|' if !is_in_class && depth == 0 => {
+                alternatives.push(&pattern[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&pattern[start..]);
+    alternatives.into_iter()
+        .map(|alternative| {
+            let (prefix, alternative) = match alternative.strip_prefix('^') {
+                Some(alternative) => ("", alternative),
+                None => (".*", alternative),
+            };
+            // A `$` preceded by an odd number of backslashes is escaped.
+            let (suffix, alternative) = match alternative.strip_suffix('$') {
+                Some(rest) if (rest.len() - rest.trim_end_matches('\\').len()) % 2 == 0 => ("", rest),
+                _ => (".*", alternative),
+            };
+            format!("{}{}{}", prefix, alternative, suffix)
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Turns qualified specification name into valid XSD type name.
+fn type_name(qualified: String) -> String {
+    qualified.replace("::", ".")
+}
+
+fn push_line(indent: usize, line: &str, output: &mut String) {
+    push_indent(indent, output);
+    output.push_str(line);
+    output.push('\n');
+}
+
+fn push_indent(indent: usize, output: &mut String) {
+    for _ in 0..indent {
+        output.push_str("  ");
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use super::*;
+
+    #[test]
+    fn generate_service() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "@Doc(\"A stored entry.\")\n",
+                    "@XmlName(\"entry\")\n",
+                    "record Entry {\n",
+                    "    @XmlAttribute(true) Id: U32,\n",
+                    "    @XmlName(\"name\") Name: Option<String>,\n",
+                    "    Tags: Set<Level>,\n",
+                    "    Counts: Map<String, I64>,\n",
+                    "}\n",
+                    "enum Level { Low, High }\n",
+                    "service S { method Get(): Entry; }\n",
+                    "implement S using HTTP/XML {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(generate(&spec, &spec.services[0]), concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" elementFormDefault=\"qualified\">\n",
+            "  <!-- Payloads of service `S`. -->\n",
+            "  <xs:element name=\"entry\" type=\"Entry\"/>\n",
+            "  <xs:complexType name=\"Entry\">\n",
+            "    <xs:annotation>\n",
+            "      <xs:documentation>A stored entry.</xs:documentation>\n",
+            "    </xs:annotation>\n",
+            "    <xs:sequence>\n",
+            "      <xs:element name=\"name\" type=\"xs:string\" minOccurs=\"0\"/>\n",
+            "      <xs:element name=\"Tags\" type=\"Level\" minOccurs=\"0\" maxOccurs=\"unbounded\"/>\n",
+            "      <xs:element name=\"Counts\">\n",
+            "        <xs:complexType>\n",
+            "          <xs:sequence>\n",
+            "            <xs:element name=\"entry\" minOccurs=\"0\" maxOccurs=\"unbounded\">\n",
+            "              <xs:complexType>\n",
+            "                <xs:simpleContent>\n",
+            "                  <xs:extension base=\"xs:long\">\n",
+            "                    <xs:attribute name=\"key\" type=\"xs:string\" use=\"required\"/>\n",
+            "                  </xs:extension>\n",
+            "                </xs:simpleContent>\n",
+            "              </xs:complexType>\n",
+            "            </xs:element>\n",
+            "          </xs:sequence>\n",
+            "        </xs:complexType>\n",
+            "      </xs:element>\n",
+            "    </xs:sequence>\n",
+            "    <xs:attribute name=\"Id\" type=\"xs:unsignedInt\" use=\"required\"/>\n",
+            "  </xs:complexType>\n",
+            "  <xs:simpleType name=\"Level\">\n",
+            "    <xs:restriction base=\"xs:string\">\n",
+            "      <xs:enumeration value=\"Low\"/>\n",
+            "      <xs:enumeration value=\"High\"/>\n",
+            "    </xs:restriction>\n",
+            "  </xs:simpleType>\n",
+            "</xs:schema>\n",
+        ));
+    }
+
+    #[test]
+    fn generate_generic_records() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "record Page<Item> { Items: List<Item>, Next: Option<Page<Item>> }\n",
+                    "service S { method Get(): Page<U8>; }\n",
+                    "implement S using HTTP/XML {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(generate(&spec, &spec.services[0]), concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" elementFormDefault=\"qualified\">\n",
+            "  <!-- Payloads of service `S`. -->\n",
+            "  <xs:element name=\"Page\" type=\"Page_U8\"/>\n",
+            "  <xs:complexType name=\"Page_U8\">\n",
+            "    <xs:sequence>\n",
+            "      <xs:element name=\"Items\" type=\"xs:unsignedByte\" minOccurs=\"0\" maxOccurs=\"unbounded\"/>\n",
+            "      <xs:element name=\"Next\" type=\"Page_U8\" minOccurs=\"0\"/>\n",
+            "    </xs:sequence>\n",
+            "  </xs:complexType>\n",
+            "</xs:schema>\n",
+        ));
+    }
+
+    #[test]
+    fn generate_aliases() {
+        let corpus = Corpus {
+            texts: vec![Text {
+                name: "test.ahfs".into(),
+                body: concat!(
+                    "type Port = U16 where 1..=65535;\n",
+                    "type Low = Port where ..1024;\n",
+                    "type Name = String where len <= 64, pattern \"^[a-z]+|x$\";\n",
+                    "type Ports = List<Port>;\n",
+                    "record Entry { Name: Name, Port: Low, Ports: Ports }\n",
+                    "service S { method Get(): Entry; }\n",
+                    "implement S using HTTP/XML {}\n",
+                ).into(),
+            }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        assert_eq!(generate(&spec, &spec.services[0]), concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" elementFormDefault=\"qualified\">\n",
+            "  <!-- Payloads of service `S`. -->\n",
+            "  <xs:element name=\"Entry\" type=\"Entry\"/>\n",
+            "  <xs:complexType name=\"Entry\">\n",
+            "    <xs:sequence>\n",
+            "      <xs:element name=\"Name\" type=\"Name\"/>\n",
+            "      <xs:element name=\"Port\" type=\"Low\"/>\n",
+            "      <xs:element name=\"Ports\" type=\"Port\" minOccurs=\"0\" maxOccurs=\"unbounded\"/>\n",
+            "    </xs:sequence>\n",
+            "  </xs:complexType>\n",
+            "  <xs:simpleType name=\"Name\">\n",
+            "    <xs:restriction base=\"xs:string\">\n",
+            "      <xs:maxLength value=\"64\"/>\n",
+            "      <xs:pattern value=\"[a-z]+.*|.*x\"/>\n",
+            "    </xs:restriction>\n",
+            "  </xs:simpleType>\n",
+            "  <xs:simpleType name=\"Low\">\n",
+            "    <xs:restriction base=\"Port\">\n",
+            "      <xs:maxExclusive value=\"1024\"/>\n",
+            "    </xs:restriction>\n",
+            "  </xs:simpleType>\n",
+            "  <xs:simpleType name=\"Port\">\n",
+            "    <xs:restriction base=\"xs:unsignedShort\">\n",
+            "      <xs:minInclusive value=\"1\"/>\n",
+            "      <xs:maxInclusive value=\"65535\"/>\n",
+            "    </xs:restriction>\n",
+            "  </xs:simpleType>\n",
+            "</xs:schema>\n",
+        ));
+    }
+}
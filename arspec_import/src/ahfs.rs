@@ -0,0 +1,302 @@
+//! Formatting of specification sources.
+//!
+//! Sources are formatted the same way as those of the Arrowhead core
+//! services, which means four spaces of indentation, a blank line between
+//! top-level items, and `@Doc` attributes with their text on lines of their
+//! own.
+
+use std::fmt;
+
+/// Maximum number of characters on a documentation line, unless a single
+/// word is longer.
+const LINE_WIDTH: usize = 80;
+
+/// Words that cannot be used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "NaN", "consumes", "enum", "false", "implement", "import", "inf", "interface", "method",
+    "null", "package", "primitive", "produces", "property", "record", "service", "system",
+    "true", "use", "using",
+];
+
+/// Names of the primitives of the Arrowhead core specifications.
+const PRIMITIVES: &[&str] = &[
+    "Boolean", "Float32", "Float64", "I8", "I16", "I32", "I64", "List", "Map", "Option", "Set",
+    "String", "U8", "U16", "U32", "U64",
+];
+
+/// A specification source file.
+#[derive(Debug, Default)]
+pub struct Source {
+    pub items: Vec<Item>,
+}
+
+/// A top-level source item.
+#[derive(Debug)]
+pub enum Item {
+    Enum(Enum),
+    Implement(Implement),
+    Record(Record),
+    Service(Service),
+}
+
+#[derive(Debug)]
+pub struct Enum {
+    pub doc: Option<String>,
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Implement {
+    pub name: String,
+    pub protocol: &'static str,
+    pub encoding: &'static str,
+    pub properties: Vec<(&'static str, String)>,
+    pub methods: Vec<ImplementMethod>,
+}
+
+#[derive(Debug)]
+pub struct ImplementMethod {
+    pub name: String,
+    pub data: Vec<(&'static str, String)>,
+}
+
+#[derive(Debug)]
+pub struct Record {
+    pub doc: Option<String>,
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub doc: Option<String>,
+    pub name: String,
+    pub type_ref: String,
+}
+
+#[derive(Debug)]
+pub struct Service {
+    pub doc: Option<String>,
+    pub name: String,
+    pub methods: Vec<Method>,
+}
+
+#[derive(Debug)]
+pub struct Method {
+    pub doc: Option<String>,
+    pub name: String,
+    pub input: Option<String>,
+    pub output: Option<String>,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, item) in self.items.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            match *item {
+                Item::Enum(ref enum_) => {
+                    write_doc(enum_.doc.as_ref(), 0, f)?;
+                    writeln!(f, "enum {} {{", enum_.name)?;
+                    for variant in &enum_.variants {
+                        writeln!(f, "    {},", variant)?;
+                    }
+                    f.write_str("}\n")?;
+                }
+                Item::Implement(ref implement) => {
+                    writeln!(f, "implement {} using {}/{} {{", implement.name, implement.protocol, implement.encoding)?;
+                    for (name, value) in &implement.properties {
+                        writeln!(f, "    property {}: {};", name, Quoted(value))?;
+                    }
+                    for (index, method) in implement.methods.iter().enumerate() {
+                        if index > 0 || !implement.properties.is_empty() {
+                            f.write_str("\n")?;
+                        }
+                        writeln!(f, "    method {} {{", method.name)?;
+                        for (name, value) in &method.data {
+                            writeln!(f, "        {}: {},", name, Quoted(value))?;
+                        }
+                        f.write_str("    }\n")?;
+                    }
+                    f.write_str("}\n")?;
+                }
+                Item::Record(ref record) => {
+                    write_doc(record.doc.as_ref(), 0, f)?;
+                    writeln!(f, "record {} {{", record.name)?;
+                    for (index, field) in record.fields.iter().enumerate() {
+                        let is_spaced = field.doc.is_some() || index > 0 && record.fields[index - 1].doc.is_some();
+                        if index > 0 && is_spaced {
+                            f.write_str("\n")?;
+                        }
+                        write_doc(field.doc.as_ref(), 1, f)?;
+                        writeln!(f, "    {}: {},", field.name, field.type_ref)?;
+                    }
+                    f.write_str("}\n")?;
+                }
+                Item::Service(ref service) => {
+                    write_doc(service.doc.as_ref(), 0, f)?;
+                    writeln!(f, "service {} {{", service.name)?;
+                    for (index, method) in service.methods.iter().enumerate() {
+                        let is_spaced = method.doc.is_some() || index > 0 && service.methods[index - 1].doc.is_some();
+                        if index > 0 && is_spaced {
+                            f.write_str("\n")?;
+                        }
+                        write_doc(method.doc.as_ref(), 1, f)?;
+                        write!(f, "    method {}(", method.name)?;
+                        if let Some(ref input) = method.input {
+                            f.write_str(input)?;
+                        }
+                        f.write_str(")")?;
+                        if let Some(ref output) = method.output {
+                            write!(f, ": {}", output)?;
+                        }
+                        f.write_str(";\n")?;
+                    }
+                    f.write_str("}\n")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes `@Doc` attribute containing `doc`, if any, at given `level` of
+/// indentation.
+fn write_doc(doc: Option<&String>, level: usize, f: &mut fmt::Formatter) -> fmt::Result {
+    let doc = match doc {
+        Some(doc) => doc,
+        None => return Ok(()),
+    };
+    let indent = "    ".repeat(level);
+    writeln!(f, "{}@Doc(\"", indent)?;
+    for line in doc.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            f.write_str("\n")?;
+            continue;
+        }
+        let margin = &line[..line.len() - line.trim_start().len()];
+        let mut width = 0;
+        for word in line.split_whitespace() {
+            if width > 0 && width + 1 + word.chars().count() > LINE_WIDTH {
+                f.write_str("\n")?;
+                width = 0;
+            }
+            if width == 0 {
+                write!(f, "{}    {}", indent, margin)?;
+                width = indent.len() + 4 + margin.len();
+            } else {
+                f.write_str(" ")?;
+                width += 1;
+            }
+            write!(f, "{}", Escaped(word))?;
+            width += word.chars().count();
+        }
+        f.write_str("\n")?;
+    }
+    writeln!(f, "{}\")", indent)
+}
+
+/// A string literal.
+struct Quoted<'a>(&'a str);
+
+impl<'a> fmt::Display for Quoted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", Escaped(self.0))
+    }
+}
+
+/// String literal contents.
+struct Escaped<'a>(&'a str);
+
+impl<'a> fmt::Display for Escaped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\t' => f.write_str("\\t")?,
+                ch if ch.is_control() => {}
+                ch => write!(f, "{}", ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps track of the names already used within some scope.
+#[derive(Debug, Default)]
+pub struct Names(Vec<String>);
+
+impl Names {
+    /// Creates name scope in which the names of the core primitives are
+    /// already in use.
+    pub fn with_primitives() -> Self {
+        Names(PRIMITIVES.iter().map(|name| name.to_string()).collect())
+    }
+
+    /// Claims `name`, or `name` with the lowest possible numeric suffix if
+    /// the name is already in use.
+    pub fn claim(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+        while self.0.contains(&candidate) {
+            candidate = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+        self.0.push(candidate.clone());
+        candidate
+    }
+}
+
+/// Whether or not `name` is a valid identifier.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|ch| ch.is_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+        && !KEYWORDS.contains(&name)
+}
+
+/// Turns `name` into a valid identifier by replacing or adding characters.
+pub fn to_identifier(name: &str) -> String {
+    let mut identifier = name.chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+        .collect::<String>();
+    if !identifier.starts_with(|ch: char| ch.is_alphabetic() || ch == '_') {
+        identifier.insert(0, '_');
+    }
+    if KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
+
+/// Turns `name` into an identifier in Pascal case, such as `PetStore` for
+/// `pet-store`, or returns `fallback` if `name` contains no alphanumeric
+/// characters.
+pub fn to_pascal_case(name: &str, fallback: &str) -> String {
+    let mut output = String::with_capacity(name.len());
+    let mut is_word_start = true;
+    for ch in name.chars() {
+        if !ch.is_alphanumeric() {
+            is_word_start = true;
+            continue;
+        }
+        if is_word_start {
+            output.extend(ch.to_uppercase());
+            is_word_start = false;
+        } else {
+            output.push(ch);
+        }
+    }
+    if output.is_empty() {
+        return fallback.into();
+    }
+    if !output.starts_with(char::is_alphabetic) {
+        output.insert(0, '_');
+    }
+    output
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// Describes an import error.
+#[derive(Debug)]
+pub enum Error {
+    /// Imported document lacks some mandatory part, or has a part of an
+    /// unexpected kind.
+    DocumentInvalid(&'static str),
+
+    /// Imported document is of a version not supported by its importer.
+    VersionUnsupported(String),
+
+    /// YAML input could not be decoded.
+    YamlInvalid {
+        line: usize,
+        message: &'static str,
+    },
+}
+
+impl arspec::Error for Error {
+    fn code(&self) -> &'static str {
+        match *self {
+            Error::DocumentInvalid(_) => "IM02",
+            Error::VersionUnsupported(_) => "IM03",
+            Error::YamlInvalid { .. } => "IM01",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::DocumentInvalid(message) => write!(f, "Invalid document: {}", message),
+            Error::VersionUnsupported(ref version) => {
+                write!(f, "Document version `{}` is not supported", version)
+            }
+            Error::YamlInvalid { line, message } => {
+                write!(f, "Invalid YAML at line {}: {}", line, message)
+            }
+        }
+    }
+}
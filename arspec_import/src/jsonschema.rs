@@ -0,0 +1,136 @@
+//! JSON Schema import.
+//!
+//! The root schema of a document, as well as every schema in its `$defs` or
+//! `definitions`, is mapped to a record or enum as described in the
+//! [`schema`](../schema/index.html) module, if it describes an object with
+//! properties or enumerates strings. Other schemas are expanded wherever
+//! they are referred to.
+
+use arspec::payload::Data;
+use crate::ahfs::{Source, to_pascal_case};
+use crate::schema::Mapper;
+use crate::{Error, Import, get_map, get_str, pointer_join};
+
+/// Imports JSON Schema `document`, naming its root schema after its `title`,
+/// or after `name` if it has no title.
+pub fn import(document: &Data, name: &str) -> Result<Import, Error> {
+    let root = match document {
+        Data::Map(_) => document,
+        _ => return Err(Error::DocumentInvalid("expected schema object")),
+    };
+    let name = to_pascal_case(get_str(root, "title").unwrap_or(name), "Schema");
+
+    let mut mapper = Mapper::new(document);
+    let declared = mapper.declare("#".into(), &name);
+    for key in ["$defs", "definitions"].iter() {
+        for (definition, _) in get_map(root, key) {
+            mapper.declare(pointer_join(&pointer_join("#", key), definition), definition);
+        }
+    }
+    mapper.define_declared();
+    if declared.is_none() && root.get("type").is_some() {
+        mapper.warn("#".into(), "root schema is neither an object nor an enum of strings, ignoring it".into());
+    }
+
+    let mut source = Source::default();
+    source.items.append(&mut mapper.items);
+    Ok(Import {
+        name: declared.unwrap_or(name),
+        source: source.to_string(),
+        warnings: mapper.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use crate::yaml;
+    use super::*;
+
+    #[test]
+    fn import_document() {
+        let document = yaml::decode(concat!(
+            "$schema: https://json-schema.org/draft/2020-12/schema\n",
+            "title: sensor-reading\n",
+            "description: A reading reported by a sensor.\n",
+            "type: object\n",
+            "required: [sensor, values]\n",
+            "properties:\n",
+            "  sensor: { $ref: '#/$defs/Sensor' }\n",
+            "  values:\n",
+            "    type: array\n",
+            "    items: { type: number }\n",
+            "  labels:\n",
+            "    description: Arbitrary labels.\n",
+            "    type: object\n",
+            "    additionalProperties: { type: string }\n",
+            "  unit: { $ref: '#/$defs/Unit' }\n",
+            "$defs:\n",
+            "  Sensor:\n",
+            "    type: object\n",
+            "    required: [id]\n",
+            "    properties:\n",
+            "      id: { type: string }\n",
+            "      location: { type: [string, 'null'] }\n",
+            "    not: { required: [other] }\n",
+            "  Unit:\n",
+            "    description: A unit of measurement.\n",
+            "    enum: [celsius, kelvin]\n",
+        )).unwrap();
+
+        let import = import(&document, "fallback").unwrap();
+        assert_eq!(import.name, "SensorReading");
+        assert_eq!(import.source, concat!(
+            "@Doc(\"\n",
+            "    A reading reported by a sensor.\n",
+            "\")\n",
+            "record SensorReading {\n",
+            "    sensor: Sensor,\n",
+            "    values: List<Float64>,\n",
+            "\n",
+            "    @Doc(\"\n",
+            "        Arbitrary labels.\n",
+            "    \")\n",
+            "    labels: Option<Map<String, String>>,\n",
+            "\n",
+            "    unit: Option<Unit>,\n",
+            "}\n",
+            "\n",
+            "record Sensor {\n",
+            "    id: String,\n",
+            "    location: Option<String>,\n",
+            "}\n",
+            "\n",
+            "@Doc(\"\n",
+            "    A unit of measurement.\n",
+            "\")\n",
+            "enum Unit {\n",
+            "    celsius,\n",
+            "    kelvin,\n",
+            "}\n",
+        ));
+        let warnings = import.warnings.iter()
+            .map(|warning| warning.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, vec!["#/$defs/Sensor/not"]);
+
+        let corpus = Corpus {
+            texts: vec![Text { name: "SensorReading.ahfs".into(), body: import.source.into() }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        assert_eq!(spec.records.len(), 2);
+        assert_eq!(spec.enums.len(), 1);
+    }
+
+    #[test]
+    fn warn_about_unmapped_root() {
+        let document = yaml::decode("type: array\nitems: { type: string }\n").unwrap();
+        let import = import(&document, "names").unwrap();
+        assert_eq!(import.name, "Names");
+        assert_eq!(import.source, "");
+        assert_eq!(import.warnings.len(), 1);
+        assert_eq!(import.warnings[0].pointer, "#");
+    }
+}
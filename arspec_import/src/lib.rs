@@ -0,0 +1,107 @@
+//! Importers of Arrowhead specifications from other description formats.
+
+pub mod jsonschema;
+pub mod openapi;
+pub mod yaml;
+
+mod ahfs;
+mod error;
+mod schema;
+
+pub use self::error::Error;
+
+use arspec::payload::{json, Data};
+use std::fmt;
+
+/// A specification source produced by an importer.
+#[derive(Debug)]
+pub struct Import {
+    /// Suggested source file name, excluding its extension.
+    pub name: String,
+
+    /// Formatted specification source.
+    pub source: String,
+
+    /// Descriptions of all constructs that could not be imported.
+    pub warnings: Vec<Warning>,
+}
+
+/// Describes a construct of an imported document that could not be mapped
+/// to any specification construct.
+#[derive(Debug)]
+pub struct Warning {
+    /// JSON pointer to the construct, such as `#/paths/~1pets/get`.
+    pub pointer: String,
+
+    /// Description of what could not be mapped.
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Decodes `input` as JSON, if its first non-whitespace character is `{`,
+/// or as YAML otherwise.
+pub fn decode(input: &str) -> arspec::Result<Data> {
+    if input.trim_start().starts_with('{') {
+        Ok(json::decode(input)?)
+    } else {
+        Ok(yaml::decode(input)?)
+    }
+}
+
+/// Creates JSON pointer by appending `segment` to `pointer`.
+fn pointer_join(pointer: &str, segment: &str) -> String {
+    format!("{}/{}", pointer, segment.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Resolves `pointer`, such as `#/components/schemas/Pet`, within
+/// `document`.
+fn pointer_resolve<'a>(document: &'a Data, pointer: &str) -> Option<&'a Data> {
+    let path = pointer.strip_prefix('#')?;
+    if path.is_empty() {
+        return Some(document);
+    }
+    let mut data = document;
+    for segment in path.strip_prefix('/')?.split('/') {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        data = match *data {
+            Data::List(ref items) => items.get(segment.parse::<usize>().ok()?)?,
+            Data::Map(_) => data.get(&segment)?,
+            _ => return None,
+        };
+    }
+    Some(data)
+}
+
+/// Gets named entry of `data` if it is a string.
+fn get_str<'a>(data: &'a Data, key: &str) -> Option<&'a str> {
+    match data.get(key) {
+        Some(Data::String(string)) => Some(string),
+        _ => None,
+    }
+}
+
+/// Gets named entry of `data` if it is a list.
+fn get_list<'a>(data: &'a Data, key: &str) -> &'a [Data] {
+    match data.get(key) {
+        Some(Data::List(items)) => items,
+        _ => &[],
+    }
+}
+
+/// Gets named entry of `data` if it is a map.
+fn get_map<'a>(data: &'a Data, key: &str) -> &'a [(String, Data)] {
+    data.get(key).map_or(&[], entries)
+}
+
+/// Gets the entries of `data` if it is a map.
+fn entries(data: &Data) -> &[(String, Data)] {
+    match data {
+        Data::Map(entries) => entries,
+        _ => &[],
+    }
+}
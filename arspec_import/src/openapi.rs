@@ -0,0 +1,468 @@
+//! OpenAPI import.
+//!
+//! Documents of versions 2.0 and 3.x of the OpenAPI Specification are
+//! imported as one service, named after the title of the document, and an
+//! HTTP/JSON implementation of that service. Every operation becomes a
+//! service method, the input and output of which are the JSON request body
+//! and the JSON body of the first successful response of the operation.
+//! Schemas are mapped to records, enums and primitives as described in the
+//! [`schema`](../schema/index.html) module.
+//!
+//! Query, header and cookie parameters, non-JSON payloads, unsuccessful
+//! responses, callbacks, webhooks and security requirements have no
+//! counterparts in specifications, and are reported as warnings.
+
+use arspec::payload::Data;
+use crate::ahfs::{Implement, ImplementMethod, Item, Method, Names, Service, Source, to_pascal_case};
+use crate::schema::Mapper;
+use crate::{Error, Import, entries, get_list, get_map, get_str, pointer_join, pointer_resolve};
+
+/// HTTP methods that may be used to invoke operations.
+const VERBS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Maximum number of references followed when resolving a parameter,
+/// request body or response.
+const MAX_INDIRECTIONS: usize = 8;
+
+/// Imports OpenAPI `document`.
+pub fn import(document: &Data) -> Result<Import, Error> {
+    let is_swagger = match (get_str(document, "openapi"), get_str(document, "swagger")) {
+        (Some(version), _) if version.starts_with("3.") => false,
+        (None, Some("2.0")) => true,
+        (Some(version), _) | (None, Some(version)) => {
+            return Err(Error::VersionUnsupported(version.into()));
+        }
+        (None, None) => return Err(Error::DocumentInvalid("expected `openapi` or `swagger` version")),
+    };
+    let info = match document.get("info") {
+        Some(info @ Data::Map(_)) => info,
+        _ => return Err(Error::DocumentInvalid("expected `info` object")),
+    };
+
+    let mut importer = Importer {
+        document,
+        is_swagger,
+        mapper: Mapper::new(document),
+        methods: Names::default(),
+    };
+    let name = importer.mapper.names.claim(to_pascal_case(get_str(info, "title").unwrap_or(""), "Service"));
+    let mut service = Service {
+        doc: get_str(info, "description")
+            .map(str::trim)
+            .filter(|doc| !doc.is_empty())
+            .map(Into::into),
+        name: name.clone(),
+        methods: Vec::new(),
+    };
+    let mut implement = Implement {
+        name: name.clone(),
+        protocol: "HTTP",
+        encoding: "JSON",
+        properties: Vec::new(),
+        methods: Vec::new(),
+    };
+    if let Some(base_path) = importer.base_path() {
+        implement.properties.push(("BasePath", base_path));
+    }
+
+    let schemas_pointer = if is_swagger { "#/definitions" } else { "#/components/schemas" };
+    let schemas = pointer_resolve(document, schemas_pointer)
+        .map_or(&[][..], entries);
+    for (name, _) in schemas {
+        importer.mapper.declare(pointer_join(schemas_pointer, name), name);
+    }
+    importer.mapper.define_declared();
+
+    for (path, path_item) in get_map(document, "paths") {
+        let pointer = pointer_join("#/paths", path);
+        if path_item.get("$ref").is_some() {
+            importer.mapper.warn(pointer_join(&pointer, "$ref"), "path item references cannot be mapped".into());
+            continue;
+        }
+        let common = get_list(path_item, "parameters");
+        for (verb, operation) in entries(path_item) {
+            if !VERBS.contains(&verb.as_str()) {
+                continue;
+            }
+            let (method, implement_method) = importer.operation(path, verb, operation, common, &pointer_join(&pointer, verb));
+            service.methods.push(method);
+            implement.methods.push(implement_method);
+        }
+    }
+    if let Some(Data::Map(webhooks)) = document.get("webhooks") {
+        if !webhooks.is_empty() {
+            importer.mapper.warn("#/webhooks".into(), "webhooks cannot be mapped".into());
+        }
+    }
+    if !get_list(document, "security").is_empty() {
+        importer.mapper.warn("#/security".into(), "security requirements cannot be mapped".into());
+    }
+
+    let mut source = Source::default();
+    source.items.push(Item::Service(service));
+    source.items.push(Item::Implement(implement));
+    source.items.append(&mut importer.mapper.items);
+    Ok(Import {
+        name,
+        source: source.to_string(),
+        warnings: importer.mapper.warnings,
+    })
+}
+
+struct Importer<'a> {
+    document: &'a Data,
+    is_swagger: bool,
+    mapper: Mapper<'a>,
+    methods: Names,
+}
+
+impl<'a> Importer<'a> {
+    /// Determines the path of the first server URL of the document, if any.
+    fn base_path(&self) -> Option<String> {
+        let url = if self.is_swagger {
+            get_str(self.document, "basePath")?
+        } else {
+            get_str(get_list(self.document, "servers").first()?, "url")?
+        };
+        let path = match url.find("://") {
+            Some(index) => {
+                let authority = &url[index + 3..];
+                authority.find('/').map_or("", |index| &authority[index..])
+            }
+            None => url,
+        };
+        let path = path.trim_end_matches('/');
+        if path.is_empty() {
+            None
+        } else {
+            Some(path.into())
+        }
+    }
+
+    /// Maps `operation`, invoked by sending `verb` requests to `path`, to a
+    /// service method and the HTTP data of that method.
+    fn operation(
+        &mut self,
+        path: &str,
+        verb: &str,
+        operation: &'a Data,
+        common: &'a [Data],
+        pointer: &str,
+    ) -> (Method, ImplementMethod) {
+        let name = match get_str(operation, "operationId") {
+            Some(id) => to_pascal_case(id, "Method"),
+            None => to_pascal_case(&format!("{} {}", verb, path), "Method"),
+        };
+        let name = self.methods.claim(name);
+        let doc = match (get_str(operation, "summary"), get_str(operation, "description")) {
+            (Some(summary), Some(description)) => Some(format!("{}\n\n{}", summary.trim(), description.trim())),
+            (summary, description) => summary.or(description)
+                .map(str::trim)
+                .filter(|doc| !doc.is_empty())
+                .map(Into::into),
+        };
+
+        let mut input = None;
+        let operation_parameters = get_list(operation, "parameters");
+        let parameters = operation_parameters.iter()
+            .enumerate()
+            .map(|(index, parameter)| (parameter, format!("{}/parameters/{}", pointer, index)))
+            .chain(common.iter().enumerate().map(|(index, parameter)| {
+                let path_pointer = pointer.rsplit_once('/').map_or("", |(path_pointer, _)| path_pointer);
+                (parameter, format!("{}/parameters/{}", path_pointer, index))
+            }))
+            .filter_map(|(parameter, pointer)| self.follow(parameter, pointer))
+            .collect::<Vec<_>>();
+        for (index, (parameter, parameter_pointer)) in parameters.iter().enumerate() {
+            let location = get_str(parameter, "in").unwrap_or("");
+            let parameter_name = get_str(parameter, "name").unwrap_or("");
+            let is_overridden = parameters[..index].iter().any(|(other, _)| {
+                get_str(other, "in") == Some(location) && get_str(other, "name") == Some(parameter_name)
+            });
+            if is_overridden {
+                continue;
+            }
+            match location {
+                "path" => {}
+                "body" => if let Some(schema) = parameter.get("schema") {
+                    let schema_pointer = pointer_join(parameter_pointer, "schema");
+                    input = Some(self.mapper.type_of(schema, &schema_pointer, &format!("{}Input", name)));
+                },
+                _ => self.mapper.warn(parameter_pointer.clone(), format!(
+                    "{} parameter `{}` cannot be mapped",
+                    location, parameter_name,
+                )),
+            }
+        }
+        if let Some(request_body) = operation.get("requestBody") {
+            let request_body_pointer = pointer_join(pointer, "requestBody");
+            if let Some((request_body, request_body_pointer)) = self.follow(request_body, request_body_pointer) {
+                if let Some((schema, schema_pointer)) = self.content_schema(request_body, &request_body_pointer) {
+                    input = Some(self.mapper.type_of(schema, &schema_pointer, &format!("{}Input", name)));
+                }
+            }
+        }
+
+        let mut output = None;
+        let mut has_output = false;
+        let responses_pointer = pointer_join(pointer, "responses");
+        for (status, response) in get_map(operation, "responses") {
+            let response_pointer = pointer_join(&responses_pointer, status);
+            let (response, response_pointer) = match self.follow(response, response_pointer) {
+                Some(response) => response,
+                None => continue,
+            };
+            let schema = if self.is_swagger {
+                response.get("schema").map(|schema| (schema, pointer_join(&response_pointer, "schema")))
+            } else {
+                self.content_schema(response, &response_pointer)
+            };
+            let (schema, schema_pointer) = match schema {
+                Some(schema) => schema,
+                None => continue,
+            };
+            if !status.starts_with('2') {
+                self.mapper.warn(response_pointer, format!(
+                    "response `{}` cannot be mapped, as only successful responses can",
+                    status,
+                ));
+            } else if has_output {
+                self.mapper.warn(response_pointer, format!(
+                    "response `{}` cannot be mapped, as only the first successful response can",
+                    status,
+                ));
+            } else {
+                output = Some(self.mapper.type_of(schema, &schema_pointer, &format!("{}Output", name)));
+                has_output = true;
+            }
+        }
+
+        if operation.get("callbacks").is_some() {
+            self.mapper.warn(pointer_join(pointer, "callbacks"), "callbacks cannot be mapped".into());
+        }
+        if !get_list(operation, "security").is_empty() {
+            self.mapper.warn(pointer_join(pointer, "security"), "security requirements cannot be mapped".into());
+        }
+
+        let method = Method { doc, name: name.clone(), input, output };
+        let implement_method = ImplementMethod {
+            name,
+            data: vec![
+                ("Method", verb.to_ascii_uppercase()),
+                ("Path", path.into()),
+            ],
+        };
+        (method, implement_method)
+    }
+
+    /// Gets the schema of the JSON representation in the `content` of the
+    /// request body or response at `pointer`, warning if there only are
+    /// other representations.
+    fn content_schema(&mut self, data: &'a Data, pointer: &str) -> Option<(&'a Data, String)> {
+        let content = get_map(data, "content");
+        let content_pointer = pointer_join(pointer, "content");
+        let json = content.iter().find(|(media_type, _)| {
+            let media_type = media_type.split(';').next().unwrap_or("").trim();
+            media_type == "application/json" || media_type.ends_with("+json")
+        });
+        match json {
+            Some((media_type, media)) => {
+                let schema = media.get("schema")?;
+                let media_pointer = pointer_join(&content_pointer, media_type);
+                Some((schema, pointer_join(&media_pointer, "schema")))
+            }
+            None => {
+                if let Some((media_type, _)) = content.first() {
+                    self.mapper.warn(pointer_join(&content_pointer, media_type), format!(
+                        "media type `{}` cannot be mapped, as only JSON can",
+                        media_type,
+                    ));
+                }
+                None
+            }
+        }
+    }
+
+    /// Follows any references of `data`, found at `pointer`, returning the
+    /// data referred to and its pointer.
+    fn follow(&mut self, mut data: &'a Data, mut pointer: String) -> Option<(&'a Data, String)> {
+        for _ in 0..MAX_INDIRECTIONS {
+            let reference = match get_str(data, "$ref") {
+                Some(reference) => reference,
+                None => return Some((data, pointer)),
+            };
+            match pointer_resolve(self.document, reference) {
+                Some(referred) => {
+                    data = referred;
+                    pointer = reference.into();
+                }
+                None => {
+                    self.mapper.warn(pointer_join(&pointer, "$ref"), format!(
+                        "reference `{}` cannot be resolved",
+                        reference,
+                    ));
+                    return None;
+                }
+            }
+        }
+        self.mapper.warn(pointer, "too many indirections to be mapped".into());
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arspec::spec::parser;
+    use arspec_parser::{Corpus, Text};
+    use crate::yaml;
+    use super::*;
+
+    #[test]
+    fn import_document() {
+        let document = yaml::decode(concat!(
+            "openapi: 3.0.3\n",
+            "info:\n",
+            "  title: Pet Store\n",
+            "  version: 1.0.0\n",
+            "  description: Manages pets.\n",
+            "servers:\n",
+            "  - url: https://example.com/v1/\n",
+            "paths:\n",
+            "  /pets:\n",
+            "    get:\n",
+            "      operationId: listPets\n",
+            "      parameters:\n",
+            "        - name: limit\n",
+            "          in: query\n",
+            "          schema: { type: integer, format: int32 }\n",
+            "      responses:\n",
+            "        '200':\n",
+            "          content:\n",
+            "            application/json:\n",
+            "              schema:\n",
+            "                type: array\n",
+            "                items: { $ref: '#/components/schemas/Pet' }\n",
+            "        default:\n",
+            "          content:\n",
+            "            application/json:\n",
+            "              schema: { $ref: '#/components/schemas/Error' }\n",
+            "    post:\n",
+            "      summary: Adds a pet.\n",
+            "      requestBody:\n",
+            "        content:\n",
+            "          application/json:\n",
+            "            schema: { $ref: '#/components/schemas/Pet' }\n",
+            "      responses:\n",
+            "        '201': { description: Created. }\n",
+            "  /pets/{petId}:\n",
+            "    delete:\n",
+            "      responses:\n",
+            "        '204': { description: Deleted. }\n",
+            "components:\n",
+            "  schemas:\n",
+            "    Pet:\n",
+            "      required: [id, name]\n",
+            "      properties:\n",
+            "        id: { type: integer, minimum: 0, maximum: 65535 }\n",
+            "        name: { type: string, description: The name of the pet. }\n",
+            "        status: { type: string, enum: [available, sold-out] }\n",
+            "        tags: { type: array, uniqueItems: true, items: { type: string } }\n",
+            "        x-owner: { $ref: '#/components/schemas/Owner' }\n",
+            "    Owner:\n",
+            "      type: string\n",
+            "    Error:\n",
+            "      type: object\n",
+            "      properties:\n",
+            "        code: { type: integer, format: int32 }\n",
+            "        message: { oneOf: [{ type: string }, { type: integer }] }\n",
+        )).unwrap();
+
+        let import = import(&document).unwrap();
+        assert_eq!(import.name, "PetStore");
+        assert_eq!(import.source, concat!(
+            "@Doc(\"\n",
+            "    Manages pets.\n",
+            "\")\n",
+            "service PetStore {\n",
+            "    method ListPets(): List<Pet>;\n",
+            "\n",
+            "    @Doc(\"\n",
+            "        Adds a pet.\n",
+            "    \")\n",
+            "    method PostPets(Pet);\n",
+            "\n",
+            "    method DeletePetsPetId();\n",
+            "}\n",
+            "\n",
+            "implement PetStore using HTTP/JSON {\n",
+            "    property BasePath: \"/v1\";\n",
+            "\n",
+            "    method ListPets {\n",
+            "        Method: \"GET\",\n",
+            "        Path: \"/pets\",\n",
+            "    }\n",
+            "\n",
+            "    method PostPets {\n",
+            "        Method: \"POST\",\n",
+            "        Path: \"/pets\",\n",
+            "    }\n",
+            "\n",
+            "    method DeletePetsPetId {\n",
+            "        Method: \"DELETE\",\n",
+            "        Path: \"/pets/{petId}\",\n",
+            "    }\n",
+            "}\n",
+            "\n",
+            "record Pet {\n",
+            "    id: U16,\n",
+            "\n",
+            "    @Doc(\"\n",
+            "        The name of the pet.\n",
+            "    \")\n",
+            "    name: String,\n",
+            "\n",
+            "    status: Option<PetStatus>,\n",
+            "    tags: Option<Set<String>>,\n",
+            "    x_owner: Option<String>,\n",
+            "}\n",
+            "\n",
+            "enum PetStatus {\n",
+            "    available,\n",
+            "    sold_out,\n",
+            "}\n",
+            "\n",
+            "record Error {\n",
+            "    code: Option<I32>,\n",
+            "    message: Option<String>,\n",
+            "}\n",
+        ));
+
+        let warnings = import.warnings.iter()
+            .map(|warning| warning.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, vec![
+            "#/components/schemas/Pet/properties/status/enum/1",
+            "#/components/schemas/Pet/properties/x-owner",
+            "#/components/schemas/Error/properties/message/oneOf",
+            "#/paths/~1pets/get/parameters/0",
+            "#/paths/~1pets/get/responses/default",
+        ]);
+
+        let corpus = Corpus {
+            texts: vec![Text { name: "PetStore.ahfs".into(), body: import.source.into() }],
+        };
+        let spec = parser::parse(&corpus).unwrap();
+        spec.verify().unwrap();
+        assert_eq!(spec.records.len(), 2);
+        assert_eq!(spec.implementations[0].methods.len(), 3);
+    }
+
+    #[test]
+    fn reject_unsupported_version() {
+        let document = yaml::decode("swagger: '1.2'\ninfo: { title: Old }\n").unwrap();
+        match import(&document) {
+            Err(Error::VersionUnsupported(version)) => assert_eq!(version, "1.2"),
+            result => panic!("{:?}", result),
+        }
+    }
+}
@@ -0,0 +1,442 @@
+//! Mapping of JSON Schemas, as used by OpenAPI schema objects, to records,
+//! enums and type references.
+//!
+//! Schemas describing objects with properties become records, and schemas
+//! enumerating strings become enums. Other schemas are mapped to primitive
+//! type references, such as `U16` or `List<String>`, wherever they are used.
+//! Records and enums are named after the schemas that declare them, or after
+//! the context in which they are used, if anonymous.
+
+use arspec::payload::Data;
+use crate::ahfs::{Enum, Field, Item, Names, Record, is_identifier, to_identifier, to_pascal_case};
+use crate::{Warning, get_list, get_map, get_str, pointer_join, pointer_resolve};
+
+/// Schema keywords that cannot be mapped to any specification construct.
+const UNSUPPORTED: &[&str] = &["dependentSchemas", "if", "not", "patternProperties"];
+
+/// Maximum number of nested `allOf` schemas followed when collecting the
+/// properties of a record.
+const MAX_DEPTH: usize = 32;
+
+/// Maps the schemas of a document to records, enums and type references.
+pub struct Mapper<'a> {
+    document: &'a Data,
+
+    /// Names of all records and enums, as well as any other names that
+    /// must not be used by any record or enum.
+    pub names: Names,
+
+    /// Records and enums, in the order they were defined.
+    pub items: Vec<Item>,
+
+    /// Any constructs that could not be mapped.
+    pub warnings: Vec<Warning>,
+
+    declared: Vec<(String, String)>,
+    aliases: Vec<(String, String)>,
+    resolving: Vec<String>,
+}
+
+impl<'a> Mapper<'a> {
+    pub fn new(document: &'a Data) -> Self {
+        Mapper {
+            document,
+            names: Names::with_primitives(),
+            items: Vec::new(),
+            warnings: Vec::new(),
+            declared: Vec::new(),
+            aliases: Vec::new(),
+            resolving: Vec::new(),
+        }
+    }
+
+    /// Declares that the schema at `pointer` is to be defined as a record or
+    /// enum with a name derived from `name`, if it describes either, in which
+    /// case the name is returned.
+    ///
+    /// Schemas must be declared before any of them is referred to, as other
+    /// schemas are expanded where referred to.
+    pub fn declare(&mut self, pointer: String, name: &str) -> Option<String> {
+        let is_definition = pointer_resolve(self.document, &pointer)
+            .is_some_and(is_definition);
+        if !is_definition {
+            return None;
+        }
+        let name = self.names.claim(to_pascal_case(name, "Type"));
+        self.declared.push((pointer, name.clone()));
+        Some(name)
+    }
+
+    /// Defines all declared records and enums.
+    pub fn define_declared(&mut self) {
+        for (pointer, name) in self.declared.clone() {
+            if let Some(schema) = pointer_resolve(self.document, &pointer) {
+                self.warn_unsupported(schema, &pointer);
+                if enum_values(schema).is_some() {
+                    self.define_enum(name, schema, &pointer);
+                } else {
+                    self.define_record(name, schema, &pointer);
+                }
+            }
+        }
+    }
+
+    /// Determines type reference of `schema` at `pointer`, defining any
+    /// anonymous records or enums with names derived from `context`.
+    pub fn type_of(&mut self, schema: &'a Data, pointer: &str, context: &str) -> String {
+        if let Some(reference) = get_str(schema, "$ref") {
+            return self.type_of_reference(reference, pointer);
+        }
+        self.warn_unsupported(schema, pointer);
+        let mut types = match schema.get("type") {
+            Some(Data::String(type_)) => vec![type_.as_str()],
+            Some(Data::List(types)) => types.iter()
+                .filter_map(|type_| match type_ {
+                    Data::String(type_) => Some(type_.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut is_nullable = schema.get("nullable") == Some(&Data::Boolean(true));
+        if let Some(index) = types.iter().position(|type_| *type_ == "null") {
+            types.remove(index);
+            is_nullable = true;
+        }
+        let type_ref = self.type_of_non_null(schema, &types, pointer, context);
+        if is_nullable {
+            option(type_ref)
+        } else {
+            type_ref
+        }
+    }
+
+    fn type_of_non_null(&mut self, schema: &'a Data, types: &[&str], pointer: &str, context: &str) -> String {
+        if schema.get("enum").is_some() {
+            if enum_values(schema).is_some() {
+                let name = self.names.claim(context.into());
+                self.define_enum(name.clone(), schema, pointer);
+                return name;
+            }
+            self.warn(pointer_join(pointer, "enum"), "only enums of strings can be mapped, ignoring enum".into());
+        }
+
+        let all_of = get_list(schema, "allOf");
+        if all_of.len() == 1 && schema.get("properties").is_none() {
+            return self.type_of(&all_of[0], &format!("{}/allOf/0", pointer), context);
+        }
+        if !all_of.is_empty() {
+            let name = self.names.claim(context.into());
+            self.define_record(name.clone(), schema, pointer);
+            return name;
+        }
+
+        for key in ["oneOf", "anyOf"].iter() {
+            let alternatives = get_list(schema, key);
+            if alternatives.is_empty() {
+                continue;
+            }
+            let non_null = alternatives.iter()
+                .enumerate()
+                .filter(|(_, alternative)| get_str(alternative, "type") != Some("null"))
+                .collect::<Vec<_>>();
+            let key_pointer = pointer_join(pointer, key);
+            if let [(index, alternative)] = non_null.as_slice() {
+                let type_ref = self.type_of(alternative, &pointer_join(&key_pointer, &index.to_string()), context);
+                return if non_null.len() < alternatives.len() {
+                    option(type_ref)
+                } else {
+                    type_ref
+                };
+            }
+            self.warn(key_pointer, format!(
+                "`{}` with {} alternatives cannot be mapped, using `String`",
+                key, non_null.len(),
+            ));
+            return "String".into();
+        }
+
+        if types.len() > 1 {
+            self.warn(pointer_join(pointer, "type"), format!(
+                "multiple types cannot be mapped, using `{}`",
+                types[0],
+            ));
+        }
+        match types.first().copied() {
+            Some("boolean") => "Boolean".into(),
+            Some("integer") => integer_type(schema).into(),
+            Some("number") => match get_str(schema, "format") {
+                Some("float") => "Float32".into(),
+                _ => "Float64".into(),
+            },
+            Some("string") => "String".into(),
+            Some("array") => self.type_of_array(schema, pointer, context),
+            Some("object") => self.type_of_object(schema, pointer, context),
+            None if schema.get("properties").is_some() || schema.get("additionalProperties").is_some() => {
+                self.type_of_object(schema, pointer, context)
+            }
+            None if schema.get("items").is_some() => self.type_of_array(schema, pointer, context),
+            Some(type_) => {
+                self.warn(pointer_join(pointer, "type"), format!("type `{}` cannot be mapped, using `String`", type_));
+                "String".into()
+            }
+            None => {
+                self.warn(pointer.into(), "schema without type cannot be mapped, using `String`".into());
+                "String".into()
+            }
+        }
+    }
+
+    fn type_of_array(&mut self, schema: &'a Data, pointer: &str, context: &str) -> String {
+        let collection = if schema.get("uniqueItems") == Some(&Data::Boolean(true)) {
+            "Set"
+        } else {
+            "List"
+        };
+        let item = match schema.get("items") {
+            Some(items @ Data::Map(_)) => {
+                self.type_of(items, &pointer_join(pointer, "items"), &format!("{}Item", context))
+            }
+            _ => {
+                self.warn(pointer.into(), format!("array without item schema cannot be mapped, using `{}<String>`", collection));
+                "String".into()
+            }
+        };
+        format!("{}<{}>", collection, item)
+    }
+
+    fn type_of_object(&mut self, schema: &'a Data, pointer: &str, context: &str) -> String {
+        if schema.get("properties").is_some() {
+            let name = self.names.claim(context.into());
+            self.define_record(name.clone(), schema, pointer);
+            return name;
+        }
+        match schema.get("additionalProperties") {
+            Some(value @ Data::Map(entries)) if !entries.is_empty() => {
+                let value = self.type_of(value, &pointer_join(pointer, "additionalProperties"), &format!("{}Value", context));
+                format!("Map<String, {}>", value)
+            }
+            _ => {
+                self.warn(pointer.into(), "free-form object cannot be mapped, using `Map<String, String>`".into());
+                "Map<String, String>".into()
+            }
+        }
+    }
+
+    fn type_of_reference(&mut self, reference: &str, pointer: &str) -> String {
+        let found = self.declared.iter()
+            .chain(self.aliases.iter())
+            .find(|(declared, _)| declared == reference);
+        if let Some((_, type_ref)) = found {
+            return type_ref.clone();
+        }
+        let pointer = pointer_join(pointer, "$ref");
+        if !reference.starts_with('#') {
+            self.warn(pointer, format!("external reference `{}` cannot be mapped, using `String`", reference));
+            return "String".into();
+        }
+        if self.resolving.iter().any(|resolving| resolving == reference) {
+            self.warn(pointer, format!("recursive reference `{}` cannot be mapped, using `String`", reference));
+            return "String".into();
+        }
+        let schema = match pointer_resolve(self.document, reference) {
+            Some(schema) => schema,
+            None => {
+                self.warn(pointer, format!("reference `{}` cannot be resolved, using `String`", reference));
+                return "String".into();
+            }
+        };
+        let context = to_pascal_case(reference.rsplit('/').next().unwrap_or(""), "Type");
+        self.resolving.push(reference.into());
+        let type_ref = self.type_of(schema, reference, &context);
+        self.resolving.pop();
+        self.aliases.push((reference.into(), type_ref.clone()));
+        type_ref
+    }
+
+    fn define_enum(&mut self, name: String, schema: &'a Data, pointer: &str) {
+        let mut names = Names::default();
+        let mut variants = Vec::new();
+        for (index, value) in enum_values(schema).unwrap_or_default().into_iter().enumerate() {
+            let variant = if is_identifier(value) {
+                value.to_string()
+            } else {
+                let variant = to_identifier(value);
+                self.warn(format!("{}/enum/{}", pointer, index), format!(
+                    "value `{}` is not a valid identifier, renamed to `{}`",
+                    value, variant,
+                ));
+                variant
+            };
+            variants.push(names.claim(variant));
+        }
+        self.items.push(Item::Enum(Enum { doc: doc_of(schema), name, variants }));
+    }
+
+    fn define_record(&mut self, name: String, schema: &'a Data, pointer: &str) {
+        // Any anonymous records or enums of the fields are placed after the
+        // record itself.
+        let index = self.items.len();
+        let mut fields = Vec::new();
+        let mut required = Vec::new();
+        self.collect_fields(&name, schema, pointer, &mut fields, &mut required, 0);
+        let fields = fields.into_iter()
+            .map(|(property, mut field): (&str, Field)| {
+                if !required.contains(&property) {
+                    field.type_ref = option(field.type_ref);
+                }
+                field
+            })
+            .collect();
+        self.items.insert(index, Item::Record(Record { doc: doc_of(schema), name, fields }));
+    }
+
+    /// Collects the properties of `schema`, and those of any schemas it is
+    /// composed of using `allOf`, as fields of the record named `name`.
+    fn collect_fields(
+        &mut self,
+        name: &str,
+        schema: &'a Data,
+        pointer: &str,
+        fields: &mut Vec<(&'a str, Field)>,
+        required: &mut Vec<&'a str>,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            self.warn(pointer.into(), "schema composition is too deep to be mapped".into());
+            return;
+        }
+        for (index, member) in get_list(schema, "allOf").iter().enumerate() {
+            let member_pointer = format!("{}/allOf/{}", pointer, index);
+            match get_str(member, "$ref") {
+                Some(reference) => match pointer_resolve(self.document, reference) {
+                    Some(member) => self.collect_fields(name, member, reference, fields, required, depth + 1),
+                    None => self.warn(pointer_join(&member_pointer, "$ref"), format!(
+                        "reference `{}` cannot be resolved, ignoring its properties",
+                        reference,
+                    )),
+                },
+                None => self.collect_fields(name, member, &member_pointer, fields, required, depth + 1),
+            }
+        }
+        for property in get_list(schema, "required") {
+            if let Data::String(property) = property {
+                required.push(property);
+            }
+        }
+        let properties_pointer = pointer_join(pointer, "properties");
+        for (property, property_schema) in get_map(schema, "properties") {
+            if fields.iter().any(|(other, _)| other == property) {
+                continue;
+            }
+            let property_pointer = pointer_join(&properties_pointer, property);
+            let field_name = if is_identifier(property) {
+                property.clone()
+            } else {
+                let field_name = to_identifier(property);
+                self.warn(property_pointer.clone(), format!(
+                    "property `{}` is not a valid identifier, renamed to `{}`",
+                    property, field_name,
+                ));
+                field_name
+            };
+            let context = format!("{}{}", name, to_pascal_case(property, "Field"));
+            let type_ref = self.type_of(property_schema, &property_pointer, &context);
+            fields.push((property, Field { doc: doc_of(property_schema), name: field_name, type_ref }));
+        }
+        match schema.get("additionalProperties") {
+            None | Some(Data::Boolean(false)) => {}
+            Some(_) => self.warn(pointer_join(pointer, "additionalProperties"), format!(
+                "additional properties of record `{}` cannot be mapped",
+                name,
+            )),
+        }
+    }
+
+    fn warn_unsupported(&mut self, schema: &Data, pointer: &str) {
+        for keyword in UNSUPPORTED {
+            if schema.get(keyword).is_some() {
+                self.warn(pointer_join(pointer, keyword), format!("`{}` cannot be mapped, ignoring it", keyword));
+            }
+        }
+    }
+
+    /// Records that the construct at `pointer` could not be mapped.
+    pub fn warn(&mut self, pointer: String, message: String) {
+        self.warnings.push(Warning { pointer, message });
+    }
+}
+
+/// Whether or not `schema` describes a record or enum.
+fn is_definition(schema: &Data) -> bool {
+    schema.get("$ref").is_none() && (enum_values(schema).is_some()
+        || schema.get("properties").is_some()
+        || get_list(schema, "allOf").len() > 1)
+}
+
+/// Gets the string values of the `enum` of `schema`, if it has one and only
+/// strings and nulls are enumerated.
+fn enum_values(schema: &Data) -> Option<Vec<&str>> {
+    let mut values = Vec::new();
+    for value in get_list(schema, "enum") {
+        match value {
+            Data::String(value) => values.push(value.as_str()),
+            Data::Null => {}
+            _ => return None,
+        }
+    }
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// Gets the description of `schema`, or its title if it has no description.
+pub fn doc_of(schema: &Data) -> Option<String> {
+    get_str(schema, "description")
+        .or_else(|| get_str(schema, "title"))
+        .map(str::trim)
+        .filter(|doc| !doc.is_empty())
+        .map(Into::into)
+}
+
+/// Selects the smallest integer primitive that can hold any value allowed by
+/// `schema`.
+fn integer_type(schema: &Data) -> &'static str {
+    match get_str(schema, "format") {
+        Some("int8") => return "I8",
+        Some("int16") => return "I16",
+        Some("int32") => return "I32",
+        Some("int64") => return "I64",
+        Some("uint8") => return "U8",
+        Some("uint16") => return "U16",
+        Some("uint32") => return "U32",
+        Some("uint64") => return "U64",
+        _ => {}
+    }
+    let bound = |key| match schema.get(key) {
+        Some(Data::Integer(integer)) => Some(*integer),
+        Some(Data::Float(float)) if float.is_finite() => Some(*float as i128),
+        _ => None,
+    };
+    match (bound("minimum"), bound("maximum")) {
+        (Some(min), Some(max)) if min >= 0 => [("U8", 8), ("U16", 16), ("U32", 32)].iter()
+            .find(|(_, bits)| max < 1 << bits)
+            .map_or("U64", |(name, _)| name),
+        (Some(min), Some(max)) => [("I8", 7), ("I16", 15), ("I32", 31)].iter()
+            .find(|(_, bits)| min >= -(1 << bits) && max < 1 << bits)
+            .map_or("I64", |(name, _)| name),
+        (Some(min), None) if min >= 0 => "U64",
+        _ => "I64",
+    }
+}
+
+/// Wraps `type_ref` in an `Option`, unless it already is one.
+fn option(type_ref: String) -> String {
+    if type_ref.starts_with("Option<") {
+        type_ref
+    } else {
+        format!("Option<{}>", type_ref)
+    }
+}
@@ -0,0 +1,709 @@
+//! YAML decoding.
+//!
+//! Only the subset of YAML 1.2 commonly used to write API descriptions is
+//! supported. Block and flow collections, plain, quoted and block scalars,
+//! as well as comments, are accepted, while anchors, aliases, tags, complex
+//! keys and streams of multiple documents are rejected. Mapping keys are
+//! always decoded as strings.
+
+use arspec::payload::Data;
+use crate::Error;
+
+/// Decodes YAML `input` into [`Data`][dat].
+///
+/// [dat]: ../../arspec/payload/enum.Data.html
+pub fn decode(input: &str) -> Result<Data, Error> {
+    let mut decoder = Decoder::new(input.trim_start_matches('\u{feff}'))?;
+    let data = decoder.node(-1)?;
+    match decoder.peek() {
+        Some(line) => Err(error(line.number, "unexpected content")),
+        None => Ok(data),
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Line text without any comment or trailing whitespace.
+    fn content(&self) -> &'a str {
+        strip_comment(self.text)
+    }
+}
+
+struct Decoder<'a> {
+    lines: Vec<Line<'a>>,
+    index: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(input: &'a str) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        let mut has_marker = false;
+        for (index, raw) in input.lines().enumerate() {
+            let number = index + 1;
+            let text = raw.trim_start_matches(' ');
+            let indent = raw.len() - text.len();
+            if indent == 0 {
+                if text.starts_with('%') && lines.is_empty() {
+                    continue;
+                }
+                let marker = strip_comment(text);
+                if marker == "---" {
+                    if has_marker || lines.iter().any(|line: &Line| !line.content().is_empty()) {
+                        return Err(error(number, "multiple documents are not supported"));
+                    }
+                    has_marker = true;
+                    continue;
+                }
+                if marker == "..." {
+                    break;
+                }
+            }
+            if text.starts_with('\t') && !text.trim().is_empty() {
+                return Err(error(number, "tabs cannot be used for indentation"));
+            }
+            lines.push(Line { number, indent, text });
+        }
+        Ok(Decoder { lines, index: 0 })
+    }
+
+    /// Skips any blank lines and returns the line that follows, if any.
+    fn peek(&mut self) -> Option<Line<'a>> {
+        while let Some(line) = self.lines.get(self.index) {
+            if !line.content().is_empty() {
+                return Some(*line);
+            }
+            self.index += 1;
+        }
+        None
+    }
+
+    /// Decodes node more indented than `parent`, or returns `Data::Null` if
+    /// there is no such node.
+    fn node(&mut self, parent: isize) -> Result<Data, Error> {
+        let line = match self.peek() {
+            Some(line) if line.indent as isize > parent => line,
+            _ => return Ok(Data::Null),
+        };
+        let content = line.content();
+        if is_entry(content) {
+            self.sequence(line.indent)
+        } else if split_key(line.number, content)?.is_some() {
+            self.map(line.indent)
+        } else {
+            self.scalar(parent)
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Data, Error> {
+        let mut items = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent || !is_entry(line.content()) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(error(line.number, "unexpected indentation"));
+            }
+            let rest = &line.text[1..];
+            let item = rest.trim_start_matches(' ');
+            if strip_comment(item).is_empty() {
+                self.index += 1;
+            } else {
+                // The item is treated as if it was on a line of its own,
+                // indented to the column at which it starts.
+                self.lines[self.index] = Line {
+                    number: line.number,
+                    indent: indent + 1 + rest.len() - item.len(),
+                    text: item,
+                };
+            }
+            items.push(self.node(indent as isize)?);
+        }
+        Ok(Data::List(items))
+    }
+
+    fn map(&mut self, indent: usize) -> Result<Data, Error> {
+        let mut entries: Vec<(String, Data)> = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent || is_entry(line.content()) {
+                return Err(error(line.number, "unexpected indentation"));
+            }
+            let (key, rest) = split_key(line.number, line.content())?
+                .ok_or_else(|| error(line.number, "expected mapping key"))?;
+            if entries.iter().any(|(other, _)| *other == key) {
+                return Err(error(line.number, "duplicate mapping key"));
+            }
+            let value = self.value(line, indent, rest)?;
+            entries.push((key, value));
+        }
+        Ok(Data::Map(entries))
+    }
+
+    /// Decodes mapping value `rest`, found on `line` after a key indented by
+    /// `indent`.
+    fn value(&mut self, line: Line<'a>, indent: usize, rest: &'a str) -> Result<Data, Error> {
+        if rest.is_empty() {
+            self.index += 1;
+            // Sequences may be indented as much as the key they belong to.
+            if let Some(next) = self.peek() {
+                if next.indent == indent && is_entry(next.content()) {
+                    return self.sequence(indent);
+                }
+            }
+            return self.node(indent as isize);
+        }
+        if rest.starts_with('|') || rest.starts_with('>') {
+            self.index += 1;
+            return self.block_scalar(line.number, indent as isize, rest);
+        }
+        self.lines[self.index] = Line { text: rest, ..line };
+        self.scalar(indent as isize)
+    }
+
+    /// Decodes flow collection or scalar, which may continue on any
+    /// subsequent lines more indented than `parent`.
+    fn scalar(&mut self, parent: isize) -> Result<Data, Error> {
+        let first = self.lines[self.index];
+        self.index += 1;
+
+        let content = first.content();
+        let mut text = content.to_string();
+        let is_complete: fn(&str) -> bool = match content.chars().next() {
+            Some('[') | Some('{') => |text| flow_depth(text) == 0,
+            Some('"') => |text| quoted_len(text, '"').is_some(),
+            Some('\'') => |text| quoted_len(text, '\'').is_some(),
+            Some('&') | Some('*') | Some('!') => {
+                return Err(error(first.number, "anchors, aliases and tags are not supported"));
+            }
+            Some('?') if content.len() == 1 || content[1..].starts_with(' ') => {
+                return Err(error(first.number, "complex mapping keys are not supported"));
+            }
+            Some('@') | Some('`') => {
+                return Err(error(first.number, "reserved indicator"));
+            }
+            _ => |_| false,
+        };
+        while !is_complete(&text) {
+            let line = match self.lines.get(self.index) {
+                Some(line) if line.content().is_empty() => {
+                    self.index += 1;
+                    if line.text.trim().is_empty() {
+                        text.push('\n');
+                    }
+                    continue;
+                }
+                Some(line) if line.indent as isize > parent => *line,
+                _ => break,
+            };
+            let is_plain = !content.starts_with(|ch| "[{\"'".contains(ch));
+            if is_plain && (is_entry(line.content()) || split_key(line.number, line.content())?.is_some()) {
+                return Err(error(line.number, "unexpected indentation"));
+            }
+            self.index += 1;
+            if !text.ends_with('\n') {
+                text.push(' ');
+            }
+            text.push_str(if is_plain || content.starts_with('[') || content.starts_with('{') {
+                line.content()
+            } else {
+                line.text.trim_end()
+            });
+        }
+        let text = text.trim_end_matches('\n');
+
+        match text.chars().next() {
+            Some('[') | Some('{') => {
+                let mut flow = Flow { number: first.number, text, offset: 0 };
+                let data = flow.value()?;
+                flow.skip_whitespace();
+                if flow.offset < text.len() {
+                    return Err(error(first.number, "unexpected characters after flow collection"));
+                }
+                Ok(data)
+            }
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let len = quoted_len(text, quote)
+                    .ok_or_else(|| error(first.number, "unterminated quoted scalar"))?;
+                if len < text.len() {
+                    return Err(error(first.number, "unexpected characters after quoted scalar"));
+                }
+                unquote(first.number, &text[..len]).map(Data::String)
+            }
+            _ => Ok(resolve(text)),
+        }
+    }
+
+    /// Decodes literal or folded block scalar with given `header`, such as
+    /// `|` or `>-`, the content of which must be more indented than `parent`.
+    fn block_scalar(&mut self, number: usize, parent: isize, header: &str) -> Result<Data, Error> {
+        let mut chars = header.chars();
+        let is_literal = chars.next() == Some('|');
+        let mut chomping = ' ';
+        let mut explicit = None;
+        for ch in chars {
+            match ch {
+                '-' | '+' if chomping == ' ' => chomping = ch,
+                '1'..='9' if explicit.is_none() => explicit = ch.to_digit(10),
+                _ => return Err(error(number, "invalid block scalar header")),
+            }
+        }
+
+        let mut indent = explicit.map(|explicit| (parent.max(0) as usize) + explicit as usize);
+        let mut lines = Vec::new();
+        while let Some(line) = self.lines.get(self.index) {
+            if line.text.trim().is_empty() {
+                lines.push(String::new());
+            } else if line.indent as isize > parent {
+                let indent = *indent.get_or_insert(line.indent);
+                if line.indent < indent {
+                    break;
+                }
+                let mut text = " ".repeat(line.indent - indent);
+                text.push_str(line.text.trim_end());
+                lines.push(text);
+            } else {
+                break;
+            }
+            self.index += 1;
+        }
+
+        let trailing = lines.iter().rev().take_while(|line| line.is_empty()).count();
+        lines.truncate(lines.len() - trailing);
+        let mut text = if is_literal {
+            lines.join("\n")
+        } else {
+            let mut text = String::new();
+            for (index, line) in lines.iter().enumerate() {
+                if index > 0 {
+                    let previous = &lines[index - 1];
+                    if line.is_empty() || previous.starts_with(' ') || line.starts_with(' ') {
+                        text.push('\n');
+                    } else if !previous.is_empty() {
+                        text.push(' ');
+                    }
+                }
+                text.push_str(line);
+            }
+            text
+        };
+        if !lines.is_empty() {
+            match chomping {
+                '-' => {}
+                '+' => text.push_str(&"\n".repeat(trailing + 1)),
+                _ => text.push('\n'),
+            }
+        }
+        Ok(Data::String(text))
+    }
+}
+
+/// A flow collection, such as `[1, 2]` or `{a: b}`.
+struct Flow<'a> {
+    number: usize,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Flow<'a> {
+    fn value(&mut self) -> Result<Data, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('[') => {
+                self.offset += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(']') {
+                        self.offset += 1;
+                        break;
+                    }
+                    items.push(self.value()?);
+                    if !self.separator(']')? {
+                        break;
+                    }
+                }
+                Ok(Data::List(items))
+            }
+            Some('{') => {
+                self.offset += 1;
+                let mut entries = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some('}') {
+                        self.offset += 1;
+                        break;
+                    }
+                    let key = match self.scalar(true)? {
+                        Data::String(key) => key,
+                        _ => unreachable!(),
+                    };
+                    self.skip_whitespace();
+                    let value = if self.peek() == Some(':') {
+                        self.offset += 1;
+                        self.value()?
+                    } else {
+                        Data::Null
+                    };
+                    entries.push((key, value));
+                    if !self.separator('}')? {
+                        break;
+                    }
+                }
+                Ok(Data::Map(entries))
+            }
+            Some('&') | Some('*') | Some('!') => {
+                Err(error(self.number, "anchors, aliases and tags are not supported"))
+            }
+            _ => self.scalar(false),
+        }
+    }
+
+    /// Consumes `,` or `close`, returning whether the former was found.
+    fn separator(&mut self, close: char) -> Result<bool, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(',') => {
+                self.offset += 1;
+                Ok(true)
+            }
+            Some(ch) if ch == close => {
+                self.offset += 1;
+                Ok(false)
+            }
+            Some(_) => Err(error(self.number, "expected `,` in flow collection")),
+            None => Err(error(self.number, "unterminated flow collection")),
+        }
+    }
+
+    /// Decodes quoted or plain scalar, which is returned as a string if
+    /// `is_key` is true.
+    fn scalar(&mut self, is_key: bool) -> Result<Data, Error> {
+        self.skip_whitespace();
+        let rest = &self.text[self.offset..];
+        if let Some(quote @ '"') | Some(quote @ '\'') = rest.chars().next() {
+            let len = quoted_len(rest, quote)
+                .ok_or_else(|| error(self.number, "unterminated quoted scalar"))?;
+            self.offset += len;
+            return unquote(self.number, &rest[..len]).map(Data::String);
+        }
+        let mut len = rest.len();
+        for (index, ch) in rest.char_indices() {
+            let is_end = match ch {
+                ',' | '[' | ']' | '{' | '}' => true,
+                ':' => rest[index + 1..].chars().next()
+                    .is_none_or(|next| next == ' ' || ",[]{}".contains(next)),
+                _ => false,
+            };
+            if is_end {
+                len = index;
+                break;
+            }
+        }
+        self.offset += len;
+        let text = rest[..len].trim();
+        if is_key {
+            Ok(Data::String(text.into()))
+        } else {
+            Ok(resolve(text))
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+}
+
+fn error(line: usize, message: &'static str) -> Error {
+    Error::YamlInvalid { line, message }
+}
+
+/// Whether or not `content` is a sequence entry, such as `- a`.
+fn is_entry(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Splits `content` into mapping key and value, if it contains a key.
+fn split_key(number: usize, content: &str) -> Result<Option<(String, &str)>, Error> {
+    let (key, rest) = match content.chars().next() {
+        Some(quote @ '"') | Some(quote @ '\'') => match quoted_len(content, quote) {
+            Some(len) => {
+                let rest = content[len..].trim_start();
+                if !is_key_end(rest) {
+                    return Ok(None);
+                }
+                (unquote(number, &content[..len])?, rest)
+            }
+            None => return Ok(None),
+        },
+        Some('[') | Some('{') | None => return Ok(None),
+        _ => {
+            let index = match content.char_indices()
+                .find(|&(index, ch)| ch == ':' && is_key_end(&content[index..]))
+            {
+                Some((index, _)) => index,
+                None => return Ok(None),
+            };
+            (content[..index].trim_end().to_string(), &content[index..])
+        }
+    };
+    Ok(Some((key, rest[1..].trim_start())))
+}
+
+fn is_key_end(rest: &str) -> bool {
+    rest == ":" || rest.starts_with(": ")
+}
+
+/// Removes any comment and trailing whitespace from `text`.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, ch) in text.char_indices() {
+        match quote {
+            Some('"') if ch == '\\' && previous == '\\' => {
+                previous = ' ';
+                continue;
+            }
+            Some('"') if ch == '"' && previous != '\\' => quote = None,
+            Some('\'') if ch == '\'' => quote = None,
+            Some(_) => {}
+            None => match ch {
+                '"' | '\'' if previous == ' ' || "[{,:".contains(previous) => quote = Some(ch),
+                '#' if previous == ' ' || previous == '\t' => return text[..index].trim_end(),
+                _ => {}
+            },
+        }
+        previous = ch;
+    }
+    text.trim_end()
+}
+
+/// Calculates bracket depth at the end of flow collection `text`.
+fn flow_depth(text: &str) -> isize {
+    let mut depth = 0;
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        match ch {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            '"' | '\'' => {
+                match quoted_len(rest, ch) {
+                    Some(len) => rest = &rest[len..],
+                    None => return depth.max(1),
+                }
+                continue;
+            }
+            _ => {}
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+    depth
+}
+
+/// Determines byte length of the quoted scalar at the beginning of `text`,
+/// including its quotes, if terminated.
+fn quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, ch)) = chars.next() {
+        if ch == '\\' && quote == '"' {
+            chars.next();
+        } else if ch == quote {
+            if quote == '\'' && text[index + 1..].starts_with('\'') {
+                chars.next();
+                continue;
+            }
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+/// Removes quotes from and resolves any escape sequences in `quoted`.
+fn unquote(number: usize, quoted: &str) -> Result<String, Error> {
+    let quote = quoted.chars().next().unwrap_or('"');
+    let input = &quoted[1..quoted.len() - 1];
+    if quote == '\'' {
+        return Ok(input.replace("''", "'"));
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        let len = match chars.next() {
+            Some('x') => 2,
+            Some('u') => 4,
+            Some('U') => 8,
+            Some(ch) => {
+                output.push(match ch {
+                    '0' => '\0',
+                    'a' => '\u{7}',
+                    'b' => '\u{8}',
+                    't' | '\t' => '\t',
+                    'n' => '\n',
+                    'v' => '\u{b}',
+                    'f' => '\u{c}',
+                    'r' => '\r',
+                    'e' => '\u{1b}',
+                    ' ' | '"' | '/' | '\\' => ch,
+                    'N' => '\u{85}',
+                    '_' => '\u{a0}',
+                    'L' => '\u{2028}',
+                    'P' => '\u{2029}',
+                    _ => return Err(error(number, "invalid escape sequence")),
+                });
+                continue;
+            }
+            None => return Err(error(number, "invalid escape sequence")),
+        };
+        let code = chars.by_ref().take(len).collect::<String>();
+        let ch = u32::from_str_radix(&code, 16).ok()
+            .filter(|_| code.len() == len)
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| error(number, "invalid escape sequence"))?;
+        output.push(ch);
+    }
+    Ok(output)
+}
+
+/// Determines the data type of plain scalar `text`.
+fn resolve(text: &str) -> Data {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Data::Null,
+        "true" | "True" | "TRUE" => return Data::Boolean(true),
+        "false" | "False" | "FALSE" => return Data::Boolean(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => return Data::Float(f64::INFINITY),
+        "-.inf" | "-.Inf" | "-.INF" => return Data::Float(f64::NEG_INFINITY),
+        ".nan" | ".NaN" | ".NAN" => return Data::Float(f64::NAN),
+        _ => {}
+    }
+    let digits = text.trim_start_matches(['-', '+']);
+    if text.len() - digits.len() <= 1 {
+        if let Some(hex) = text.strip_prefix("0x") {
+            if let Ok(integer) = i128::from_str_radix(hex, 16) {
+                return Data::Integer(integer);
+            }
+        }
+        if let Some(octal) = text.strip_prefix("0o") {
+            if let Ok(integer) = i128::from_str_radix(octal, 8) {
+                return Data::Integer(integer);
+            }
+        }
+        if !digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_digit()) {
+            if let Ok(integer) = text.trim_start_matches('+').parse() {
+                return Data::Integer(integer);
+            }
+        }
+        let is_numeric = digits.chars().any(|ch| ch.is_ascii_digit())
+            && digits.chars().all(|ch| ch.is_ascii_digit() || ".eE+-".contains(ch));
+        if is_numeric {
+            if let Ok(float) = text.parse() {
+                return Data::Float(float);
+            }
+        }
+    }
+    Data::String(text.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_document() {
+        let data = decode(concat!(
+            "%YAML 1.2\n",
+            "---\n",
+            "# A comment.\n",
+            "openapi: 3.0.3\n",
+            "info:\n",
+            "  title: 'Pet''s Store' # Another comment.\n",
+            "  version: \"1.0\"\n",
+            "  description: |\n",
+            "    First line.\n",
+            "      Indented line.\n",
+            "\n",
+            "  summary: >-\n",
+            "    Folded\n",
+            "    text.\n",
+            "tags:\n",
+            "- name: pets\n",
+            "  x-count: 12\n",
+            "- [a, \"b\", {c: 1.5}]\n",
+            "- - nested\n",
+            "  - ~\n",
+            "paths:\n",
+            "  /pets/{id}:\n",
+            "    get:\n",
+            "      operationId: getPet\n",
+            "      deprecated: false\n",
+            "      plain: this is\n",
+            "        continued\n",
+            "...\n",
+        )).unwrap();
+        assert_eq!(data, Data::Map(vec![
+            ("openapi".into(), Data::String("3.0.3".into())),
+            ("info".into(), Data::Map(vec![
+                ("title".into(), Data::String("Pet's Store".into())),
+                ("version".into(), Data::String("1.0".into())),
+                ("description".into(), Data::String("First line.\n  Indented line.\n".into())),
+                ("summary".into(), Data::String("Folded text.".into())),
+            ])),
+            ("tags".into(), Data::List(vec![
+                Data::Map(vec![
+                    ("name".into(), Data::String("pets".into())),
+                    ("x-count".into(), Data::Integer(12)),
+                ]),
+                Data::List(vec![
+                    Data::String("a".into()),
+                    Data::String("b".into()),
+                    Data::Map(vec![("c".into(), Data::Float(1.5))]),
+                ]),
+                Data::List(vec![
+                    Data::String("nested".into()),
+                    Data::Null,
+                ]),
+            ])),
+            ("paths".into(), Data::Map(vec![
+                ("/pets/{id}".into(), Data::Map(vec![
+                    ("get".into(), Data::Map(vec![
+                        ("operationId".into(), Data::String("getPet".into())),
+                        ("deprecated".into(), Data::Boolean(false)),
+                        ("plain".into(), Data::String("this is continued".into())),
+                    ])),
+                ])),
+            ])),
+        ]));
+    }
+
+    #[test]
+    fn reject_unsupported() {
+        for (input, line) in [
+            ("a: &anchor 1\n", 1),
+            ("a: 1\nb: *anchor\n", 2),
+            ("a: 1\n---\nb: 2\n", 2),
+            ("a:\n\t- 1\n", 2),
+            ("a: 1\na: 2\n", 2),
+            ("a:\n  b: 1\n   c: 2\n", 3),
+        ].iter() {
+            match decode(input) {
+                Err(Error::YamlInvalid { line: actual, .. }) => assert_eq!(actual, *line, "{}", input),
+                result => panic!("{:?} decoded into {:?}", input, result),
+            }
+        }
+    }
+}
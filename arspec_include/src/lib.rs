@@ -0,0 +1,184 @@
+//! Procedural macros for including Arrowhead specifications into Rust
+//! crates.
+//!
+//! These macros live in their own crate, rather than in `arspec_macro`, as
+//! they depend on the `arspec` crate, which itself depends on
+//! `arspec_macro`.
+
+use arspec::project::{self, Project};
+use arspec::spec::{self, parser};
+use arspec_parser::{Corpus, Text};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::env;
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Parses and verifies the specification file at given path, relative to
+/// the root folder of the invoking crate, and expands into the Rust types
+/// and traits described by the [`arspec_gen::rust::items`][items] module.
+///
+/// If the file belongs to a specification project, the other source files
+/// and dependencies of that project are loaded with it, as are any files it
+/// imports. Only the definitions of the file, and the types they refer to,
+/// are expanded.
+///
+/// Any parse or verification errors, including references to types that
+/// are not defined, are reported as compile errors. The invoking crate is
+/// rebuilt whenever any loaded file changes. As the expansion
+/// brings `Deserialize`, `Serialize`, `BTreeMap` and `BTreeSet` into scope,
+/// the macro is best invoked in a module of its own.
+///
+/// ```ignore
+/// mod orchestrator {
+///     arspec_include::include_spec!("core/Orchestrator.ahfs");
+/// }
+/// ```
+///
+/// [items]: ../arspec_gen/rust/items/index.html
+#[proc_macro]
+pub fn include_spec(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err((span, message)) => compile_error(span, &message),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, (Span, String)> {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal,
+        (Some(token), _) => return Err((token.span(), "expected specification path string literal".into())),
+        (None, _) => return Err((Span::call_site(), "expected specification path string literal".into())),
+    };
+    let span = literal.span();
+    let name = unquote(&literal.to_string())
+        .ok_or_else(|| (span, "expected specification path string literal".into()))?;
+
+    let root = env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| (span, "`CARGO_MANIFEST_DIR` not set".into()))?;
+    let path = root.join(&name);
+    let corpus = read_corpus(&path)
+        .map_err(|error| (span, format!("failed to read `{}`: {}", name, strip_colors(&error.to_string()))))?;
+
+    let spec = parser::parse(&corpus)
+        .map_err(|error| (span, format!("failed to parse `{}`: {}", name, strip_colors(&error.to_string()))))?;
+    spec.verify()
+        .map_err(|error| (span, format!("failed to verify `{}`: {}", name, strip_colors(&error.to_string()))))?;
+
+    // Including the files makes the compiler rebuild the crate whenever any
+    // of them changes.
+    let mut output = String::new();
+    for text in &corpus.texts {
+        let _ = writeln!(output, "const _: &str = include_str!({:?});", &*text.name);
+    }
+    let normalized = spec::normalize(&path);
+    output.push_str(&arspec_gen::rust::items::generate_from(&spec, |name| {
+        spec::normalize(Path::new(&*name.source.name)) == normalized
+    }));
+    output.parse()
+        .map_err(|error| (span, format!("failed to expand `{}`: {:?}", name, error)))
+}
+
+/// Reads the specification file at `path`, the other source files of any
+/// project containing it, and all files imported by any of them, into a new
+/// `Corpus`.
+fn read_corpus(path: &Path) -> arspec::Result<Corpus> {
+    let mut corpus = match Project::locate(path.parent().unwrap_or(path)) {
+        Ok(project) => project.corpus()?,
+        Err(error) if error.as_io_error().is_some_and(|error| error.kind() == io::ErrorKind::NotFound) => {
+            Corpus::default()
+        }
+        Err(error) => return Err(error),
+    };
+    let normalized = spec::normalize(path);
+    let is_loaded = corpus.texts.iter()
+        .any(|text| spec::normalize(Path::new(&*text.name)) == normalized);
+    if !is_loaded {
+        corpus.texts.push(Text::read_at(path)?);
+        project::read_imports(&mut corpus)?;
+    }
+    Ok(corpus)
+}
+
+/// Creates `compile_error!` invocation reporting `message` at `span`.
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut arguments = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    arguments.set_span(span);
+    let mut semicolon = Punct::new(';', Spacing::Alone);
+    semicolon.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(arguments),
+        TokenTree::Punct(semicolon),
+    ].into_iter().collect()
+}
+
+/// Gets contents of Rust string `literal`, unless not a string literal or
+/// containing unsupported escape sequences.
+fn unquote(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = raw.get(hashes..raw.len() - hashes)?;
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.into());
+    }
+    let quoted = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut output = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        output.push(match chars.next()? {
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '0' => '\0',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            _ => return None,
+        });
+    }
+    Some(output)
+}
+
+/// Removes any VT100 color codes from `text`.
+fn strip_colors(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            chars.by_ref().find(|ch| ch.is_ascii_alphabetic());
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unquote_literals() {
+        assert_eq!(unquote(r#""core/Orchestrator.ahfs""#).as_deref(), Some("core/Orchestrator.ahfs"));
+        assert_eq!(unquote(r#""a\\b\"c""#).as_deref(), Some("a\\b\"c"));
+        assert_eq!(unquote(r###"r#"a"b"#"###).as_deref(), Some("a\"b"));
+        assert_eq!(unquote(r#""\u{41}""#), None);
+        assert_eq!(unquote("42"), None);
+    }
+
+    #[test]
+    fn strip_color_codes() {
+        assert_eq!(strip_colors("\x1b[34mtest.ahfs\x1b[0m: 1"), "test.ahfs: 1");
+    }
+}
@@ -0,0 +1,118 @@
+//! Expands `include_spec!` by compiling crates invoking it.
+//!
+//! As procedural macros cannot be invoked by the crate defining them, each
+//! test compiles a library crate with `rustc`, linking it to the built macro
+//! crate. The `serde` derive macros are replaced by macros expanding to
+//! nothing, as no `serde` crate is available to tests.
+
+use std::env;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Type checks library crate `source`, in which specification paths are
+/// relative to the workspace root, returning the compiler output if it does
+/// not compile.
+fn compile(name: &str, source: &str, specs: &[(&str, &str)]) -> Result<(), String> {
+    let folder = env::temp_dir().join(format!("arspec-include-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("serde.rs"), concat!(
+        "extern crate proc_macro;\n",
+        "use proc_macro::TokenStream;\n",
+        "#[proc_macro_derive(Deserialize, attributes(serde))]\n",
+        "pub fn deserialize(_: TokenStream) -> TokenStream { TokenStream::new() }\n",
+        "#[proc_macro_derive(Serialize, attributes(serde))]\n",
+        "pub fn serialize(_: TokenStream) -> TokenStream { TokenStream::new() }\n",
+    )).unwrap();
+    fs::write(folder.join("lib.rs"), source).unwrap();
+    for (path, body) in specs {
+        fs::write(folder.join(path), body).unwrap();
+    }
+
+    let rustc = |root: &Path, args: &[&str]| {
+        Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".into()))
+            .args(["--edition", "2018", "--out-dir"])
+            .arg(&folder)
+            .args(args)
+            .current_dir(&folder)
+            .env("CARGO_MANIFEST_DIR", root)
+            .output()
+            .unwrap()
+    };
+    let output = rustc(&folder, &["--crate-type", "proc-macro", "--crate-name", "serde", "serde.rs"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let root = if specs.is_empty() { workspace_root() } else { folder.clone() };
+    let include_spec = format!("arspec_include={}", macro_crate().display());
+    let output = rustc(&root, &[
+        "--crate-type", "lib", "--emit", "metadata",
+        "--extern", "serde", "--extern", &include_spec,
+        "-L", folder.to_str().unwrap(),
+        "lib.rs",
+    ]);
+    fs::remove_dir_all(&folder).unwrap();
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into())
+    }
+}
+
+/// Locates most recently built `arspec_include` macro crate.
+fn macro_crate() -> PathBuf {
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let prefix = format!("{}arspec_include-", DLL_PREFIX);
+    deps.read_dir().unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(DLL_SUFFIX)
+        })
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("no built macro crate")
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().into()
+}
+
+#[test]
+fn include_core_spec() {
+    // Any field referring to an unresolved type would become a
+    // `serde_json::Value`, which does not compile without that crate.
+    compile("core", concat!(
+        "mod orchestrator {\n",
+        "    arspec_include::include_spec!(\"core/Orchestrator.ahfs\");\n",
+        "}\n",
+        "pub fn provider_name(response: orchestrator::OrchestrationResponse) -> Option<String> {\n",
+        "    let system: orchestrator::ArrowheadSystem = response.response.into_iter().next()?.provider;\n",
+        "    Some(system.system_name)\n",
+        "}\n",
+    ), &[]).unwrap();
+}
+
+#[test]
+fn include_spec_with_imports() {
+    compile("imports", concat!(
+        "mod a {\n",
+        "    arspec_include::include_spec!(\"a.ahfs\");\n",
+        "}\n",
+        "pub fn value(a: a::A) -> u8 { a.b.value }\n",
+    ), &[
+        ("a.ahfs", "import \"b.ahfs\";\nrecord A { B: B }\n"),
+        ("b.ahfs", "record B { Value: U8 }\n"),
+    ]).unwrap();
+}
+
+#[test]
+fn report_unknown_types() {
+    let error = compile("unknown", concat!(
+        "mod a {\n",
+        "    arspec_include::include_spec!(\"a.ahfs\");\n",
+        "}\n",
+    ), &[
+        ("a.ahfs", "record A { B: B }\n"),
+    ]).unwrap_err();
+    assert!(error.contains("failed to verify `a.ahfs`: Cannot refer to non-existing type."), "{}", error);
+}